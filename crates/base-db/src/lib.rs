@@ -0,0 +1,44 @@
+//! base_db defines basic database traits. The concrete DB is defined by ide.
+
+use std::sync::Arc;
+
+use syntax::{ast, Edition, LineEndings, Parse, ParseConfig};
+
+pub use salsa;
+pub use vfs::{FileId, FileSet, VfsPath};
+
+/// Database which stores all significant input facts: the text of the files,
+/// their paths and how to parse them. Everything else is derived from them.
+#[salsa::query_group(SourceDatabaseStorage)]
+pub trait SourceDatabase: std::fmt::Debug {
+    /// Text of the file.
+    #[salsa::input]
+    fn file_text(&self, file_id: FileId) -> Arc<str>;
+
+    /// The paths of all the files, used to resolve the paths of includes.
+    #[salsa::input]
+    fn file_set(&self) -> Arc<FileSet>;
+
+    /// The edition of the files which don't select one with an
+    /// `// edition: N` comment.
+    #[salsa::input]
+    fn default_edition(&self) -> Edition;
+
+    /// The line endings accepted in the files.
+    #[salsa::input]
+    fn line_endings(&self) -> LineEndings;
+
+    /// Parses the file into the syntax tree.
+    fn parse(&self, file_id: FileId) -> Parse<ast::Grammar>;
+}
+
+fn parse(db: &dyn SourceDatabase, file_id: FileId) -> Parse<ast::Grammar> {
+    let _p = tracing::span!(tracing::Level::INFO, "parse", ?file_id).entered();
+    let text = db.file_text(file_id);
+    let config = ParseConfig {
+        default_edition: db.default_edition(),
+        line_endings: db.line_endings(),
+        ..Default::default()
+    };
+    ast::Grammar::parse_with_config(&text, config)
+}
@@ -0,0 +1,168 @@
+//! Defines the queries of the semantic model.
+//!
+//! Lowering a whole file is cheap, but analyses like field inference are not,
+//! and every keystroke changes the file. So besides the resolved model of the
+//! file, each node definition is also lowered on its own, from its syntax
+//! detached from the file: an edit in one node, or inserting text before it,
+//! leaves the other definitions equal to the previous ones, and nothing which
+//! only depends on them is recomputed.
+//!
+//! Nodes may also be defined in other files, made visible with an
+//! `include 'path'` item. References are resolved within a file first, and
+//! the definitions of the included files are only consulted for the nodes
+//! which the file leaves undefined.
+
+use std::sync::Arc;
+
+use crate::{fields, Grammar, GrammarSourceMap};
+use base_db::{salsa, FileId, SourceDatabase};
+use rustc_hash::FxHashSet;
+use syntax::{ast, AstNode, GreenNode, SyntaxNode};
+
+#[salsa::query_group(HirDatabaseStorage)]
+pub trait HirDatabase: SourceDatabase {
+    /// Returns the names of the nodes defined in the file.
+    fn node_index(&self, file_id: FileId) -> Arc<NodeIndex>;
+
+    /// Lowers the whole file and resolves the references between its nodes.
+    fn grammar_with_source_map(&self, file_id: FileId) -> (Arc<Grammar>, Arc<GrammarSourceMap>);
+
+    fn grammar(&self, file_id: FileId) -> Arc<Grammar>;
+
+    /// Returns the syntax of the first definition of the node `name`, detached
+    /// from the file. It stays equal as long as the text of the definition
+    /// does, wherever the definition moves.
+    fn node_def(&self, file_id: FileId, name: String) -> Option<GreenNode>;
+
+    /// Lowers the first definition of the node `name` on its own. The ranges
+    /// of the source map are relative to the start of the definition.
+    fn node_grammar_with_source_map(
+        &self,
+        file_id: FileId,
+        name: String,
+    ) -> Option<(Arc<Grammar>, Arc<GrammarSourceMap>)>;
+
+    /// Like [`HirDatabase::node_grammar_with_source_map`], without the source
+    /// map, which changes with any edit of the definition, even of trivia.
+    fn node_grammar(&self, file_id: FileId, name: String) -> Option<Arc<Grammar>>;
+
+    /// Infers the fields of the node `name`. The rules of the fields refer to
+    /// the [`HirDatabase::node_grammar`] of the node.
+    fn node_fields(&self, file_id: FileId, name: String) -> Arc<fields::NodeFields>;
+
+    /// Returns the files included by the file, in source order. Includes
+    /// which don't resolve to a known file are skipped.
+    fn included_files(&self, file_id: FileId) -> Arc<[FileId]>;
+
+    /// Returns the file itself and the files it includes, directly or through
+    /// other includes, in the order their definitions are looked up.
+    fn visible_files(&self, file_id: FileId) -> Arc<[FileId]>;
+
+    /// Returns the file which defines the node `name`, as seen from the file.
+    fn resolve_node(&self, file_id: FileId, name: String) -> Option<FileId>;
+}
+
+/// The names of the node definitions of a file, in source order.
+///
+/// The index doesn't store positions, so edits inside of the definitions
+/// don't change it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeIndex {
+    defs: Vec<Option<String>>,
+}
+
+impl NodeIndex {
+    /// Returns the names of the defined nodes, in the order of their first
+    /// definition.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        let mut seen = FxHashSet::default();
+        self.defs.iter().flatten().map(String::as_str).filter(move |it| seen.insert(*it))
+    }
+
+    /// Returns the position of the first definition of `name` among the
+    /// node definitions of the file.
+    pub fn position(&self, name: &str) -> Option<usize> {
+        self.defs.iter().position(|it| it.as_deref() == Some(name))
+    }
+}
+
+fn node_index(db: &dyn HirDatabase, file_id: FileId) -> Arc<NodeIndex> {
+    let file = db.parse(file_id).tree();
+    let defs = file.nodes().map(|node| node.name().map(|it| it.text())).collect();
+    Arc::new(NodeIndex { defs })
+}
+
+fn grammar_with_source_map(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+) -> (Arc<Grammar>, Arc<GrammarSourceMap>) {
+    let _p = tracing::span!(tracing::Level::INFO, "grammar_with_source_map").entered();
+    let (grammar, source_map) = Grammar::lower(&db.parse(file_id).tree());
+    (Arc::new(grammar), Arc::new(source_map))
+}
+
+fn grammar(db: &dyn HirDatabase, file_id: FileId) -> Arc<Grammar> {
+    db.grammar_with_source_map(file_id).0
+}
+
+fn node_def(db: &dyn HirDatabase, file_id: FileId, name: String) -> Option<GreenNode> {
+    let position = db.node_index(file_id).position(&name)?;
+    let node = db.parse(file_id).tree().nodes().nth(position)?;
+    Some(node.syntax().green().into_owned())
+}
+
+fn node_grammar_with_source_map(
+    db: &dyn HirDatabase,
+    file_id: FileId,
+    name: String,
+) -> Option<(Arc<Grammar>, Arc<GrammarSourceMap>)> {
+    let node = ast::Node::cast(SyntaxNode::new_root(db.node_def(file_id, name)?))?;
+    let (grammar, source_map) = Grammar::lower_node(&node);
+    Some((Arc::new(grammar), Arc::new(source_map)))
+}
+
+fn node_grammar(db: &dyn HirDatabase, file_id: FileId, name: String) -> Option<Arc<Grammar>> {
+    db.node_grammar_with_source_map(file_id, name).map(|(grammar, _)| grammar)
+}
+
+fn node_fields(db: &dyn HirDatabase, file_id: FileId, name: String) -> Arc<fields::NodeFields> {
+    let _p = tracing::span!(tracing::Level::INFO, "node_fields", name).entered();
+    let Some(grammar) = db.node_grammar(file_id, name) else { return Default::default() };
+    let node = grammar.iter().next().unwrap();
+    Arc::new(fields::node_fields(&grammar, node))
+}
+
+fn included_files(db: &dyn HirDatabase, file_id: FileId) -> Arc<[FileId]> {
+    let file_set = db.file_set();
+    let file = db.parse(file_id).tree();
+    file.includes()
+        .filter_map(|include| {
+            let path = include.string()?;
+            file_set.resolve_path(file_id, &path.value()?)
+        })
+        .collect()
+}
+
+fn visible_files(db: &dyn HirDatabase, file_id: FileId) -> Arc<[FileId]> {
+    let mut res = vec![file_id];
+    let mut seen = FxHashSet::from_iter([file_id]);
+    let mut idx = 0;
+    // Breadth first, so the closest definition wins. Cyclic includes are
+    // fine, each file is only visited once.
+    while let Some(&file_id) = res.get(idx) {
+        for &included in db.included_files(file_id).iter() {
+            if seen.insert(included) {
+                res.push(included);
+            }
+        }
+        idx += 1;
+    }
+    res.into()
+}
+
+fn resolve_node(db: &dyn HirDatabase, file_id: FileId, name: String) -> Option<FileId> {
+    db.visible_files(file_id)
+        .iter()
+        .copied()
+        .find(|&file_id| db.node_index(file_id).position(&name).is_some())
+}
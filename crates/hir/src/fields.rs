@@ -0,0 +1,320 @@
+//! Field names inferred for the parts of a node's rule.
+//!
+//! Code generators built on Ungrammar (most notably the one in rust-analyzer)
+//! turn every node into a struct with one accessor per *field*. A labeled rule
+//! names its field explicitly, for everything else the name is inferred:
+//!
+//! * a node reference `Expr` becomes `expr`,
+//! * a repeated node reference `Expr*` becomes `exprs`,
+//! * a token `'('` becomes `l_paren_token`,
+//! * a separated list `Expr (',' Expr)* ','?` becomes a single `exprs`.
+//!
+//! A node whose rule is an alternative of plain node references (`Item = Fn |
+//! Struct`) is an enum and has no fields.
+
+#[cfg(test)]
+mod tests;
+
+use crate::{Grammar, Node, Rule, RuleData, Token};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub ty: FieldTy,
+    pub cardinality: Cardinality,
+    /// Whether the name comes from a label rather than being inferred.
+    pub labeled: bool,
+    /// The `Node` and `Token` rules this field is derived from.
+    pub atoms: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FieldTy {
+    Node(Node),
+    Token(Token),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cardinality {
+    Optional,
+    Many,
+}
+
+/// Several atoms of one node infer the same field name, so a generated
+/// accessor can't tell them apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldClash {
+    pub name: String,
+    pub kind: FieldClashKind,
+    pub atoms: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldClashKind {
+    /// The same field may occur more than once, e.g. `Expr '+' Expr`.
+    Repeated,
+    /// The name is used for fields of different types, e.g. `Name | name:'ident'`.
+    Conflicting,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeFields {
+    pub fields: Vec<Field>,
+    pub clashes: Vec<FieldClash>,
+}
+
+impl Field {
+    pub fn is_many(&self) -> bool {
+        self.cardinality == Cardinality::Many
+    }
+}
+
+/// Returns true if the rule of `node` is an alternative of plain node
+/// references, which code generators turn into an enum.
+pub fn is_enum(grammar: &Grammar, node: Node) -> bool {
+    let Some(rule) = grammar[node].rule else { return false };
+    match &grammar[rule] {
+        RuleData::Alt(alts) => alts.iter().all(|&alt| matches!(grammar[alt], RuleData::Node(_))),
+        _ => false,
+    }
+}
+
+/// Infers the fields of `node`, reporting atoms whose names clash.
+pub fn node_fields(grammar: &Grammar, node: Node) -> NodeFields {
+    let Some(rule) = grammar[node].rule else { return NodeFields::default() };
+    if is_enum(grammar, node) {
+        return NodeFields::default();
+    }
+
+    let mut occurrences = Vec::new();
+    collect(grammar, rule, None, Cardinality::Optional, &mut Vec::new(), &mut occurrences);
+
+    let mut res = NodeFields::default();
+    let mut seen = vec![false; occurrences.len()];
+    for i in 0..occurrences.len() {
+        if seen[i] {
+            continue;
+        }
+        let group = (i..occurrences.len())
+            .filter(|&j| occurrences[j].field.name == occurrences[i].field.name)
+            .collect::<Vec<_>>();
+        group.iter().for_each(|&j| seen[j] = true);
+
+        let first = &occurrences[i].field;
+        let atoms = group.iter().flat_map(|&j| occurrences[j].field.atoms.iter().copied());
+        let atoms = atoms.collect::<Vec<_>>();
+
+        let conflicting = group.iter().any(|&j| !same_type(first, &occurrences[j].field));
+        let repeated = group.iter().enumerate().any(|(n, &a)| {
+            group[n + 1..]
+                .iter()
+                .any(|&b| co_occur(grammar, &occurrences[a].ancestors, &occurrences[b].ancestors))
+        });
+        let clash = match (conflicting, repeated) {
+            (true, _) => Some(FieldClashKind::Conflicting),
+            (false, true) => Some(FieldClashKind::Repeated),
+            (false, false) => None,
+        };
+        if let Some(kind) = clash {
+            res.clashes.push(FieldClash { name: first.name.clone(), kind, atoms: atoms.clone() });
+        }
+        res.fields.push(Field { atoms, ..first.clone() });
+    }
+    res
+}
+
+/// Converts a token literal into the name used for its field and kind, e.g.
+/// `(` into `l_paren` and `fn` into `fn`.
+pub fn token_name(text: &str) -> String {
+    let is_ident = text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_ident && text != "_" {
+        return text.to_owned();
+    }
+    if let Some(&(_, name)) = PUNCT_NAMES.iter().find(|&&(punct, _)| punct == text) {
+        return name.to_owned();
+    }
+    if text.is_empty() {
+        return "empty".to_owned();
+    }
+    text.chars().map(char_name).collect::<Vec<_>>().join("_")
+}
+
+/// Names of common (multi-character) punctuation, following rust-analyzer.
+const PUNCT_NAMES: &[(&str, &str)] = &[
+    ("->", "thin_arrow"),
+    ("=>", "fat_arrow"),
+    ("..", "dotdot"),
+    ("...", "dotdotdot"),
+    ("..=", "dotdoteq"),
+    ("::", "coloncolon"),
+];
+
+fn char_name(c: char) -> String {
+    let name = match c {
+        ';' => "semicolon",
+        ',' => "comma",
+        '(' => "l_paren",
+        ')' => "r_paren",
+        '{' => "l_curly",
+        '}' => "r_curly",
+        '[' => "l_brack",
+        ']' => "r_brack",
+        '<' => "l_angle",
+        '>' => "r_angle",
+        '@' => "at",
+        '#' => "pound",
+        '~' => "tilde",
+        '?' => "question_mark",
+        '$' => "dollar",
+        '&' => "amp",
+        '|' => "pipe",
+        '+' => "plus",
+        '*' => "star",
+        '/' => "slash",
+        '^' => "caret",
+        '%' => "percent",
+        '_' => "underscore",
+        '.' => "dot",
+        ':' => "colon",
+        '=' => "eq",
+        '!' => "excl",
+        '-' => "minus",
+        '\'' => "quote",
+        '"' => "double_quote",
+        '\\' => "backslash",
+        '`' => "backtick",
+        ' ' => "space",
+        c if c.is_ascii_alphanumeric() => return c.to_ascii_lowercase().to_string(),
+        c => return format!("u{:04x}", c as u32),
+    };
+    name.to_owned()
+}
+
+pub fn pluralize(name: &str) -> String {
+    format!("{name}s")
+}
+
+struct Occurrence {
+    field: Field,
+    /// Compound rules enclosing the atoms, outermost first.
+    ancestors: Vec<Rule>,
+}
+
+fn collect(
+    grammar: &Grammar,
+    rule: Rule,
+    label: Option<&str>,
+    cardinality: Cardinality,
+    ancestors: &mut Vec<Rule>,
+    acc: &mut Vec<Occurrence>,
+) {
+    if let Some((node, atoms)) = separated_list(grammar, rule) {
+        let name = match label {
+            Some(label) => label.to_owned(),
+            None => pluralize(&stdx::to_lower_snake_case(&grammar[node].name)),
+        };
+        let field = Field {
+            name,
+            ty: FieldTy::Node(node),
+            cardinality: Cardinality::Many,
+            labeled: label.is_some(),
+            atoms,
+        };
+        acc.push(Occurrence { field, ancestors: ancestors.clone() });
+        return;
+    }
+
+    let (name, ty) = match &grammar[rule] {
+        &RuleData::Node(node) => {
+            let name = stdx::to_lower_snake_case(&grammar[node].name);
+            let name = match cardinality {
+                Cardinality::Optional => name,
+                Cardinality::Many => pluralize(&name),
+            };
+            (name, FieldTy::Node(node))
+        }
+        &RuleData::Token(token) => {
+            (format!("{}_token", token_name(&grammar[token].name)), FieldTy::Token(token))
+        }
+        RuleData::Labeled { label, rule } => {
+            collect(grammar, *rule, Some(label), cardinality, ancestors, acc);
+            return;
+        }
+        RuleData::Seq(rules) | RuleData::Alt(rules) => {
+            ancestors.push(rule);
+            for &it in rules {
+                collect(grammar, it, label, cardinality, ancestors, acc);
+            }
+            ancestors.pop();
+            return;
+        }
+        &RuleData::Opt(inner) => {
+            ancestors.push(rule);
+            collect(grammar, inner, label, cardinality, ancestors, acc);
+            ancestors.pop();
+            return;
+        }
+        &RuleData::Rep(inner) => {
+            ancestors.push(rule);
+            collect(grammar, inner, label, Cardinality::Many, ancestors, acc);
+            ancestors.pop();
+            return;
+        }
+    };
+    let field = Field {
+        name: label.map_or(name, str::to_owned),
+        ty,
+        cardinality,
+        labeled: label.is_some(),
+        atoms: vec![rule],
+    };
+    acc.push(Occurrence { field, ancestors: ancestors.clone() });
+}
+
+/// Matches `T (',' T)*` and `T (',' T)* ','?`, returning `T` and both of its
+/// references.
+fn separated_list(grammar: &Grammar, rule: Rule) -> Option<(Node, Vec<Rule>)> {
+    let RuleData::Seq(rules) = &grammar[rule] else { return None };
+    let (first, repeat, trailing) = match *rules.as_slice() {
+        [first, repeat] => (first, repeat, None),
+        [first, repeat, trailing] => (first, repeat, Some(trailing)),
+        _ => return None,
+    };
+    let &RuleData::Node(node) = &grammar[first] else { return None };
+    let &RuleData::Rep(repeat) = &grammar[repeat] else { return None };
+    let RuleData::Seq(repeat) = &grammar[repeat] else { return None };
+    let &[sep, second] = repeat.as_slice() else { return None };
+    let &RuleData::Token(sep) = &grammar[sep] else { return None };
+    if grammar[second] != RuleData::Node(node) {
+        return None;
+    }
+    if let Some(trailing) = trailing {
+        let &RuleData::Opt(trailing) = &grammar[trailing] else { return None };
+        if grammar[trailing] != RuleData::Token(sep) {
+            return None;
+        }
+    }
+    Some((node, vec![first, second]))
+}
+
+fn same_type(a: &Field, b: &Field) -> bool {
+    let same_ty = match (a.ty, b.ty) {
+        (FieldTy::Node(a), FieldTy::Node(b)) => a == b,
+        // Different tokens under one label, as in `op:('+' | '-')`, are a
+        // single token field.
+        (FieldTy::Token(_), FieldTy::Token(_)) => true,
+        _ => false,
+    };
+    same_ty && a.cardinality == b.cardinality
+}
+
+/// Two atoms can both be present in one instance of a node unless they sit in
+/// different branches of an alternative.
+fn co_occur(grammar: &Grammar, a: &[Rule], b: &[Rule]) -> bool {
+    let common = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+    match common.checked_sub(1).map(|it| a[it]) {
+        Some(lca) => !matches!(grammar[lca], RuleData::Alt(_)),
+        None => true,
+    }
+}
@@ -0,0 +1,130 @@
+use expect_test::{expect, Expect};
+use syntax::ast;
+
+use crate::{
+    fields::{node_fields, Cardinality, FieldTy},
+    Grammar,
+};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let parse = ast::Grammar::parse(ungrammar);
+    assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+    let (grammar, _source_map) = Grammar::lower(&parse.tree());
+
+    let mut actual = String::new();
+    for node in grammar.iter().filter(|&it| grammar[it].rule.is_some()) {
+        let fields = node_fields(&grammar, node);
+        actual.push_str(&format!("{}\n", grammar[node].name));
+        for field in &fields.fields {
+            let ty = match field.ty {
+                FieldTy::Node(node) => grammar[node].name.clone(),
+                FieldTy::Token(token) => format!("'{}'", grammar[token].name),
+            };
+            let ty = match field.cardinality {
+                Cardinality::Optional => ty,
+                Cardinality::Many => format!("{ty}*"),
+            };
+            let label = if field.labeled { " (labeled)" } else { "" };
+            actual.push_str(&format!("  {}: {ty}{label}\n", field.name));
+        }
+        for clash in &fields.clashes {
+            let n = clash.atoms.len();
+            actual.push_str(&format!("  clash {:?} `{}` x{n}\n", clash.kind, clash.name));
+        }
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn infers_field_names() {
+    check(
+        r#"
+Fn = 'fn' Name ParamList '(' Expr* ')' ret:Type? ';'
+"#,
+        expect![[r#"
+            Fn
+              fn_token: 'fn'
+              name: Name
+              param_list: ParamList
+              l_paren_token: '('
+              exprs: Expr*
+              r_paren_token: ')'
+              ret: Type (labeled)
+              semicolon_token: ';'
+        "#]],
+    );
+}
+
+#[test]
+fn enums_have_no_fields() {
+    check(
+        r#"
+Item = Fn | Struct
+Fn = 'fn'
+Struct = 'struct'
+"#,
+        expect![[r#"
+            Item
+            Fn
+              fn_token: 'fn'
+            Struct
+              struct_token: 'struct'
+        "#]],
+    );
+}
+
+#[test]
+fn separated_list_is_a_single_field() {
+    check(
+        r#"
+ArgList = '(' (Arg (',' Arg)* ','?)? ')'
+"#,
+        expect![[r#"
+            ArgList
+              l_paren_token: '('
+              args: Arg*
+              r_paren_token: ')'
+        "#]],
+    );
+}
+
+#[test]
+fn repeated_fields_clash() {
+    check(
+        r#"
+BinExpr = Expr '+' Expr
+LabeledBinExpr = lhs:Expr op:('+' | '-') rhs:Expr
+"#,
+        expect![[r#"
+            BinExpr
+              expr: Expr
+              plus_token: '+'
+              clash Repeated `expr` x2
+            LabeledBinExpr
+              lhs: Expr (labeled)
+              op: '+' (labeled)
+              rhs: Expr (labeled)
+        "#]],
+    );
+}
+
+#[test]
+fn alternatives_share_fields() {
+    check(
+        r#"
+Visibility = 'pub' ('(' 'crate' ')' | '(' 'super' ')')
+Conflict = Name | name:'ident'
+"#,
+        expect![[r#"
+            Visibility
+              pub_token: 'pub'
+              l_paren_token: '('
+              crate_token: 'crate'
+              r_paren_token: ')'
+              super_token: 'super'
+            Conflict
+              name: Name
+              clash Conflicting `name` x2
+        "#]],
+    );
+}
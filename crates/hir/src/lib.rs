@@ -0,0 +1,210 @@
+//! HIR provides a high-level semantic model of a grammar.
+//!
+//! The syntax tree of a `.ungram` file is lowered into a [`Grammar`]: a list
+//! of named nodes, the token literals they mention and the rules that connect
+//! them. The shape mirrors the data model of the `ungrammar` crate, so tools
+//! written against it feel at home. Unlike the syntax tree, the model is
+//! resolved: a [`Rule`] refers to [`Node`]s rather than to their names.
+//!
+//! Every item remembers where it came from through the [`GrammarSourceMap`],
+//! which is what the IDE layer uses to map results back to text ranges.
+//!
+//! The model is computed on demand and memoized by the queries of the
+//! [`HirDatabase`].
+
+mod db;
+mod lower;
+
+pub mod fields;
+
+#[cfg(test)]
+mod test_db;
+#[cfg(test)]
+mod tests;
+
+use std::ops;
+
+pub use crate::db::{HirDatabase, HirDatabaseStorage, NodeIndex};
+
+use rustc_hash::FxHashMap;
+use syntax::{ast, AstPtr, SyntaxNodePtr};
+
+/// A node defined (or referenced) in the grammar, e.g. `Expr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Node(u32);
+
+/// A token literal used in the grammar, e.g. `'fn'`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Token(u32);
+
+/// A rule, or a part of a rule, on the right-hand side of a node definition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Rule(u32);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeData {
+    pub name: String,
+    /// `None` for nodes which are referenced but never defined, and for
+    /// definitions without a (valid) rule.
+    pub rule: Option<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenData {
+    /// The unescaped contents of the literal, without quotes.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleData {
+    Labeled { label: String, rule: Rule },
+    Node(Node),
+    Token(Token),
+    Seq(Vec<Rule>),
+    Alt(Vec<Rule>),
+    Opt(Rule),
+    Rep(Rule),
+}
+
+/// The semantic model of a single `.ungram` file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Grammar {
+    nodes: Vec<NodeData>,
+    tokens: Vec<TokenData>,
+    rules: Vec<RuleData>,
+}
+
+impl Grammar {
+    /// Lowers a syntax tree into the semantic model, together with the
+    /// mapping back to the syntax.
+    pub fn lower(file: &ast::Grammar) -> (Grammar, GrammarSourceMap) {
+        lower::lower(file)
+    }
+
+    /// Lowers a single node definition on its own, so the result only depends
+    /// on the text of the definition. The defined node is the first node of
+    /// the grammar.
+    pub fn lower_node(node: &ast::Node) -> (Grammar, GrammarSourceMap) {
+        lower::lower_node(node)
+    }
+
+    /// Returns an iterator over all nodes in the grammar, in the order of
+    /// their first appearance.
+    pub fn iter(&self) -> impl Iterator<Item = Node> + '_ {
+        (0..self.nodes.len() as u32).map(Node)
+    }
+
+    /// Returns an iterator over all tokens in the grammar, in the order of
+    /// their first appearance.
+    pub fn tokens(&self) -> impl Iterator<Item = Token> + '_ {
+        (0..self.tokens.len() as u32).map(Token)
+    }
+
+    /// Returns an iterator over all rules and subrules in the grammar.
+    pub fn rules(&self) -> impl Iterator<Item = Rule> + '_ {
+        (0..self.rules.len() as u32).map(Rule)
+    }
+
+    pub fn find_node(&self, name: &str) -> Option<Node> {
+        self.iter().find(|&it| self[it].name == name)
+    }
+
+    pub fn find_token(&self, name: &str) -> Option<Token> {
+        self.tokens().find(|&it| self[it].name == name)
+    }
+
+    /// Calls `f` for `rule` and each of its subrules, in preorder.
+    pub fn walk_rule(&self, rule: Rule, f: &mut impl FnMut(Rule)) {
+        f(rule);
+        match &self[rule] {
+            RuleData::Node(_) | RuleData::Token(_) => (),
+            RuleData::Labeled { rule, .. } | RuleData::Opt(rule) | RuleData::Rep(rule) => {
+                self.walk_rule(*rule, f)
+            }
+            RuleData::Seq(rules) | RuleData::Alt(rules) => {
+                rules.iter().for_each(|&rule| self.walk_rule(rule, f))
+            }
+        }
+    }
+
+    /// Returns the node references in the rule of `node`, in source order.
+    pub fn node_refs(&self, node: Node) -> Vec<NodeRef> {
+        let mut res = Vec::new();
+        if let Some(rule) = self[node].rule {
+            self.walk_rule(rule, &mut |rule| {
+                if let RuleData::Node(target) = self[rule] {
+                    res.push(NodeRef { container: node, rule, target });
+                }
+            });
+        }
+        res
+    }
+
+    /// Returns the references to `target` from the rules of all nodes.
+    pub fn usages(&self, target: Node) -> Vec<NodeRef> {
+        self.iter().flat_map(|node| self.node_refs(node)).filter(|it| it.target == target).collect()
+    }
+}
+
+/// A reference to `target` by the `rule` of `container`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeRef {
+    pub container: Node,
+    pub rule: Rule,
+    pub target: Node,
+}
+
+impl ops::Index<Node> for Grammar {
+    type Output = NodeData;
+    fn index(&self, Node(index): Node) -> &NodeData {
+        &self.nodes[index as usize]
+    }
+}
+
+impl ops::Index<Token> for Grammar {
+    type Output = TokenData;
+    fn index(&self, Token(index): Token) -> &TokenData {
+        &self.tokens[index as usize]
+    }
+}
+
+impl ops::Index<Rule> for Grammar {
+    type Output = RuleData;
+    fn index(&self, Rule(index): Rule) -> &RuleData {
+        &self.rules[index as usize]
+    }
+}
+
+/// Maps the items of a [`Grammar`] back to the syntax they were lowered from.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GrammarSourceMap {
+    node_defs: Vec<Option<AstPtr<ast::Node>>>,
+    rules: Vec<SyntaxNodePtr>,
+    rule_map: FxHashMap<SyntaxNodePtr, Rule>,
+    duplicate_defs: Vec<(Node, AstPtr<ast::Node>)>,
+}
+
+impl GrammarSourceMap {
+    /// The definition of `node`, or `None` if the node is only referenced.
+    pub fn node_def(&self, node: Node) -> Option<AstPtr<ast::Node>> {
+        self.node_defs[node.0 as usize].clone()
+    }
+
+    /// The syntax `rule` was lowered from.
+    ///
+    /// For a [`RuleData::Node`] and a [`RuleData::Token`] this is the
+    /// `NAME_REF` and the `TOKEN` respectively.
+    pub fn rule_syntax(&self, rule: Rule) -> SyntaxNodePtr {
+        self.rules[rule.0 as usize]
+    }
+
+    pub fn syntax_rule(&self, ptr: &SyntaxNodePtr) -> Option<Rule> {
+        self.rule_map.get(ptr).copied()
+    }
+
+    /// Definitions which reuse the name of an earlier definition and are
+    /// therefore ignored.
+    pub fn duplicate_defs(&self) -> &[(Node, AstPtr<ast::Node>)] {
+        &self.duplicate_defs
+    }
+}
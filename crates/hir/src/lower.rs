@@ -0,0 +1,158 @@
+//! Lowers the syntax tree of a `.ungram` file into a [`Grammar`].
+
+use rustc_hash::FxHashMap;
+use syntax::{ast, AstNode, AstPtr, SyntaxNodePtr};
+
+use crate::{Grammar, GrammarSourceMap, Node, NodeData, Rule, RuleData, Token, TokenData};
+
+pub(crate) fn lower(file: &ast::Grammar) -> (Grammar, GrammarSourceMap) {
+    lower_defs(file.nodes())
+}
+
+/// Lowers a single definition, as if it was the only one in the file. Its
+/// references stay undefined nodes.
+pub(crate) fn lower_node(node: &ast::Node) -> (Grammar, GrammarSourceMap) {
+    lower_defs([node.clone()])
+}
+
+fn lower_defs(nodes: impl IntoIterator<Item = ast::Node>) -> (Grammar, GrammarSourceMap) {
+    let mut ctx = LowerCtx::default();
+
+    // Allocate all definitions first, so that references can be resolved
+    // regardless of the order in which nodes are defined.
+    let mut defs = Vec::new();
+    for node in nodes {
+        let Some(name) = node.name() else { continue };
+        let name = name.text();
+        match ctx.node_by_name.get(&name) {
+            Some(&existing) => ctx.source_map.duplicate_defs.push((existing, AstPtr::new(&node))),
+            None => {
+                let id = ctx.alloc_node(name, Some(AstPtr::new(&node)));
+                defs.push((id, node));
+            }
+        }
+    }
+
+    for (id, node) in defs {
+        let rule = node.rule().and_then(|rule| ctx.lower_rule(rule));
+        ctx.grammar.nodes[id.0 as usize].rule = rule;
+    }
+
+    (ctx.grammar, ctx.source_map)
+}
+
+#[derive(Default)]
+struct LowerCtx {
+    grammar: Grammar,
+    source_map: GrammarSourceMap,
+    node_by_name: FxHashMap<String, Node>,
+    token_by_name: FxHashMap<String, Token>,
+}
+
+impl LowerCtx {
+    fn alloc_node(&mut self, name: String, def: Option<AstPtr<ast::Node>>) -> Node {
+        let id = Node(self.grammar.nodes.len() as u32);
+        self.grammar.nodes.push(NodeData { name: name.clone(), rule: None });
+        self.source_map.node_defs.push(def);
+        self.node_by_name.insert(name, id);
+        id
+    }
+
+    fn alloc_rule(&mut self, data: RuleData, syntax: &ast::Rule) -> Rule {
+        let id = Rule(self.grammar.rules.len() as u32);
+        let ptr = SyntaxNodePtr::new(syntax.syntax());
+        self.grammar.rules.push(data);
+        self.source_map.rules.push(ptr);
+        self.source_map.rule_map.insert(ptr, id);
+        id
+    }
+
+    fn resolve_node(&mut self, name: String) -> Node {
+        match self.node_by_name.get(&name) {
+            Some(&node) => node,
+            None => self.alloc_node(name, None),
+        }
+    }
+
+    fn intern_token(&mut self, name: String) -> Token {
+        if let Some(&token) = self.token_by_name.get(&name) {
+            return token;
+        }
+        let id = Token(self.grammar.tokens.len() as u32);
+        self.grammar.tokens.push(TokenData { name: name.clone() });
+        self.token_by_name.insert(name, id);
+        id
+    }
+
+    fn lower_rule(&mut self, rule: ast::Rule) -> Option<Rule> {
+        let data = match &rule {
+            ast::Rule::ParenRule(paren) => {
+                let inner = self.lower_rule(paren.rule()?)?;
+                let ptr = SyntaxNodePtr::new(paren.syntax());
+                self.source_map.rule_map.insert(ptr, inner);
+                return Some(inner);
+            }
+            ast::Rule::NameRef(name_ref) => RuleData::Node(self.resolve_node(name_ref.text())),
+            ast::Rule::Token(token) => {
+                let string = token.string()?;
+                // Invalid escapes are reported by the parser, `'\+'` is
+                // still the `'+'` token.
+                RuleData::Token(self.intern_token(string.unescaped().value().to_owned()))
+            }
+            ast::Rule::LabeledRule(labeled) => {
+                let label = labeled.label()?.text();
+                let rule = self.lower_rule(labeled.rule()?)?;
+                RuleData::Labeled { label, rule }
+            }
+            ast::Rule::SeqRule(seq) => {
+                let mut rules = Vec::new();
+                for rule in seq.rules() {
+                    self.lower_flattened(rule, &mut rules, |it| match it {
+                        ast::Rule::SeqRule(it) => Some(it.rules()),
+                        _ => None,
+                    });
+                }
+                match rules.len() {
+                    0 => return None,
+                    1 => return rules.pop(),
+                    _ => RuleData::Seq(rules),
+                }
+            }
+            ast::Rule::AltRule(alt) => {
+                let mut rules = Vec::new();
+                for rule in alt.rules() {
+                    self.lower_flattened(rule, &mut rules, |it| match it {
+                        ast::Rule::AltRule(it) => Some(it.rules()),
+                        _ => None,
+                    });
+                }
+                match rules.len() {
+                    0 => return None,
+                    1 => return rules.pop(),
+                    _ => RuleData::Alt(rules),
+                }
+            }
+            ast::Rule::OptRule(opt) => RuleData::Opt(self.lower_rule(opt.rule()?)?),
+            ast::Rule::RepRule(rep) => RuleData::Rep(self.lower_rule(rep.rule()?)?),
+        };
+        Some(self.alloc_rule(data, &rule))
+    }
+
+    /// The parser nests binary rules (`A | B | C` is `(A | B) | C`), we want a
+    /// flat list of alternatives instead.
+    fn lower_flattened(
+        &mut self,
+        rule: ast::Rule,
+        acc: &mut Vec<Rule>,
+        split: fn(&ast::Rule) -> Option<ast::AstChildren<ast::Rule>>,
+    ) {
+        match split(&rule) {
+            Some(children) => {
+                for child in children {
+                    self.lower_flattened(child, acc, split);
+                }
+            }
+            None => acc.extend(self.lower_rule(rule)),
+        }
+    }
+}
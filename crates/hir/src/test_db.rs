@@ -0,0 +1,60 @@
+//! Database used for testing `hir`.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use base_db::{salsa, SourceDatabase, SourceDatabaseStorage};
+use syntax::{Edition, LineEndings};
+
+use crate::db::HirDatabaseStorage;
+
+#[salsa::database(SourceDatabaseStorage, HirDatabaseStorage)]
+pub(crate) struct TestDB {
+    storage: salsa::Storage<TestDB>,
+    events: Mutex<Option<Vec<salsa::Event>>>,
+}
+
+impl Default for TestDB {
+    fn default() -> TestDB {
+        let mut db = TestDB { storage: Default::default(), events: Default::default() };
+        db.set_file_set(Arc::default());
+        db.set_default_edition(Edition::CURRENT);
+        db.set_line_endings(LineEndings::Unix);
+        db
+    }
+}
+
+impl fmt::Debug for TestDB {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TestDB").finish()
+    }
+}
+
+impl salsa::Database for TestDB {
+    fn salsa_event(&self, event: salsa::Event) {
+        let mut events = self.events.lock().unwrap();
+        if let Some(events) = &mut *events {
+            events.push(event);
+        }
+    }
+}
+
+impl TestDB {
+    /// Runs `f` and returns the queries which were (re)computed by it.
+    pub(crate) fn log_executed(&self, f: impl FnOnce()) -> Vec<String> {
+        *self.events.lock().unwrap() = Some(Vec::new());
+        f();
+        let events = self.events.lock().unwrap().take().unwrap();
+        events
+            .into_iter()
+            .filter_map(|event| match event.kind {
+                salsa::EventKind::WillExecute { database_key } => {
+                    Some(format!("{:?}", database_key.debug(self)))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
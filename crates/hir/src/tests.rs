@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use base_db::{FileId, FileSet, SourceDatabase, VfsPath};
+use expect_test::{expect, Expect};
+use syntax::ast;
+
+use crate::{test_db::TestDB, Grammar, HirDatabase, Rule, RuleData};
+
+fn lower(ungrammar: &str) -> Grammar {
+    let parse = ast::Grammar::parse(ungrammar);
+    Grammar::lower(&parse.tree()).0
+}
+
+fn render(grammar: &Grammar, rule: Rule) -> String {
+    let list = |rules: &[Rule], sep: &str| {
+        rules.iter().map(|&it| render(grammar, it)).collect::<Vec<_>>().join(sep)
+    };
+    match &grammar[rule] {
+        RuleData::Labeled { label, rule } => format!("{label}:{}", render(grammar, *rule)),
+        RuleData::Node(node) => grammar[*node].name.clone(),
+        RuleData::Token(token) => format!("{:?}", grammar[*token].name),
+        RuleData::Seq(rules) => format!("({})", list(rules, " ")),
+        RuleData::Alt(rules) => format!("({})", list(rules, " | ")),
+        RuleData::Opt(rule) => format!("{}?", render(grammar, *rule)),
+        RuleData::Rep(rule) => format!("{}*", render(grammar, *rule)),
+    }
+}
+
+fn check_lower(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    let mut actual = String::new();
+    for node in grammar.iter() {
+        let rule = grammar[node].rule.map(|it| render(&grammar, it));
+        actual.push_str(&format!("{} = {}\n", grammar[node].name, rule.unwrap_or_default()));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn lowers_rules() {
+    check_lower(
+        r#"
+Expr = Literal | BinExpr | '(' Expr ')'
+BinExpr = lhs:Expr op:('+' | '-') rhs:Expr
+Literal = 'int' | 'str' ('\'' | '\\')*
+Broken =
+"#,
+        expect![[r#"
+            Expr = (Literal | BinExpr | ("(" Expr ")"))
+            BinExpr = (lhs:Expr op:("+" | "-") rhs:Expr)
+            Literal = ("int" | ("str" ("'" | "\\")*))
+            Broken = 
+        "#]],
+    );
+}
+
+#[test]
+fn unresolved_references_are_allocated() {
+    check_lower(
+        r#"
+A = B C
+B = 'b'
+"#,
+        expect![[r#"
+            A = (B C)
+            B = "b"
+            C = 
+        "#]],
+    );
+}
+
+#[test]
+fn usages() {
+    let grammar = lower(
+        r#"
+Expr = Literal | BinExpr
+BinExpr = lhs:Expr '+' rhs:Expr
+Literal = 'int'
+"#,
+    );
+    let expr = grammar.find_node("Expr").unwrap();
+    let usages = grammar
+        .usages(expr)
+        .iter()
+        .map(|it| grammar[it.container].name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(usages, ["BinExpr", "BinExpr"]);
+
+    let refs = grammar
+        .node_refs(expr)
+        .iter()
+        .map(|it| grammar[it.target].name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(refs, ["Literal", "BinExpr"]);
+}
+
+#[test]
+fn edits_in_a_node_keep_the_fields_of_other_nodes() {
+    let file_id = FileId(0);
+    let mut db = TestDB::default();
+    db.set_file_text(file_id, Arc::from("A = B 'a'\nB = A*\n"));
+
+    let infer_all = |db: &TestDB| {
+        for name in db.node_index(file_id).names() {
+            db.node_fields(file_id, name.to_owned());
+        }
+    };
+    let executed = db.log_executed(|| infer_all(&db));
+    assert!(executed.iter().any(|it| it.contains("node_fields") && it.contains("\"A\"")));
+    assert!(executed.iter().any(|it| it.contains("node_fields") && it.contains("\"B\"")));
+
+    db.set_file_text(file_id, Arc::from("A = B 'a' 'b'\nB = A*\n"));
+    let executed = db.log_executed(|| infer_all(&db));
+    let executed = executed.iter().filter(|it| it.contains("node_fields")).collect::<Vec<_>>();
+    assert_eq!(executed.len(), 1, "{executed:?}");
+    assert!(executed[0].contains("\"A\""));
+}
+
+#[test]
+fn whitespace_edits_keep_the_grammar() {
+    let file_id = FileId(0);
+    let mut db = TestDB::default();
+    db.set_file_text(file_id, Arc::from("A = B\n"));
+    db.log_executed(|| {
+        db.node_fields(file_id, "A".to_owned());
+    });
+
+    db.set_file_text(file_id, Arc::from("\n// a comment\nA =   B\n"));
+    let executed = db.log_executed(|| {
+        db.node_fields(file_id, "A".to_owned());
+    });
+    assert!(executed.iter().any(|it| it.contains("parse")), "{executed:?}");
+    assert!(!executed.iter().any(|it| it.contains("node_fields")), "{executed:?}");
+}
+
+#[test]
+fn edits_before_a_node_keep_its_source_map() {
+    let file_id = FileId(0);
+    let mut db = TestDB::default();
+    db.set_file_text(file_id, Arc::from("A = 'a'\nB = A*\n"));
+    db.log_executed(|| {
+        db.node_grammar_with_source_map(file_id, "B".to_owned());
+    });
+
+    db.set_file_text(file_id, Arc::from("A = 'a' 'b'\nB = A*\n"));
+    let executed = db.log_executed(|| {
+        db.node_grammar_with_source_map(file_id, "B".to_owned());
+    });
+    assert!(executed.iter().any(|it| it.contains("node_def")), "{executed:?}");
+    assert!(!executed.iter().any(|it| it.contains("node_grammar")), "{executed:?}");
+}
+
+#[test]
+fn resolves_nodes_across_includes() {
+    let files = [
+        ("/grammar.ungram", "include 'rules/expr.ungram'\nFile = Expr* Missing\n"),
+        (
+            "/rules/expr.ungram",
+            "include '../tokens.ungram'\ninclude 'gone.ungram'\nExpr = Literal\n",
+        ),
+        ("/tokens.ungram", "include 'grammar.ungram'\nLiteral = 'int'\nExpr = 'shadowed'\n"),
+    ];
+    let mut db = TestDB::default();
+    let mut file_set = FileSet::default();
+    for (idx, (path, text)) in files.into_iter().enumerate() {
+        let file_id = FileId(idx as u32);
+        db.set_file_text(file_id, Arc::from(text));
+        file_set.insert(file_id, VfsPath::new_virtual_path(path.to_owned()));
+    }
+    db.set_file_set(Arc::new(file_set));
+
+    let (grammar, expr, tokens) = (FileId(0), FileId(1), FileId(2));
+    assert_eq!(*db.included_files(expr), [tokens]);
+    assert_eq!(*db.visible_files(grammar), [grammar, expr, tokens]);
+    assert_eq!(*db.visible_files(tokens), [tokens, grammar, expr]);
+
+    assert_eq!(db.resolve_node(grammar, "Expr".to_owned()), Some(expr));
+    assert_eq!(db.resolve_node(grammar, "Literal".to_owned()), Some(tokens));
+    assert_eq!(db.resolve_node(tokens, "Expr".to_owned()), Some(tokens));
+    assert_eq!(db.resolve_node(grammar, "Missing".to_owned()), None);
+}
@@ -0,0 +1,78 @@
+//! Annotations shown above node definitions, e.g. the number of places that
+//! reference a node. Clients usually render these as code lenses.
+//!
+//! References are counted in all the files which see the definition through
+//! an include, not only in the file of the definition.
+
+#[cfg(test)]
+mod tests;
+
+use syntax::{ast::AstNode, TextRange};
+
+use crate::{files_referring_to, FileAnalysis, FileId};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AnnotationConfig {
+    pub annotate_references: bool,
+}
+
+impl Default for AnnotationConfig {
+    fn default() -> Self {
+        AnnotationConfig { annotate_references: true }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Annotation {
+    /// The name of the annotated node definition.
+    pub range: TextRange,
+    pub kind: AnnotationKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AnnotationKind {
+    HasReferences { references: Vec<Reference> },
+}
+
+/// A place that refers to the annotated node.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Reference {
+    pub file_id: FileId,
+    /// The range of the referring `NAME_REF`.
+    pub range: TextRange,
+    /// The name of the node whose rule contains the reference.
+    pub container: String,
+}
+
+pub(crate) fn annotations(file: &FileAnalysis<'_>, config: &AnnotationConfig) -> Vec<Annotation> {
+    let _p = tracing::span!(tracing::Level::INFO, "annotations").entered();
+    let FileAnalysis { db, file_id, parse, grammar, source_map } = file;
+    let mut acc = Vec::new();
+    if !config.annotate_references {
+        return acc;
+    }
+
+    let root = parse.syntax_node();
+    for node in grammar.iter() {
+        let Some(name) = source_map.node_def(node).and_then(|it| it.to_node(&root).name()) else {
+            continue;
+        };
+        let name_text = &grammar[node].name;
+        let mut references = Vec::new();
+        for referrer in files_referring_to(*db, *file_id, name_text) {
+            let referrer = FileAnalysis::new(*db, referrer);
+            let Some(target) = referrer.grammar.find_node(name_text) else { continue };
+            references.extend(referrer.grammar.usages(target).into_iter().map(|it| Reference {
+                file_id: referrer.file_id,
+                range: referrer.source_map.rule_syntax(it.rule).text_range(),
+                container: referrer.grammar[it.container].name.clone(),
+            }));
+        }
+        acc.push(Annotation {
+            range: name.syntax().text_range(),
+            kind: AnnotationKind::HasReferences { references },
+        });
+    }
+    acc.sort_by_key(|it| it.range.start());
+    acc
+}
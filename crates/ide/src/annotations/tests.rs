@@ -0,0 +1,84 @@
+use expect_test::{expect, Expect};
+
+use crate::{fixture, Analysis, AnnotationConfig, AnnotationKind};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let (analysis, file_id) = Analysis::from_single_file(ungrammar.to_owned());
+    let annotations = analysis.annotations(&AnnotationConfig::default(), file_id);
+
+    let mut actual = String::new();
+    for annotation in annotations {
+        let AnnotationKind::HasReferences { references } = annotation.kind;
+        let name = &ungrammar[annotation.range];
+        actual.push_str(&format!(
+            "{name} {:?}: {} references\n",
+            annotation.range,
+            references.len()
+        ));
+        for reference in references {
+            actual.push_str(&format!("  {:?} in {}\n", reference.range, reference.container));
+        }
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn reference_counts() {
+    check(
+        r#"
+Expr = Literal | BinExpr
+BinExpr = lhs:Expr '+' rhs:Expr
+Literal = 'int'
+"#,
+        expect![[r#"
+            Expr 1..5: 2 references
+              40..44 in BinExpr
+              53..57 in BinExpr
+            BinExpr 26..33: 1 references
+              18..25 in Expr
+            Literal 58..65: 1 references
+              8..15 in Expr
+        "#]],
+    );
+}
+
+#[test]
+fn unreferenced_and_undefined_nodes() {
+    check(
+        r#"
+Root = Missing
+Root = 'dup'
+"#,
+        expect![[r#"
+            Root 1..5: 0 references
+        "#]],
+    );
+}
+
+#[test]
+fn disabled() {
+    let (analysis, file_id) = Analysis::from_single_file("A = B\nB = A".to_owned());
+    let config = AnnotationConfig { annotate_references: false };
+    assert!(analysis.annotations(&config, file_id).is_empty());
+}
+
+#[test]
+fn references_from_including_files() {
+    let (analysis, files) = fixture::files(
+        r#"
+//- /grammar.ungram
+include 'tokens.ungram'
+File = Literal*
+//- /tokens.ungram
+Literal = 'int' | Literal
+//- /shadowing.ungram
+include 'tokens.ungram'
+Literal = 'str'
+Other = Literal
+"#,
+    );
+    let annotations = analysis.annotations(&AnnotationConfig::default(), files[1]);
+    let AnnotationKind::HasReferences { references } = &annotations[0].kind;
+    let references = references.iter().map(|it| (it.file_id, it.range)).collect::<Vec<_>>();
+    assert_eq!(format!("{references:?}"), "[(FileId(0), 31..38), (FileId(1), 18..25)]",);
+}
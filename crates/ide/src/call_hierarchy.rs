@@ -0,0 +1,87 @@
+//! Entry point for the node hierarchy: the "callers" of a node are the nodes
+//! whose rules reference it, and its "callees" are the nodes its rule
+//! references. Both may live in other files, connected by includes.
+
+#[cfg(test)]
+mod tests;
+
+use hir::NodeRef;
+use syntax::TextRange;
+
+use crate::{files_referring_to, FileAnalysis, FileId, FilePosition, NavigationTarget};
+
+#[derive(Debug, Clone)]
+pub struct CallItem {
+    pub target: NavigationTarget,
+    /// The references between the two nodes, in the rule of the caller.
+    pub ranges: Vec<TextRange>,
+}
+
+pub(crate) fn call_hierarchy(
+    file: &FileAnalysis<'_>,
+    position: FilePosition,
+) -> Option<Vec<NavigationTarget>> {
+    let (def_file, name) = node_at(file, position)?;
+    let target = NavigationTarget::from_name(file.db, def_file, &name)?;
+    Some(vec![target])
+}
+
+pub(crate) fn incoming_calls(
+    file: &FileAnalysis<'_>,
+    position: FilePosition,
+) -> Option<Vec<CallItem>> {
+    let (def_file, name) = node_at(file, position)?;
+    let mut res = Vec::new();
+    for file_id in files_referring_to(file.db, def_file, &name) {
+        let caller = FileAnalysis::new(file.db, file_id);
+        let Some(node) = caller.grammar.find_node(&name) else { continue };
+        let usages = caller.grammar.usages(node);
+        res.extend(group_calls(&caller, usages, |it| it.container));
+    }
+    Some(res)
+}
+
+pub(crate) fn outgoing_calls(
+    file: &FileAnalysis<'_>,
+    position: FilePosition,
+) -> Option<Vec<CallItem>> {
+    let (def_file, name) = node_at(file, position)?;
+    let callee = FileAnalysis::new(file.db, def_file);
+    let node = callee.grammar.find_node(&name)?;
+    let refs = callee.grammar.node_refs(node);
+    Some(group_calls(&callee, refs, |it| it.target))
+}
+
+/// Returns the name of the node at `position` and the file defining it.
+fn node_at(file: &FileAnalysis<'_>, position: FilePosition) -> Option<(FileId, String)> {
+    let token = file.token_at_offset(position.offset)?;
+    let node = file.resolve_node(&token.parent()?)?;
+    Some((file.def_file(node), file.grammar[node].name.clone()))
+}
+
+/// Groups the references in `file` by the node picked by `key`, in order of
+/// first appearance. Nodes without a definition can't be navigated to and
+/// are skipped.
+fn group_calls(
+    file: &FileAnalysis<'_>,
+    refs: Vec<NodeRef>,
+    key: impl Fn(&NodeRef) -> hir::Node,
+) -> Vec<CallItem> {
+    let mut groups: Vec<(hir::Node, Vec<TextRange>)> = Vec::new();
+    for node_ref in refs {
+        let range = file.source_map.rule_syntax(node_ref.rule).text_range();
+        let node = key(&node_ref);
+        match groups.iter_mut().find(|(it, _)| *it == node) {
+            Some((_, ranges)) => ranges.push(range),
+            None => groups.push((node, vec![range])),
+        }
+    }
+    groups
+        .into_iter()
+        .filter_map(|(node, ranges)| {
+            let name = &file.grammar[node].name;
+            let target = NavigationTarget::from_name(file.db, file.def_file(node), name)?;
+            Some(CallItem { target, ranges })
+        })
+        .collect()
+}
@@ -0,0 +1,125 @@
+use expect_test::{expect, Expect};
+use syntax::TextSize;
+
+use crate::{fixture, Analysis, CallItem, FilePosition, NavigationTarget};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let offset = ungrammar.find("$0").expect("no cursor marker");
+    let text = ungrammar.replacen("$0", "", 1);
+    let (analysis, file_id) = Analysis::from_single_file(text);
+    let position = FilePosition { file_id, offset: TextSize::from(offset as u32) };
+    check_at(&analysis, position, |it| format!("{} {:?}", it.name, it.full_range), expect);
+}
+
+/// Like [`check`], for a multi-file fixture. Targets are prefixed by their
+/// file.
+fn check_fixture(fixture: &str, expect: Expect) {
+    let (analysis, position) = fixture::position(fixture);
+    let render =
+        |it: &NavigationTarget| format!("{:?} {} {:?}", it.file_id, it.name, it.full_range);
+    check_at(&analysis, position, render, expect);
+}
+
+fn check_at(
+    analysis: &Analysis,
+    position: FilePosition,
+    render_target: impl Fn(&NavigationTarget) -> String,
+    expect: Expect,
+) {
+    let render = |items: Option<Vec<CallItem>>| {
+        items
+            .unwrap_or_default()
+            .iter()
+            .map(|it| format!("{} {:?}\n", render_target(&it.target), it.ranges))
+            .collect::<String>()
+    };
+    let mut actual = String::new();
+    for target in analysis.call_hierarchy(position).unwrap_or_default() {
+        actual.push_str(&format!("item: {} {:?}\n", render_target(&target), target.focus_range));
+    }
+    actual.push_str("incoming:\n");
+    actual.push_str(&render(analysis.incoming_calls(position)));
+    actual.push_str("outgoing:\n");
+    actual.push_str(&render(analysis.outgoing_calls(position)));
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn from_definition() {
+    check(
+        r#"
+Ex$0pr = Literal | BinExpr | ParenExpr
+BinExpr = lhs:Expr '+' rhs:Expr
+ParenExpr = '(' Expr ')'
+Literal = 'int'
+"#,
+        expect![[r#"
+            item: Expr 1..37 Some(1..5)
+            incoming:
+            BinExpr 38..69 [52..56, 65..69]
+            ParenExpr 70..94 [86..90]
+            outgoing:
+            Literal 95..110 [8..15]
+            BinExpr 38..69 [18..25]
+            ParenExpr 70..94 [28..37]
+        "#]],
+    );
+}
+
+#[test]
+fn from_reference() {
+    check(
+        r#"
+Expr = Literal | Bin$0Expr
+BinExpr = lhs:Expr '+' rhs:Expr
+"#,
+        expect![[r#"
+            item: BinExpr 26..57 Some(26..33)
+            incoming:
+            Expr 1..25 [18..25]
+            outgoing:
+            Expr 1..25 [40..44, 53..57]
+        "#]],
+    );
+}
+
+#[test]
+fn undefined_node() {
+    check(
+        r#"
+A = B$0
+"#,
+        expect![[r#"
+            incoming:
+            A 1..6 [5..6]
+            outgoing:
+        "#]],
+    );
+}
+
+#[test]
+fn across_includes() {
+    check_fixture(
+        r#"
+//- /grammar.ungram
+include 'expr.ungram'
+File = Ex$0pr*
+Stmt = Expr ';'
+//- /expr.ungram
+include 'tokens.ungram'
+Expr = Literal | Missing
+//- /tokens.ungram
+Literal = 'int'
+//- /other.ungram
+Other = Expr
+"#,
+        expect![[r#"
+            item: FileId(1) Expr 24..48 Some(24..28)
+            incoming:
+            FileId(0) File 22..34 [29..33]
+            FileId(0) Stmt 35..50 [42..46]
+            outgoing:
+            FileId(2) Literal 0..15 [31..38]
+        "#]],
+    );
+}
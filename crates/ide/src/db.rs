@@ -0,0 +1,57 @@
+//! The database of the analyzer, which combines the queries of all layers.
+
+use std::{fmt, sync::Arc};
+
+use base_db::{salsa, FileId, SourceDatabase, SourceDatabaseStorage};
+use hir::{HirDatabase, HirDatabaseStorage};
+use syntax::{Edition, LineEndings};
+
+use crate::{diagnostics, Diagnostic, LineIndex};
+
+#[salsa::database(SourceDatabaseStorage, HirDatabaseStorage, IdeDatabaseStorage)]
+pub struct RootDatabase {
+    storage: salsa::Storage<RootDatabase>,
+}
+
+impl Default for RootDatabase {
+    fn default() -> RootDatabase {
+        let mut db = RootDatabase { storage: Default::default() };
+        db.set_file_set(Arc::default());
+        db.set_default_edition(Edition::CURRENT);
+        db.set_line_endings(LineEndings::Unix);
+        db
+    }
+}
+
+impl fmt::Debug for RootDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RootDatabase").finish()
+    }
+}
+
+impl salsa::Database for RootDatabase {}
+
+impl salsa::ParallelDatabase for RootDatabase {
+    fn snapshot(&self) -> salsa::Snapshot<RootDatabase> {
+        salsa::Snapshot::new(RootDatabase { storage: self.storage.snapshot() })
+    }
+}
+
+#[salsa::query_group(IdeDatabaseStorage)]
+pub(crate) trait IdeDatabase: HirDatabase {
+    fn line_index(&self, file_id: FileId) -> Arc<LineIndex>;
+
+    /// All the diagnostics of the file, before filtering them by the config.
+    #[salsa::invoke(diagnostics::diagnostics_query)]
+    fn diagnostics(&self, file_id: FileId) -> Arc<[Diagnostic]>;
+
+    /// The diagnostics of the node `name`, with ranges relative to the start
+    /// of its definition, so they are only recomputed when it changes.
+    #[salsa::invoke(diagnostics::node_diagnostics_query)]
+    fn node_diagnostics(&self, file_id: FileId, name: String) -> Arc<[Diagnostic]>;
+}
+
+fn line_index(db: &dyn IdeDatabase, file_id: FileId) -> Arc<LineIndex> {
+    let text = db.file_text(file_id);
+    Arc::new(LineIndex::new(&text))
+}
@@ -0,0 +1,216 @@
+//! Collects diagnostics for a file: syntax errors and problems found in the
+//! semantic model of the grammar.
+
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use base_db::FileId;
+use hir::fields::FieldClashKind;
+use rustc_hash::FxHashSet;
+use syntax::{ast::AstToken, SyntaxErrorKind, SyntaxKind, TextRange, TextSize};
+
+use crate::{db::IdeDatabase, node_defs};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DiagnosticCode(pub &'static str);
+
+impl DiagnosticCode {
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub code: DiagnosticCode,
+    pub message: String,
+    pub range: TextRange,
+    pub severity: Severity,
+    pub fixes: Vec<Fix>,
+}
+
+/// A change which resolves a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub label: String,
+    /// Non-overlapping edits of the file of the diagnostic.
+    pub edits: Vec<TextEdit>,
+}
+
+/// Replaces the text at `range` with `insert`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub range: TextRange,
+    pub insert: String,
+}
+
+impl Diagnostic {
+    fn new(code: &'static str, message: impl Into<String>, range: TextRange) -> Diagnostic {
+        Diagnostic {
+            code: DiagnosticCode(code),
+            message: message.into(),
+            range,
+            severity: Severity::Error,
+            fixes: Vec::new(),
+        }
+    }
+
+    fn with_severity(self, severity: Severity) -> Diagnostic {
+        Diagnostic { severity, ..self }
+    }
+
+    fn with_fix(mut self, fix: Fix) -> Diagnostic {
+        self.fixes.push(fix);
+        self
+    }
+
+    /// Moves the diagnostic and its fixes by `offset`.
+    fn shifted(&self, offset: TextSize) -> Diagnostic {
+        let mut res = self.clone();
+        res.range += offset;
+        for edit in res.fixes.iter_mut().flat_map(|it| &mut it.edits) {
+            edit.range += offset;
+        }
+        res
+    }
+}
+
+#[derive(Default, Debug, Clone)]
+pub struct DiagnosticsConfig {
+    /// Codes of the diagnostics which should not be reported.
+    pub disabled: FxHashSet<String>,
+}
+
+pub(crate) fn diagnostics_query(db: &dyn IdeDatabase, file_id: FileId) -> Arc<[Diagnostic]> {
+    let _p = tracing::span!(tracing::Level::INFO, "diagnostics").entered();
+    let mut res = Vec::new();
+
+    syntax_errors(&mut res, db, file_id);
+    unresolved_includes(&mut res, db, file_id);
+    for (name, offset) in node_defs(db, file_id) {
+        res.extend(db.node_diagnostics(file_id, name).iter().map(|it| it.shifted(offset)));
+    }
+
+    res.into()
+}
+
+pub(crate) fn node_diagnostics_query(
+    db: &dyn IdeDatabase,
+    file_id: FileId,
+    name: String,
+) -> Arc<[Diagnostic]> {
+    let _p = tracing::span!(tracing::Level::INFO, "node_diagnostics", name).entered();
+    let mut res = Vec::new();
+    field_name_clashes(&mut res, db, file_id, name);
+    res.into()
+}
+
+fn syntax_errors(acc: &mut Vec<Diagnostic>, db: &dyn IdeDatabase, file_id: FileId) {
+    let text = db.file_text(file_id);
+    let parse = db.parse(file_id);
+    let mut stray_crs = Vec::new();
+    for err in parse.errors() {
+        if err.kind() == SyntaxErrorKind::InvalidLineEnding {
+            stray_crs.push(err.range());
+            continue;
+        }
+        acc.push(Diagnostic::new("syntax-error", err.to_string(), err.range()));
+    }
+    // The `\r`s of token literals are part of the token, not line endings.
+    let literals = parse
+        .syntax_node()
+        .descendants_with_tokens()
+        .filter_map(|it| it.into_token())
+        .filter(|it| it.kind() == SyntaxKind::STRING)
+        .map(|it| it.text_range())
+        .collect::<Vec<_>>();
+    invalid_line_endings(acc, &text, &stray_crs, &literals);
+}
+
+// Diagnostic: invalid-line-endings
+//
+// This diagnostic is triggered when a file contains `\r` characters which
+// aren't accepted as line endings. It is reported once per file, rather than
+// once per line, with a fix which converts all line endings to `\n`.
+fn invalid_line_endings(
+    acc: &mut Vec<Diagnostic>,
+    text: &str,
+    stray_crs: &[TextRange],
+    literals: &[TextRange],
+) {
+    let Some(&first) = stray_crs.first() else { return };
+    let message = match stray_crs.len() {
+        1 => "unexpected `\\r`, only Unix-style line endings allowed".to_owned(),
+        n => format!("unexpected `\\r` on {n} lines, only Unix-style line endings allowed"),
+    };
+    let edits = text
+        .match_indices('\r')
+        .map(|(offset, _)| TextRange::at(TextSize::from(offset as u32), TextSize::from(1)))
+        .filter(|range| !literals.iter().any(|it| it.contains_range(*range)))
+        .map(|range| {
+            let offset = usize::from(range.start());
+            let insert = if text[offset + 1..].starts_with('\n') { "" } else { "\n" };
+            TextEdit { range, insert: insert.to_owned() }
+        })
+        .collect();
+    let fix = Fix { label: "Convert line endings to `\\n`".to_owned(), edits };
+    acc.push(Diagnostic::new("invalid-line-endings", message, first).with_fix(fix));
+}
+
+// Diagnostic: unresolved-include
+//
+// This diagnostic is triggered when the path of an `include` doesn't point to
+// a file known to the analyzer. Paths are relative to the including file.
+fn unresolved_includes(acc: &mut Vec<Diagnostic>, db: &dyn IdeDatabase, file_id: FileId) {
+    let file_set = db.file_set();
+    for include in db.parse(file_id).tree().includes() {
+        let Some(path) = include.string() else { continue };
+        // Invalid escapes are reported as syntax errors.
+        let Some(path_text) = path.value() else { continue };
+        if file_set.resolve_path(file_id, &path_text).is_none() {
+            let message = format!("unresolved include `{path_text}`");
+            acc.push(Diagnostic::new("unresolved-include", message, path.syntax().text_range()));
+        }
+    }
+}
+
+// Diagnostic: field-name-clash
+//
+// This diagnostic is triggered when several atoms of a node infer the same
+// field name, so a generated accessor can't tell them apart.
+fn field_name_clashes(
+    acc: &mut Vec<Diagnostic>,
+    db: &dyn IdeDatabase,
+    file_id: FileId,
+    node_name: String,
+) {
+    let Some((_, source_map)) = db.node_grammar_with_source_map(file_id, node_name.clone()) else {
+        return;
+    };
+    for clash in db.node_fields(file_id, node_name.clone()).clashes.iter() {
+        let message = match clash.kind {
+            FieldClashKind::Repeated => format!(
+                "field `{}` of `{node_name}` is inferred {} times, add labels to tell them apart",
+                clash.name,
+                clash.atoms.len(),
+            ),
+            FieldClashKind::Conflicting => format!(
+                "field `{}` of `{node_name}` is used for values of different types",
+                clash.name,
+            ),
+        };
+        acc.extend(clash.atoms.iter().map(|&atom| {
+            let range = source_map.rule_syntax(atom).text_range();
+            Diagnostic::new("field-name-clash", message.clone(), range)
+                .with_severity(Severity::Warning)
+        }));
+    }
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use expect_test::{expect, Expect};
+
+use crate::{fixture, Analysis, AnalysisHost, Change, DiagnosticsConfig, FileId, LineEndings};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let (analysis, file_id) = Analysis::from_single_file(ungrammar.to_owned());
+    let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id);
+
+    let mut actual = String::new();
+    for d in diagnostics {
+        let text = &ungrammar[d.range];
+        actual.push_str(&format!(
+            "{:?} {} {:?} {:?}: {}\n",
+            d.severity,
+            d.code.as_str(),
+            d.range,
+            text,
+            d.message
+        ));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn syntax_errors() {
+    check(
+        r#"A = 'a' |"#,
+        expect![[r#"
+            Error syntax-error 9..9 "": expected rule
+        "#]],
+    );
+}
+
+#[test]
+fn escape_errors() {
+    check(
+        r#"A = 'a\zb\y' | ''"#,
+        expect![[r#"
+            Error syntax-error 6..8 "\\z": unknown character escape: `\z`, only `\\` and `\'` are allowed
+            Error syntax-error 9..11 "\\y": unknown character escape: `\y`, only `\\` and `\'` are allowed
+            Error syntax-error 15..17 "''": empty token literal
+        "#]],
+    );
+}
+
+#[test]
+fn field_name_clash() {
+    check(
+        r#"
+BinExpr = Expr '+' Expr
+Labeled = lhs:Expr '+' rhs:Expr
+Choice = Expr | 'x' Expr
+Conflict = Name | name:'ident'
+"#,
+        expect![[r#"
+            Warning field-name-clash 11..15 "Expr": field `expr` of `BinExpr` is inferred 2 times, add labels to tell them apart
+            Warning field-name-clash 20..24 "Expr": field `expr` of `BinExpr` is inferred 2 times, add labels to tell them apart
+            Warning field-name-clash 93..97 "Name": field `name` of `Conflict` is used for values of different types
+            Warning field-name-clash 105..112 "'ident'": field `name` of `Conflict` is used for values of different types
+        "#]],
+    );
+}
+
+#[test]
+fn disabled_diagnostics() {
+    let (analysis, file_id) = Analysis::from_single_file("A = B B".to_owned());
+    let mut config = DiagnosticsConfig::default();
+    assert_eq!(analysis.diagnostics(&config, file_id).len(), 2);
+    config.disabled.insert("field-name-clash".to_owned());
+    assert!(analysis.diagnostics(&config, file_id).is_empty());
+}
+
+#[test]
+fn unresolved_includes() {
+    let (analysis, files) = fixture::files(
+        r#"
+//- /src/grammar.ungram
+include 'tokens.ungram'
+include '../src/tokens.ungram'
+include 'missing.ungram'
+//- /src/tokens.ungram
+"#,
+    );
+    let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), files[0]);
+    let diagnostics =
+        diagnostics.iter().map(|d| format!("{} {:?}: {}", d.code.as_str(), d.range, d.message));
+    assert_eq!(
+        diagnostics.collect::<Vec<_>>(),
+        ["unresolved-include 63..79: unresolved include `missing.ungram`"]
+    );
+}
+
+#[test]
+fn invalid_line_endings() {
+    let text = "A = 'a'\r\nB = 'b'\r\n// c\r\nC = 'c'\r";
+    check(
+        text,
+        expect![[r#"
+            Error invalid-line-endings 7..8 "\r": unexpected `\r` on 3 lines, only Unix-style line endings allowed
+        "#]],
+    );
+
+    let (analysis, file_id) = Analysis::from_single_file(text.to_owned());
+    let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id);
+    let [fix] = &diagnostics[0].fixes[..] else { panic!("expected a single fix") };
+    let mut fixed = text.to_owned();
+    for edit in fix.edits.iter().rev() {
+        fixed.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.insert);
+    }
+    assert_eq!(fixed, "A = 'a'\nB = 'b'\n// c\nC = 'c'\n");
+}
+
+#[test]
+fn carriage_returns_in_token_literals() {
+    let text = "A = 'a\rb'\r\nB = 'b'\r\n";
+    check(
+        text,
+        expect![[r#"
+            Error syntax-error 6..7 "\r": bare CR not allowed in token literals
+            Error invalid-line-endings 9..10 "\r": unexpected `\r` on 2 lines, only Unix-style line endings allowed
+        "#]],
+    );
+
+    let (analysis, file_id) = Analysis::from_single_file(text.to_owned());
+    let diagnostics = analysis.diagnostics(&DiagnosticsConfig::default(), file_id);
+    let fix = diagnostics.iter().flat_map(|it| &it.fixes).next().unwrap();
+    let mut fixed = text.to_owned();
+    for edit in fix.edits.iter().rev() {
+        fixed.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.insert);
+    }
+    assert_eq!(fixed, "A = 'a\rb'\nB = 'b'\n");
+}
+
+#[test]
+fn accepted_crlf_line_endings() {
+    let mut host = AnalysisHost::new();
+    let mut change = Change::new();
+    let file_id = FileId(0);
+    change.change_file(file_id, Some(Arc::from("A = 'a'\r\nB = 'b'\r\n")));
+    change.set_line_endings(LineEndings::Crlf);
+    host.apply_change(change);
+    assert!(host.analysis().diagnostics(&DiagnosticsConfig::default(), file_id).is_empty());
+}
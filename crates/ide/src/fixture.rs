@@ -0,0 +1,58 @@
+//! Creates an [`Analysis`] of several files for tests.
+//!
+//! A fixture is a list of files, each starting with a `//- /path` line:
+//!
+//! ```text
+//! //- /grammar.ungram
+//! include 'tokens.ungram'
+//! File = Literal*
+//! //- /tokens.ungram
+//! Literal = 'int'
+//! ```
+//!
+//! One of the files may contain a `$0` cursor marker.
+
+use std::sync::Arc;
+
+use syntax::TextSize;
+use vfs::VfsPath;
+
+use crate::{Analysis, AnalysisHost, Change, FileId, FilePosition, FileSet};
+
+/// Returns the analysis and the ids of the files, in fixture order.
+pub(crate) fn files(fixture: &str) -> (Analysis, Vec<FileId>) {
+    let (analysis, file_ids, _) = parse(fixture);
+    (analysis, file_ids)
+}
+
+/// Like [`files`], but also returns the position of the `$0` marker.
+pub(crate) fn position(fixture: &str) -> (Analysis, FilePosition) {
+    let (analysis, _, position) = parse(fixture);
+    (analysis, position.expect("no cursor marker"))
+}
+
+fn parse(fixture: &str) -> (Analysis, Vec<FileId>, Option<FilePosition>) {
+    let mut host = AnalysisHost::new();
+    let mut change = Change::new();
+    let mut file_set = FileSet::default();
+    let mut file_ids = Vec::new();
+    let mut position = None;
+
+    for (idx, entry) in fixture.split("//- ").skip(1).enumerate() {
+        let (path, text) = entry.split_once('\n').unwrap_or((entry, ""));
+        let file_id = FileId(idx as u32);
+        let text = match text.find("$0") {
+            Some(offset) => {
+                position = Some(FilePosition { file_id, offset: TextSize::from(offset as u32) });
+                text.replacen("$0", "", 1)
+            }
+            None => text.to_owned(),
+        };
+        change.change_file(file_id, Some(Arc::from(text)));
+        file_set.insert(file_id, VfsPath::new_virtual_path(path.trim().to_owned()));
+        file_ids.push(file_id);
+    }
+    change.set_file_set(file_set);
+    host.apply_change(change);
+    (host.analysis(), file_ids, position)
+}
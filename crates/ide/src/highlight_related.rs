@@ -0,0 +1,93 @@
+//! Highlights the occurrences of the item under the cursor in the same file:
+//! the definition and references of a node, the uses of a token literal, or
+//! the uses of a label within the enclosing node definition.
+
+#[cfg(test)]
+mod tests;
+
+use hir::RuleData;
+use syntax::{
+    ast::{self, AstNode},
+    TextRange, TextSize,
+};
+
+use crate::FileAnalysis;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HighlightedRange {
+    pub range: TextRange,
+    /// `None` for occurrences which neither define nor reference a node.
+    pub category: Option<ReferenceCategory>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReferenceCategory {
+    /// The name of a node definition.
+    Write,
+    /// A reference to a node.
+    Read,
+}
+
+pub(crate) fn highlight_related(
+    file: &FileAnalysis<'_>,
+    offset: TextSize,
+) -> Vec<HighlightedRange> {
+    let _p = tracing::span!(tracing::Level::INFO, "highlight_related").entered();
+    let Some(token) = file.token_at_offset(offset) else { return Vec::new() };
+    let Some(parent) = token.parent() else { return Vec::new() };
+
+    let mut res = if let Some(token) = ast::Token::cast(parent.clone()) {
+        highlight_token(file, &token)
+    } else if let Some(label) = ast::Label::cast(parent.clone()) {
+        highlight_label(&label)
+    } else {
+        highlight_node(file, file.resolve_node(&parent))
+    };
+    res.sort_by_key(|it| it.range.start());
+    res
+}
+
+fn highlight_node(file: &FileAnalysis<'_>, node: Option<hir::Node>) -> Vec<HighlightedRange> {
+    let FileAnalysis { parse, grammar, source_map, .. } = file;
+    let Some(node) = node else { return Vec::new() };
+    let root = parse.syntax_node();
+
+    let def = source_map.node_def(node).and_then(|it| it.to_node(&root).name());
+    let def = def.map(|name| HighlightedRange {
+        range: name.syntax().text_range(),
+        category: Some(ReferenceCategory::Write),
+    });
+    let refs = grammar.usages(node).into_iter().map(|it| HighlightedRange {
+        range: source_map.rule_syntax(it.rule).text_range(),
+        category: Some(ReferenceCategory::Read),
+    });
+    def.into_iter().chain(refs).collect()
+}
+
+fn highlight_token(file: &FileAnalysis<'_>, token: &ast::Token) -> Vec<HighlightedRange> {
+    let FileAnalysis { grammar, source_map, .. } = file;
+    let Some(&RuleData::Token(target)) = file.resolve_rule(token.syntax()) else {
+        return Vec::new();
+    };
+    grammar
+        .rules()
+        .filter(|&rule| grammar[rule] == RuleData::Token(target))
+        .map(|rule| HighlightedRange {
+            range: source_map.rule_syntax(rule).text_range(),
+            category: None,
+        })
+        .collect()
+}
+
+fn highlight_label(label: &ast::Label) -> Vec<HighlightedRange> {
+    let Some(node) = label.syntax().ancestors().find_map(ast::Node::cast) else {
+        return Vec::new();
+    };
+    let text = label.text();
+    node.syntax()
+        .descendants()
+        .filter_map(ast::Label::cast)
+        .filter(|it| it.text() == text)
+        .map(|it| HighlightedRange { range: it.syntax().text_range(), category: None })
+        .collect()
+}
@@ -0,0 +1,113 @@
+use expect_test::{expect, Expect};
+use syntax::TextSize;
+
+use crate::{Analysis, FilePosition, ReferenceCategory};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let offset = ungrammar.find("$0").expect("no cursor marker");
+    let text = ungrammar.replacen("$0", "", 1);
+    let (analysis, file_id) = Analysis::from_single_file(text.clone());
+    let position = FilePosition { file_id, offset: TextSize::from(offset as u32) };
+
+    let mut actual = text;
+    for highlight in analysis.highlight_related(position).iter().rev() {
+        let category = match highlight.category {
+            Some(ReferenceCategory::Write) => "write",
+            Some(ReferenceCategory::Read) => "read",
+            None => "",
+        };
+        actual.insert_str(highlight.range.end().into(), &format!("</{category}>"));
+        actual.insert_str(highlight.range.start().into(), &format!("<{category}>"));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn node_from_definition() {
+    check(
+        r#"
+Ex$0pr = Literal | BinExpr
+BinExpr = lhs:Expr '+' rhs:Expr
+"#,
+        expect![[r#"
+
+            <write>Expr</write> = Literal | BinExpr
+            BinExpr = lhs:<read>Expr</read> '+' rhs:<read>Expr</read>
+        "#]],
+    );
+}
+
+#[test]
+fn node_from_reference() {
+    check(
+        r#"
+Expr = Literal | BinExpr
+BinExpr = lhs:Expr '+' rhs:$0Expr
+Literal = 'int'
+"#,
+        expect![[r#"
+
+            <write>Expr</write> = Literal | BinExpr
+            BinExpr = lhs:<read>Expr</read> '+' rhs:<read>Expr</read>
+            Literal = 'int'
+        "#]],
+    );
+}
+
+#[test]
+fn undefined_node() {
+    check(
+        r#"
+A = B$0 B
+"#,
+        expect![[r#"
+
+            A = <read>B</read> <read>B</read>
+        "#]],
+    );
+}
+
+#[test]
+fn token_literal() {
+    check(
+        r#"
+A = '+' B '+$0'
+B = ('+' | '-')*
+C = '\+'
+"#,
+        expect![[r#"
+
+            A = <>'+'</> B <>'+'</>
+            B = (<>'+'</> | '-')*
+            C = <>'\+'</>
+        "#]],
+    );
+}
+
+#[test]
+fn label_within_node() {
+    check(
+        r#"
+A = ex$0pr:B | expr:C
+B = expr:A
+"#,
+        expect![[r#"
+
+            A = <>expr</>:B | <>expr</>:C
+            B = expr:A
+        "#]],
+    );
+}
+
+#[test]
+fn nothing_on_punctuation() {
+    check(
+        r#"
+A $0= B
+"#,
+        expect![[r#"
+
+            A = B
+        "#]],
+    );
+}
@@ -0,0 +1,68 @@
+//! Inlay hints with the field names that code generators infer for unlabeled
+//! node references and tokens, e.g. `exprs:` in front of `Expr*`.
+
+#[cfg(test)]
+mod tests;
+
+use syntax::TextRange;
+
+use crate::{node_defs, FileAnalysis};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlayHintsConfig {
+    pub field_names: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        InlayHintsConfig { field_names: true }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlayKind {
+    FieldName,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InlayHintPosition {
+    Before,
+    After,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InlayHint {
+    /// The text range this inlay hint applies to.
+    pub range: TextRange,
+    pub position: InlayHintPosition,
+    pub kind: InlayKind,
+    pub label: String,
+}
+
+pub(crate) fn inlay_hints(file: &FileAnalysis<'_>, config: &InlayHintsConfig) -> Vec<InlayHint> {
+    let _p = tracing::span!(tracing::Level::INFO, "inlay_hints").entered();
+    let mut acc = Vec::new();
+    if config.field_names {
+        field_name_hints(&mut acc, file);
+    }
+    acc.sort_by_key(|hint| hint.range.start());
+    acc
+}
+
+fn field_name_hints(acc: &mut Vec<InlayHint>, file: &FileAnalysis<'_>) {
+    let FileAnalysis { db, file_id, .. } = *file;
+    for (name, offset) in node_defs(db, file_id) {
+        let Some((_, source_map)) = db.node_grammar_with_source_map(file_id, name.clone()) else {
+            continue;
+        };
+        let node_fields = db.node_fields(file_id, name);
+        for field in node_fields.fields.iter().filter(|it| !it.labeled) {
+            acc.extend(field.atoms.iter().map(|&atom| InlayHint {
+                range: source_map.rule_syntax(atom).text_range() + offset,
+                position: InlayHintPosition::Before,
+                kind: InlayKind::FieldName,
+                label: format!("{}:", field.name),
+            }));
+        }
+    }
+}
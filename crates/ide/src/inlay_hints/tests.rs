@@ -0,0 +1,59 @@
+use expect_test::{expect, Expect};
+
+use crate::{Analysis, InlayHintPosition, InlayHintsConfig};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let (analysis, file_id) = Analysis::from_single_file(ungrammar.to_owned());
+    let hints = analysis.inlay_hints(&InlayHintsConfig::default(), file_id);
+
+    let mut actual = ungrammar.to_owned();
+    for hint in hints.iter().rev() {
+        let offset = match hint.position {
+            InlayHintPosition::Before => hint.range.start(),
+            InlayHintPosition::After => hint.range.end(),
+        };
+        actual.insert_str(offset.into(), &format!("<{}>", hint.label));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn field_names() {
+    check(
+        r#"
+Fn = 'fn' Name ParamList ret:Type? Expr*
+"#,
+        expect![[r#"
+
+            Fn = <fn_token:>'fn' <name:>Name <param_list:>ParamList ret:Type? <exprs:>Expr*
+        "#]],
+    );
+}
+
+#[test]
+fn no_hints_for_enums() {
+    check(
+        r#"
+Item = Fn | Struct
+Fn = 'fn' | 'pub' 'fn'
+"#,
+        expect![[r#"
+
+            Item = Fn | Struct
+            Fn = <fn_token:>'fn' | <pub_token:>'pub' <fn_token:>'fn'
+        "#]],
+    );
+}
+
+#[test]
+fn separated_list() {
+    check(
+        r#"
+ArgList = '(' (Arg (',' Arg)*)? ')'
+"#,
+        expect![[r#"
+
+            ArgList = <l_paren_token:>'(' (<args:>Arg (',' <args:>Arg)*)? <r_paren_token:>')'
+        "#]],
+    );
+}
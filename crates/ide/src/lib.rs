@@ -0,0 +1,330 @@
+//! ide crate provides "ide-centric" APIs for the Ungrammar analyzer. That is,
+//! it generally operates with files and text ranges, and returns results as
+//! Strings, suitable for displaying to the human.
+//!
+//! What powers this API are the `syntax` crate, which provides the syntax
+//! tree, and the `hir` crate, which provides the semantic model of a grammar.
+//! Both are computed on demand by the queries of the [`RootDatabase`], which
+//! memoizes the results until the text of the files changes.
+
+mod annotations;
+mod call_hierarchy;
+mod db;
+mod diagnostics;
+#[cfg(test)]
+mod fixture;
+mod highlight_related;
+mod inlay_hints;
+mod line_index;
+mod navigation_target;
+
+use std::sync::Arc;
+
+use base_db::{
+    salsa::{self, ParallelDatabase},
+    SourceDatabase,
+};
+use hir::{Grammar, GrammarSourceMap, HirDatabase, RuleData};
+use rustc_hash::FxHashSet;
+
+use crate::db::IdeDatabase;
+use syntax::{
+    ast::{self, AstNode},
+    Parse, SyntaxKind, SyntaxNode, SyntaxNodePtr, SyntaxToken,
+};
+
+pub use crate::{
+    annotations::{Annotation, AnnotationConfig, AnnotationKind, Reference},
+    call_hierarchy::CallItem,
+    db::RootDatabase,
+    diagnostics::{Diagnostic, DiagnosticCode, DiagnosticsConfig, Fix, Severity, TextEdit},
+    highlight_related::{HighlightedRange, ReferenceCategory},
+    inlay_hints::{InlayHint, InlayHintPosition, InlayHintsConfig, InlayKind},
+    line_index::{LineCol, LineIndex, WideLineCol},
+    navigation_target::NavigationTarget,
+};
+pub use syntax::{Edition, LineEndings, SyntaxError, TextRange, TextSize};
+pub use vfs::{FileId, FileSet};
+
+/// A text offset in a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FilePosition {
+    pub file_id: FileId,
+    pub offset: TextSize,
+}
+
+/// The definition which the name of a node resolves to, as seen from a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VisibleNode {
+    pub file_id: FileId,
+    /// The syntax of the definition, detached from its file.
+    pub def: ast::Node,
+    /// The other visible files which define the node too, whose definitions
+    /// are hidden by this one.
+    pub shadowed: Vec<FileId>,
+}
+
+/// A batch of modifications to the files known to the analyzer.
+#[derive(Debug, Default)]
+pub struct Change {
+    files_changed: Vec<(FileId, Option<Arc<str>>)>,
+    file_set: Option<FileSet>,
+    default_edition: Option<Edition>,
+    line_endings: Option<LineEndings>,
+}
+
+impl Change {
+    pub fn new() -> Change {
+        Change::default()
+    }
+
+    /// Sets the text of `file_id`, or removes the file if `text` is `None`.
+    pub fn change_file(&mut self, file_id: FileId, text: Option<Arc<str>>) {
+        self.files_changed.push((file_id, text))
+    }
+
+    /// Replaces the paths of the files, which are used to resolve includes.
+    pub fn set_file_set(&mut self, file_set: FileSet) {
+        self.file_set = Some(file_set)
+    }
+
+    /// Sets the edition of the files which don't select one themselves.
+    pub fn set_default_edition(&mut self, edition: Edition) {
+        self.default_edition = Some(edition)
+    }
+
+    /// Sets the line endings accepted in the files.
+    pub fn set_line_endings(&mut self, line_endings: LineEndings) {
+        self.line_endings = Some(line_endings)
+    }
+}
+
+/// `AnalysisHost` stores the current state of the world.
+#[derive(Debug, Default)]
+pub struct AnalysisHost {
+    db: RootDatabase,
+}
+
+impl AnalysisHost {
+    pub fn new() -> AnalysisHost {
+        AnalysisHost::default()
+    }
+
+    /// Returns a snapshot of the current state, which you can query for
+    /// semantic information.
+    pub fn analysis(&self) -> Analysis {
+        Analysis { db: self.db.snapshot() }
+    }
+
+    /// Applies changes to the current state of the world. If there are
+    /// outstanding snapshots, they will be canceled.
+    pub fn apply_change(&mut self, change: Change) {
+        let _p = tracing::span!(tracing::Level::INFO, "apply_change").entered();
+        for (file_id, text) in change.files_changed {
+            // Salsa inputs can't be removed, a removed file is an empty one.
+            let text = text.unwrap_or_else(|| Arc::from(""));
+            self.db.set_file_text(file_id, text);
+        }
+        if let Some(file_set) = change.file_set {
+            self.db.set_file_set(Arc::new(file_set));
+        }
+        if let Some(edition) = change.default_edition {
+            self.db.set_default_edition(edition);
+        }
+        if let Some(line_endings) = change.line_endings {
+            self.db.set_line_endings(line_endings);
+        }
+    }
+}
+
+/// Analysis is a snapshot of a world state at a moment in time. It is the main
+/// entry point for asking semantic information about the world.
+#[derive(Debug)]
+pub struct Analysis {
+    db: salsa::Snapshot<RootDatabase>,
+}
+
+impl Analysis {
+    /// Creates an analysis instance for a single file, without any external
+    /// dependencies. Useful for testing and for one-off tools.
+    pub fn from_single_file(text: String) -> (Analysis, FileId) {
+        let file_id = FileId(0);
+        let mut host = AnalysisHost::new();
+        let mut change = Change::new();
+        change.change_file(file_id, Some(Arc::from(text)));
+        host.apply_change(change);
+        (host.analysis(), file_id)
+    }
+
+    /// Gets the text of the source file.
+    pub fn file_text(&self, file_id: FileId) -> Arc<str> {
+        self.db.file_text(file_id)
+    }
+
+    /// Gets the syntax tree of the file.
+    pub fn parse(&self, file_id: FileId) -> ast::Grammar {
+        self.db.parse(file_id).tree()
+    }
+
+    /// Gets the syntax errors of the file.
+    pub fn syntax_errors(&self, file_id: FileId) -> Vec<SyntaxError> {
+        self.db.parse(file_id).errors().to_vec()
+    }
+
+    /// Returns the nodes visible from the file with the definitions their
+    /// names resolve to, in the order the definitions are looked up.
+    pub fn visible_nodes(&self, file_id: FileId) -> Vec<VisibleNode> {
+        visible_nodes(&*self.db, file_id)
+    }
+
+    /// Gets the file's `LineIndex`: data structure to convert between absolute
+    /// offsets and line/column representation.
+    pub fn file_line_index(&self, file_id: FileId) -> Arc<LineIndex> {
+        self.db.line_index(file_id)
+    }
+
+    /// Returns the field names inferred for unlabeled atoms.
+    pub fn inlay_hints(&self, config: &InlayHintsConfig, file_id: FileId) -> Vec<InlayHint> {
+        inlay_hints::inlay_hints(&self.file(file_id), config)
+    }
+
+    /// Returns the annotations for the node definitions of the file.
+    pub fn annotations(&self, config: &AnnotationConfig, file_id: FileId) -> Vec<Annotation> {
+        annotations::annotations(&self.file(file_id), config)
+    }
+
+    /// Returns the ranges related to the name, token or label at `position`.
+    pub fn highlight_related(&self, position: FilePosition) -> Vec<HighlightedRange> {
+        highlight_related::highlight_related(&self.file(position.file_id), position.offset)
+    }
+
+    /// Returns the node at `position`, to show its hierarchy. The node may be
+    /// defined in an included file.
+    pub fn call_hierarchy(&self, position: FilePosition) -> Option<Vec<NavigationTarget>> {
+        call_hierarchy::call_hierarchy(&self.file(position.file_id), position)
+    }
+
+    /// Returns the nodes whose rules reference the node at `position`, in all
+    /// the files which see its definition.
+    pub fn incoming_calls(&self, position: FilePosition) -> Option<Vec<CallItem>> {
+        call_hierarchy::incoming_calls(&self.file(position.file_id), position)
+    }
+
+    /// Returns the nodes referenced from the rule of the node at `position`.
+    pub fn outgoing_calls(&self, position: FilePosition) -> Option<Vec<CallItem>> {
+        call_hierarchy::outgoing_calls(&self.file(position.file_id), position)
+    }
+
+    /// Computes the set of diagnostics for the given file.
+    pub fn diagnostics(&self, config: &DiagnosticsConfig, file_id: FileId) -> Vec<Diagnostic> {
+        let mut res = self.db.diagnostics(file_id).to_vec();
+        res.retain(|d| !config.disabled.contains(d.code.as_str()));
+        res
+    }
+
+    fn file(&self, file_id: FileId) -> FileAnalysis<'_> {
+        FileAnalysis::new(&*self.db, file_id)
+    }
+}
+
+/// The syntax tree and the semantic model of a single file.
+pub(crate) struct FileAnalysis<'a> {
+    db: &'a dyn HirDatabase,
+    file_id: FileId,
+    parse: Parse<ast::Grammar>,
+    grammar: Arc<Grammar>,
+    source_map: Arc<GrammarSourceMap>,
+}
+
+impl<'a> FileAnalysis<'a> {
+    fn new(db: &'a dyn HirDatabase, file_id: FileId) -> FileAnalysis<'a> {
+        let parse = db.parse(file_id);
+        let (grammar, source_map) = db.grammar_with_source_map(file_id);
+        FileAnalysis { db, file_id, parse, grammar, source_map }
+    }
+
+    /// Returns the most interesting token at `offset`, preferring names and
+    /// literals over punctuation and trivia.
+    fn token_at_offset(&self, offset: TextSize) -> Option<SyntaxToken> {
+        self.parse.syntax_node().token_at_offset(offset).max_by_key(|token| match token.kind() {
+            SyntaxKind::IDENT | SyntaxKind::STRING => 2,
+            SyntaxKind::WHITESPACE | SyntaxKind::COMMENT | SyntaxKind::DOC_COMMENT => 0,
+            _ => 1,
+        })
+    }
+
+    /// Returns the rule lowered from `syntax`.
+    fn resolve_rule(&self, syntax: &SyntaxNode) -> Option<&RuleData> {
+        let rule = self.source_map.syntax_rule(&SyntaxNodePtr::new(syntax))?;
+        Some(&self.grammar[rule])
+    }
+
+    /// Returns the file which defines `node`. Nodes which aren't defined
+    /// anywhere belong to this file.
+    fn def_file(&self, node: hir::Node) -> FileId {
+        let name = self.grammar[node].name.clone();
+        self.db.resolve_node(self.file_id, name).unwrap_or(self.file_id)
+    }
+
+    /// Resolves a `NAME` of a definition or a `NAME_REF` to its node.
+    fn resolve_node(&self, syntax: &SyntaxNode) -> Option<hir::Node> {
+        if let Some(name) = ast::Name::cast(syntax.clone()) {
+            return self.grammar.find_node(&name.text());
+        }
+        let name_ref = ast::NameRef::cast(syntax.clone())?;
+        match self.resolve_rule(name_ref.syntax())? {
+            RuleData::Node(node) => Some(*node),
+            _ => None,
+        }
+    }
+}
+
+/// Returns the nodes defined in the file with the offset of their first
+/// definition, which the ranges of the per-node queries are relative to.
+fn node_defs(db: &dyn HirDatabase, file_id: FileId) -> Vec<(String, TextSize)> {
+    let mut seen = FxHashSet::default();
+    db.parse(file_id)
+        .tree()
+        .nodes()
+        .filter_map(|node| {
+            let name = node.name()?.text();
+            let offset = node.syntax().text_range().start();
+            seen.insert(name.clone()).then_some((name, offset))
+        })
+        .collect()
+}
+
+fn visible_nodes(db: &dyn HirDatabase, file_id: FileId) -> Vec<VisibleNode> {
+    let files = db.visible_files(file_id);
+    let mut seen = FxHashSet::default();
+    let mut res = Vec::new();
+    for &visible in files.iter() {
+        for name in db.node_index(visible).names() {
+            if !seen.insert(name.to_owned()) {
+                continue;
+            }
+            let Some(def_file) = db.resolve_node(file_id, name.to_owned()) else { continue };
+            let Some(def) = db.node_def(def_file, name.to_owned()) else { continue };
+            let Some(def) = ast::Node::cast(SyntaxNode::new_root(def)) else { continue };
+            let shadowed = files
+                .iter()
+                .copied()
+                .filter(|&it| it != def_file && db.node_index(it).position(name).is_some())
+                .collect();
+            res.push(VisibleNode { file_id: def_file, def, shadowed });
+        }
+    }
+    res
+}
+
+/// Returns the files in which the node `name` is the one of `def_file`,
+/// either because they define it or because they see its definition through
+/// an include.
+fn files_referring_to(db: &dyn HirDatabase, def_file: FileId, name: &str) -> Vec<FileId> {
+    let mut res = db.file_set().iter().collect::<Vec<_>>();
+    res.push(def_file);
+    res.sort();
+    res.dedup();
+    res.retain(|&file_id| db.resolve_node(file_id, name.to_owned()).unwrap_or(file_id) == def_file);
+    res
+}
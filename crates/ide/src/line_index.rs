@@ -0,0 +1,162 @@
+//! `LineIndex` maps flat `TextSize` offsets into `(Line, Column)`
+//! representation.
+
+#[cfg(test)]
+mod tests;
+
+use rustc_hash::FxHashMap;
+use syntax::{TextRange, TextSize};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    /// Offset the beginning of each line, zero-based.
+    newlines: Vec<TextSize>,
+    /// List of non-ASCII characters on each line.
+    line_wide_chars: FxHashMap<u32, Vec<WideChar>>,
+    len: TextSize,
+}
+
+/// Line/Column information in native, utf8 format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LineCol {
+    /// Zero-based
+    pub line: u32,
+    /// Zero-based utf8 offset
+    pub col: u32,
+}
+
+/// Line/Column information in utf16 format, as used by the LSP.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct WideLineCol {
+    /// Zero-based
+    pub line: u32,
+    /// Zero-based utf16 offset
+    pub col: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct WideChar {
+    /// Start offset of a character inside a line, zero-based
+    start: TextSize,
+    /// End offset of a character inside a line, zero-based
+    end: TextSize,
+}
+
+impl WideChar {
+    /// Returns the length in 8-bit UTF-8 code units.
+    fn len(&self) -> TextSize {
+        self.end - self.start
+    }
+
+    /// Returns the length in 16-bit UTF-16 code units.
+    fn wide_len(&self) -> u32 {
+        if self.len() == TextSize::from(4) {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> LineIndex {
+        let mut line_wide_chars = FxHashMap::default();
+        let mut wide_chars = Vec::new();
+
+        let mut newlines = vec![TextSize::from(0)];
+
+        let mut curr_row = TextSize::from(0);
+        let mut curr_col = TextSize::from(0);
+        let mut line = 0;
+        for c in text.chars() {
+            let c_len = TextSize::of(c);
+            curr_row += c_len;
+            if c == '\n' {
+                newlines.push(curr_row);
+
+                // Save any utf-16 characters seen in the previous line
+                if !wide_chars.is_empty() {
+                    line_wide_chars.insert(line, std::mem::take(&mut wide_chars));
+                }
+
+                // Prepare for processing the next line
+                curr_col = TextSize::from(0);
+                line += 1;
+                continue;
+            }
+
+            if !c.is_ascii() {
+                wide_chars.push(WideChar { start: curr_col, end: curr_col + c_len });
+            }
+
+            curr_col += c_len;
+        }
+
+        // Save any utf-16 characters seen in the last line
+        if !wide_chars.is_empty() {
+            line_wide_chars.insert(line, wide_chars);
+        }
+
+        LineIndex { newlines, line_wide_chars, len: TextSize::of(text) }
+    }
+
+    /// Returns the length of the original text.
+    pub fn len(&self) -> TextSize {
+        self.len
+    }
+
+    pub fn line_col(&self, offset: TextSize) -> LineCol {
+        let line = self.newlines.partition_point(|&it| it <= offset) - 1;
+        let line_start_offset = self.newlines[line];
+        let col = offset - line_start_offset;
+        LineCol { line: line as u32, col: col.into() }
+    }
+
+    pub fn offset(&self, line_col: LineCol) -> Option<TextSize> {
+        self.newlines
+            .get(line_col.line as usize)
+            .map(|offset| offset + TextSize::from(line_col.col))
+            .filter(|&offset| offset <= self.len)
+    }
+
+    pub fn to_wide(&self, line_col: LineCol) -> WideLineCol {
+        let mut res = line_col.col;
+        if let Some(wide_chars) = self.line_wide_chars.get(&line_col.line) {
+            for c in wide_chars {
+                if c.end <= TextSize::from(line_col.col) {
+                    res -= u32::from(c.len()) - c.wide_len();
+                } else {
+                    // From here on, all utf16 characters come *after* the
+                    // character we are mapping, so we don't need to take them
+                    // into account
+                    break;
+                }
+            }
+        }
+        WideLineCol { line: line_col.line, col: res }
+    }
+
+    pub fn to_utf8(&self, line_col: WideLineCol) -> LineCol {
+        let mut col = line_col.col;
+        if let Some(wide_chars) = self.line_wide_chars.get(&line_col.line) {
+            for c in wide_chars {
+                if col > u32::from(c.start) {
+                    col += u32::from(c.len()) - c.wide_len();
+                } else {
+                    // From here on, all utf16 characters come *after* the
+                    // character we are mapping, so we don't need to take them
+                    // into account
+                    break;
+                }
+            }
+        }
+        LineCol { line: line_col.line, col }
+    }
+
+    /// Returns the range of `line`, including the trailing newline.
+    pub fn line(&self, line: u32) -> Option<TextRange> {
+        let start = *self.newlines.get(line as usize)?;
+        let end = self.newlines.get(line as usize + 1).copied().unwrap_or(self.len);
+        Some(TextRange::new(start, end))
+    }
+}
@@ -0,0 +1,30 @@
+use syntax::TextSize;
+
+use super::{LineCol, LineIndex, WideLineCol};
+
+#[test]
+fn test_line_index() {
+    let text = "hello\nworld";
+    let index = LineIndex::new(text);
+    let table =
+        [(0, 0, 0), (1, 0, 1), (5, 0, 5), (6, 1, 0), (7, 1, 1), (8, 1, 2), (10, 1, 4), (11, 1, 5)];
+    for (offset, line, col) in table {
+        let line_col = index.line_col(offset.into());
+        assert_eq!(line_col, LineCol { line, col });
+        assert_eq!(index.offset(line_col), Some(TextSize::from(offset)));
+    }
+    assert_eq!(index.offset(LineCol { line: 2, col: 0 }), None);
+}
+
+#[test]
+fn test_wide_columns() {
+    let text = "A = 'ы' '🦀' B\n";
+    let index = LineIndex::new(text);
+
+    // `B` is at byte 16 but at utf16 column 13.
+    let line_col = index.line_col(TextSize::from(16));
+    assert_eq!(line_col, LineCol { line: 0, col: 16 });
+    let wide = index.to_wide(line_col);
+    assert_eq!(wide, WideLineCol { line: 0, col: 13 });
+    assert_eq!(index.to_utf8(wide), line_col);
+}
@@ -0,0 +1,54 @@
+//! See [`NavigationTarget`].
+
+use hir::HirDatabase;
+use syntax::{ast::AstNode, TextRange};
+
+use crate::{FileAnalysis, FileId};
+
+/// `NavigationTarget` represents an element in the editor's UI which you can
+/// click on to navigate to a particular piece of code.
+///
+/// Typically, a `NavigationTarget` corresponds to some element in the source
+/// code, like a node definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NavigationTarget {
+    pub file_id: FileId,
+    /// Range which encompasses the whole element, e.g. the whole definition.
+    pub full_range: TextRange,
+    /// The name of the element, which the editor selects when navigating.
+    pub focus_range: Option<TextRange>,
+    pub name: String,
+}
+
+impl NavigationTarget {
+    /// Returns the target for the definition of `node`, or `None` if the node
+    /// is only referenced.
+    pub(crate) fn from_node(
+        file: &FileAnalysis<'_>,
+        file_id: FileId,
+        node: hir::Node,
+    ) -> Option<NavigationTarget> {
+        let def = file.source_map.node_def(node)?.to_node(&file.parse.syntax_node());
+        Some(NavigationTarget {
+            file_id,
+            full_range: def.syntax().text_range(),
+            focus_range: def.name().map(|it| it.syntax().text_range()),
+            name: file.grammar[node].name.clone(),
+        })
+    }
+
+    /// Returns the target for the definition of the node `name` in `file_id`.
+    pub(crate) fn from_name(
+        db: &dyn HirDatabase,
+        file_id: FileId,
+        name: &str,
+    ) -> Option<NavigationTarget> {
+        let file = FileAnalysis::new(db, file_id);
+        let node = file.grammar.find_node(name)?;
+        NavigationTarget::from_node(&file, file_id, node)
+    }
+
+    pub fn focus_or_full_range(&self) -> TextRange {
+        self.focus_range.unwrap_or(self.full_range)
+    }
+}
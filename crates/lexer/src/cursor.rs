@@ -1,5 +1,7 @@
 use std::str::Chars;
 
+use crate::LineEndings;
+
 /// Peekable iterator over a char sequence.
 ///
 /// Next characters can be peeked via `first` method,
@@ -8,6 +10,7 @@ pub struct Cursor<'a> {
     len_remaining: usize,
     /// Iterator over chars. Slightly faster than a &str.
     chars: Chars<'a>,
+    pub(crate) line_endings: LineEndings,
     #[cfg(debug_assertions)]
     prev: char,
 }
@@ -19,6 +22,7 @@ impl<'a> Cursor<'a> {
         Cursor {
             len_remaining: input.len(),
             chars: input.chars(),
+            line_endings: LineEndings::Unix,
             #[cfg(debug_assertions)]
             prev: EOF_CHAR,
         }
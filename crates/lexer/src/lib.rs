@@ -37,6 +37,17 @@ pub enum TokenKind {
     /// `// comment`
     LineComment,
 
+    /// `/// doc comment`, which documents the item following it.
+    DocComment,
+
+    /// `/* block comment */`
+    ///
+    /// Block comments can be nested, so a comment can wrap a part of the
+    /// grammar that already contains comments.
+    BlockComment {
+        terminated: bool,
+    },
+
     /// Any whitespace character sequence.
     Whitespace,
 
@@ -72,9 +83,29 @@ pub enum TokenKind {
     Eof,
 }
 
+/// The line endings accepted by the lexer.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineEndings {
+    /// Only `\n`, every `\r` is a [`TokenKind::InvalidLineEnding`].
+    #[default]
+    Unix,
+    /// Both `\n` and `\r\n`. A `\r` which isn't followed by `\n` is still
+    /// invalid.
+    Crlf,
+}
+
 /// Creates an iterator that produces tokens from the input string.
 pub fn tokenize(input: &str) -> impl Iterator<Item = Token> + '_ {
+    tokenize_with_line_endings(input, LineEndings::Unix)
+}
+
+/// Like [`tokenize`], accepting the given line endings.
+pub fn tokenize_with_line_endings(
+    input: &str,
+    line_endings: LineEndings,
+) -> impl Iterator<Item = Token> + '_ {
     let mut cursor = Cursor::new(input);
+    cursor.line_endings = line_endings;
     std::iter::from_fn(move || {
         let token = cursor.advance_token();
         if token.kind != TokenKind::Eof {
@@ -108,6 +139,7 @@ impl Cursor<'_> {
             // Slash, comment or block comment.
             '/' => match self.first() {
                 '/' => self.line_comment(),
+                '*' => self.block_comment(),
                 _ => TokenKind::Unknown,
             },
 
@@ -129,6 +161,7 @@ impl Cursor<'_> {
 
             // String literal.
             '\'' => TokenKind::Str { terminated: self.quoted_string() },
+            '\r' if self.at_crlf_tail() => self.whitespace(),
             '\r' => TokenKind::InvalidLineEnding,
             _ => TokenKind::Unknown,
         };
@@ -141,16 +174,75 @@ impl Cursor<'_> {
         debug_assert!(self.prev() == '/' && self.first() == '/');
         self.bump();
 
-        self.eat_while(|c| c != '\n');
-        TokenKind::LineComment
+        // `////` is an ordinary comment, like in Rust.
+        let is_doc = self.first() == '/' && self.second() != '/';
+        loop {
+            self.eat_while(|c| c != '\n' && c != '\r');
+            // An accepted `\r\n` ends the comment, any other `\r` is a part
+            // of it.
+            if self.first() == '\r' && !(self.second() == '\n' && self.accepts_crlf()) {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+        if is_doc {
+            TokenKind::DocComment
+        } else {
+            TokenKind::LineComment
+        }
+    }
+
+    fn block_comment(&mut self) -> TokenKind {
+        debug_assert!(self.prev() == '/' && self.first() == '*');
+        self.bump();
+
+        let mut depth = 1usize;
+        while let Some(c) = self.bump() {
+            match c {
+                '/' if self.first() == '*' => {
+                    self.bump();
+                    depth += 1;
+                }
+                '*' if self.first() == '/' => {
+                    self.bump();
+                    depth -= 1;
+                    if depth == 0 {
+                        // This block comment is closed, so for a construction like "/* */ */"
+                        // there will be a successfully parsed block comment "/* */"
+                        // and " */" will be processed separately.
+                        break;
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        TokenKind::BlockComment { terminated: depth == 0 }
     }
 
     fn whitespace(&mut self) -> TokenKind {
-        debug_assert!(is_whitespace(self.prev()));
-        self.eat_while(is_whitespace);
+        debug_assert!(is_whitespace(self.prev()) || self.prev() == '\r');
+        loop {
+            self.eat_while(is_whitespace);
+            if self.first() == '\r' && self.second() == '\n' && self.accepts_crlf() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
         TokenKind::Whitespace
     }
 
+    /// Checks if the `\r` just consumed starts an accepted `\r\n`.
+    fn at_crlf_tail(&self) -> bool {
+        self.first() == '\n' && self.accepts_crlf()
+    }
+
+    fn accepts_crlf(&self) -> bool {
+        self.line_endings == LineEndings::Crlf
+    }
+
     fn ident(&mut self) -> TokenKind {
         debug_assert!(is_ident_char(self.prev()));
         // Start is already eaten, eat the rest of identifier.
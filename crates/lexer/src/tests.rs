@@ -24,7 +24,7 @@ Person =
 Greeting =
   'Hello' person:Person '!'"#,
         expect![[r#"
-            Token { kind: LineComment, len: 28 }
+            Token { kind: DocComment, len: 28 }
             Token { kind: Whitespace, len: 1 }
             Token { kind: Ident, len: 5 }
             Token { kind: Whitespace, len: 1 }
@@ -67,3 +67,67 @@ Greeting =
         "#]],
     )
 }
+
+#[test]
+fn doc_comments() {
+    check_lexing(
+        "// comment\n/// doc\n////not doc\n///",
+        expect![[r#"
+            Token { kind: LineComment, len: 10 }
+            Token { kind: Whitespace, len: 1 }
+            Token { kind: DocComment, len: 7 }
+            Token { kind: Whitespace, len: 1 }
+            Token { kind: LineComment, len: 11 }
+            Token { kind: Whitespace, len: 1 }
+            Token { kind: DocComment, len: 3 }
+        "#]],
+    );
+}
+
+#[test]
+fn nested_block_comments() {
+    check_lexing(
+        "/* a /* b */ c */ A /* unterminated /* */",
+        expect![[r#"
+            Token { kind: BlockComment { terminated: true }, len: 17 }
+            Token { kind: Whitespace, len: 1 }
+            Token { kind: Ident, len: 1 }
+            Token { kind: Whitespace, len: 1 }
+            Token { kind: BlockComment { terminated: false }, len: 21 }
+        "#]],
+    );
+}
+
+#[test]
+fn crlf_line_endings() {
+    let lex = |line_endings| {
+        tokenize_with_line_endings("A\r\n\r\n B\r// c\r\n", line_endings)
+            .map(|it| format!("{:?} {}", it.kind, it.len))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        lex(LineEndings::Unix),
+        [
+            "Ident 1",
+            "InvalidLineEnding 1",
+            "Whitespace 1",
+            "InvalidLineEnding 1",
+            "Whitespace 2",
+            "Ident 1",
+            "InvalidLineEnding 1",
+            "LineComment 5",
+            "Whitespace 1",
+        ]
+    );
+    assert_eq!(
+        lex(LineEndings::Crlf),
+        [
+            "Ident 1",
+            "Whitespace 5",
+            "Ident 1",
+            "InvalidLineEnding 1",
+            "LineComment 4",
+            "Whitespace 2"
+        ]
+    );
+}
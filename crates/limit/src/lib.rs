@@ -1,5 +1,6 @@
 //! limit defines a struct to enforce limits.
 
+use std::ops;
 #[cfg(feature = "tracking")]
 use std::sync::atomic::AtomicUsize;
 
@@ -43,6 +44,21 @@ impl Limit {
         self.upper_bound
     }
 
+    /// Returns the largest value which passed the check so far, or `None` if
+    /// the limit isn't tracking.
+    #[inline]
+    pub fn max(&self) -> Option<usize> {
+        #[cfg(feature = "tracking")]
+        {
+            let max = self.max.load(std::sync::atomic::Ordering::Relaxed);
+            // `new_tracking` starts at 1, so 0 means tracking is off.
+            if max != 0 {
+                return Some(max);
+            }
+        }
+        None
+    }
+
     /// Checks whether the given value is below the limit.
     /// Returns `Ok` when `other` is below `self`, and `Err` otherwise.
     #[inline]
@@ -69,3 +85,49 @@ impl Limit {
         }
     }
 }
+
+/// Names limits which are checked together, e.g. the limits of a single
+/// parse, so that their tracked maxima can be reported.
+#[derive(Debug, Default)]
+pub struct LimitRegistry {
+    limits: Vec<(&'static str, Limit)>,
+}
+
+/// Refers to a limit of a [`LimitRegistry`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LimitId(usize);
+
+/// A snapshot of a named limit, see [`LimitRegistry::stats`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LimitStats {
+    pub name: &'static str,
+    pub upper_bound: usize,
+    /// The largest value which passed the check, if the limit is tracking.
+    pub max: Option<usize>,
+}
+
+impl LimitRegistry {
+    pub fn register(&mut self, name: &'static str, limit: Limit) -> LimitId {
+        self.limits.push((name, limit));
+        LimitId(self.limits.len() - 1)
+    }
+
+    /// Returns the limits in registration order.
+    pub fn stats(&self) -> Vec<LimitStats> {
+        self.limits
+            .iter()
+            .map(|&(name, ref limit)| LimitStats {
+                name,
+                upper_bound: limit.inner(),
+                max: limit.max(),
+            })
+            .collect()
+    }
+}
+
+impl ops::Index<LimitId> for LimitRegistry {
+    type Output = Limit;
+    fn index(&self, LimitId(index): LimitId) -> &Limit {
+        &self.limits[index].1
+    }
+}
@@ -1,15 +1,21 @@
-//! The edition of the Rust language used in a crate.
-// Ideally this would be defined in the span crate, but the dependency chain is all over the place
-// wrt to span, parser and syntax.
+//! The edition of the Ungrammar language used in a file.
+//!
+//! Editions let the language grow without changing the meaning of existing
+//! files: new syntax is only accepted in the edition which introduced it. A
+//! file selects its edition with an `// edition: N` comment before the first
+//! item, see [`LexedStr::edition_marker`](crate::LexedStr::edition_marker).
 use std::fmt;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Edition {
     V1,
+    /// Adds `include 'path'` items.
+    V2,
 }
 
 impl Edition {
-    pub const CURRENT: Edition = Edition::V1;
+    /// The latest edition, used for files which don't select one.
+    pub const CURRENT: Edition = Edition::V2;
     pub const DEFAULT: Edition = Edition::V1;
 }
 
@@ -31,6 +37,7 @@ impl std::str::FromStr for Edition {
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let res = match s {
             "1" => Edition::V1,
+            "2" => Edition::V2,
             _ => return Err(ParseEditionError { invalid_input: s.to_owned() }),
         };
         Ok(res)
@@ -41,6 +48,7 @@ impl fmt::Display for Edition {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.write_str(match self {
             Edition::V1 => "1",
+            Edition::V2 => "2",
         })
     }
 }
@@ -33,6 +33,7 @@ mod rules;
 
 use crate::{
     parser::{CompletedMarker, Marker, Parser},
+    Edition,
     SyntaxKind::{self, *},
     TokenSet, T,
 };
@@ -46,6 +47,7 @@ pub(crate) mod entry {
         pub(crate) fn grammar(p: &mut Parser<'_>) {
             let m = p.start();
             items::grammar_contents(p);
+            p.recover_from_abort();
             m.complete(p, GRAMMAR);
         }
     }
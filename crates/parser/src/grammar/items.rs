@@ -30,12 +30,25 @@ pub(super) fn item(p: &mut Parser<'_>) {
 /// Try to parse an item, completing `m` in case of success.
 fn opt_item(p: &mut Parser<'_>, m: Marker) -> Result<(), Marker> {
     match p.current() {
+        IDENT if p.at_contextual_kw(T![include]) && p.nth_at(1, STRING) => include(p, m),
         IDENT | T![=] => node(p, m),
         _ => return Err(m),
     };
     Ok(())
 }
 
+// test node_doc_comments
+// /// The root of a file.
+// ///
+// /// Contains items.
+// // A regular comment.
+// SourceFile = Item*
+//
+// // Detached by the blank line.
+//
+// /// Attached despite the blank line.
+//
+// Item = 'item'
 fn node(p: &mut Parser<'_>, m: Marker) {
     // test_err recovery_node_name
     // Hello = 'Hello'
@@ -51,3 +64,21 @@ fn node(p: &mut Parser<'_>, m: Marker) {
     rules::rule(p);
     m.complete(p, NODE);
 }
+
+// test include
+// include 'tokens.ungram'
+// SourceFile = Item*
+// include 'items.ungram'
+// include = 'include'
+fn include(p: &mut Parser<'_>, m: Marker) {
+    // test_err include_in_edition_1
+    // // edition: 1
+    // include 'tokens.ungram'
+    // SourceFile = Item* include 'items.ungram'
+    if p.edition() < Edition::V2 {
+        p.error("`include` items require edition 2 or later");
+    }
+    p.bump_remap(T![include]);
+    p.bump(STRING);
+    m.complete(p, INCLUDE);
+}
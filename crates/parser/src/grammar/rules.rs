@@ -13,6 +13,8 @@ mod bp {
     pub(crate) const SEQ: u8 = 2;
     pub(crate) const OPT: u8 = 3;
     pub(crate) const REP: u8 = 3;
+    /// Binds a single atom together with its postfix operators.
+    pub(crate) const POSTFIX: u8 = 3;
 }
 
 pub(super) fn rule(p: &mut Parser<'_>) -> Option<CompletedMarker> {
@@ -52,6 +54,19 @@ fn current_op(p: &Parser<'_>) -> (u8, SyntaxKind, Associativity) {
 
 // Parses expression with binding power of at least bp.
 fn rule_bp(p: &mut Parser<'_>, m: Option<Marker>, bp: u8) -> Option<CompletedMarker> {
+    // Parenthesized and labeled rules recurse through here.
+    if !p.enter() {
+        if let Some(m) = m {
+            m.abandon(p);
+        }
+        return None;
+    }
+    let res = rule_bp_inner(p, m, bp);
+    p.leave();
+    res
+}
+
+fn rule_bp_inner(p: &mut Parser<'_>, m: Option<Marker>, bp: u8) -> Option<CompletedMarker> {
     let m = m.unwrap_or_else(|| p.start());
 
     if !p.at_ts(RULE_FIRST) || p.at(END_OF_NODE) {
@@ -68,10 +83,10 @@ fn rule_bp(p: &mut Parser<'_>, m: Option<Marker>, bp: u8) -> Option<CompletedMar
     };
 
     loop {
-        if p.at_ts(RULE_FIRST) && !p.at(END_OF_NODE) {
+        if bp <= bp::SEQ && p.at_ts(RULE_FIRST) && !p.at(END_OF_NODE) {
             let mut count = 0;
             while p.at_ts(RULE_FIRST) && !p.at(END_OF_NODE) {
-                if rule_bp(p, None, bp::SEQ).is_none() {
+                if rule_bp(p, None, bp::SEQ + 1).is_none() {
                     break;
                 }
                 count += 1;
@@ -80,6 +95,7 @@ fn rule_bp(p: &mut Parser<'_>, m: Option<Marker>, bp: u8) -> Option<CompletedMar
                 let m = lhs.precede(p);
                 // test seq_rule
                 // SourceFile = 'Hello' 'World'
+                // SourceFileB = 'Hello' 'World' '!'*
                 lhs = m.complete(p, SEQ_RULE);
                 continue;
             }
@@ -47,12 +47,13 @@ fn paren_rule(p: &mut Parser) -> CompletedMarker {
 
 // test labeled_rule
 // SourceFile = name:'World'
+// SourceFileB = greeting:'Hello' names:Name* | 'Hi'
 fn labeled_rule(p: &mut Parser) -> CompletedMarker {
     assert!(p.at(IDENT) && p.nth_at(1, T![:]));
     let m = p.start();
     label(p);
     p.bump(T![:]);
-    rule(p);
+    rule_bp(p, None, bp::POSTFIX);
     m.complete(p, LABELED_RULE)
 }
 
@@ -29,6 +29,10 @@ impl Input {
     pub fn push_node(&mut self, contextual_kind: SyntaxKind) {
         self.push_impl(SyntaxKind::NODE, contextual_kind)
     }
+    #[inline]
+    pub fn push_ident(&mut self, contextual_kind: SyntaxKind) {
+        self.push_impl(SyntaxKind::IDENT, contextual_kind)
+    }
     /// Sets jointness for the last token we've pushed.
     ///
     /// This is a separate API rather than an argument to the `push` to make it
@@ -54,7 +58,7 @@ impl Input {
     #[inline]
     fn push_impl(&mut self, kind: SyntaxKind, contextual_kind: SyntaxKind) {
         let idx = self.len();
-        if idx % (bits::BITS as usize) == 0 {
+        if idx.is_multiple_of(bits::BITS as usize) {
             self.joint.push(0);
         }
         self.kind.push(kind);
@@ -67,7 +71,7 @@ impl Input {
     pub(crate) fn kind(&self, idx: usize) -> SyntaxKind {
         self.kind.get(idx).copied().unwrap_or(SyntaxKind::EOF)
     }
-    pub(crate) fn _contextual_kind(&self, idx: usize) -> SyntaxKind {
+    pub(crate) fn contextual_kind(&self, idx: usize) -> SyntaxKind {
         self.contextual_kind.get(idx).copied().unwrap_or(SyntaxKind::EOF)
     }
     pub(crate) fn _is_joint(&self, n: usize) -> bool {
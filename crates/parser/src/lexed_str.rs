@@ -10,9 +10,10 @@
 
 use std::ops;
 
-use lexer::unescape::EscapeError;
+use lexer::{unescape::EscapeError, LineEndings};
 
 use crate::{
+    Edition, ParseEditionError,
     SyntaxKind::{self, *},
     T,
 };
@@ -24,16 +25,34 @@ pub struct LexedStr<'a> {
     error: Vec<LexError>,
 }
 
+/// The errors which tools may want to handle differently from the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LexErrorKind {
+    /// A `\r` outside of a token literal which isn't part of an accepted
+    /// line ending.
+    InvalidLineEnding,
+    Other,
+}
+
 struct LexError {
     msg: String,
+    kind: LexErrorKind,
     token: u32,
+    /// The range of the error in the text, which is a part of the token for
+    /// an invalid escape sequence.
+    range: ops::Range<u32>,
 }
 
 impl<'a> LexedStr<'a> {
     pub fn new(text: &'a str) -> LexedStr<'a> {
+        LexedStr::new_with_line_endings(text, LineEndings::Unix)
+    }
+
+    /// Like [`LexedStr::new`], accepting the given line endings.
+    pub fn new_with_line_endings(text: &'a str, line_endings: LineEndings) -> LexedStr<'a> {
         let _p = tracing::span!(tracing::Level::INFO, "LexedStr::new").entered();
         let mut conv = Converter::new(text);
-        for token in lexer::tokenize(&text[conv.offset..]) {
+        for token in lexer::tokenize_with_line_endings(&text[conv.offset..], line_endings) {
             let token_text = &text[conv.offset..][..token.len as usize];
 
             conv.extend_token(&token.kind, token_text);
@@ -42,6 +61,25 @@ impl<'a> LexedStr<'a> {
         conv.finalize_with_eof()
     }
 
+    /// Looks for an `// edition: N` comment before the first item, which
+    /// selects the edition of the file. Returns the index of the comment and
+    /// the edition it names.
+    pub fn edition_marker(&self) -> Option<(usize, Result<Edition, ParseEditionError>)> {
+        for i in 0..self.len() {
+            match self.kind(i) {
+                WHITESPACE => (),
+                COMMENT => {
+                    let comment = self.text(i).strip_prefix("//").unwrap_or_default();
+                    if let Some(edition) = comment.trim_start().strip_prefix("edition:") {
+                        return Some((i, edition.trim().parse()));
+                    }
+                }
+                _ => break,
+            }
+        }
+        None
+    }
+
     pub fn as_str(&self) -> &str {
         self.text
     }
@@ -87,14 +125,27 @@ impl<'a> LexedStr<'a> {
         r.end - r.start
     }
 
-    pub fn error(&self, i: usize) -> Option<&str> {
+    /// Returns the errors of the `i`th token, with their ranges in the text.
+    pub fn token_errors(&self, i: usize) -> impl Iterator<Item = (ops::Range<usize>, &str)> + '_ {
         assert!(i < self.len());
-        let err = self.error.binary_search_by_key(&(i as u32), |i| i.token).ok()?;
-        Some(self.error[err].msg.as_str())
+        let lo = self.error.partition_point(|it| it.token < i as u32);
+        let hi = self.error.partition_point(|it| it.token <= i as u32);
+        self.error[lo..hi].iter().map(LexError::as_pair)
+    }
+
+    /// Returns all errors, with their ranges in the text.
+    pub fn errors(&self) -> impl Iterator<Item = (ops::Range<usize>, &str)> + '_ {
+        self.error.iter().map(LexError::as_pair)
     }
 
-    pub fn errors(&self) -> impl Iterator<Item = (usize, &str)> + '_ {
-        self.error.iter().map(|it| (it.token as usize, it.msg.as_str()))
+    /// Like [`LexedStr::errors`], with the kind of each error.
+    pub fn errors_with_kinds(
+        &self,
+    ) -> impl Iterator<Item = (ops::Range<usize>, &str, LexErrorKind)> + '_ {
+        self.error.iter().map(|it| {
+            let (range, msg) = it.as_pair();
+            (range, msg, it.kind)
+        })
     }
 
     fn push(&mut self, kind: SyntaxKind, offset: usize) {
@@ -103,6 +154,12 @@ impl<'a> LexedStr<'a> {
     }
 }
 
+impl LexError {
+    fn as_pair(&self) -> (ops::Range<usize>, &str) {
+        (self.range.start as usize..self.range.end as usize, self.msg.as_str())
+    }
+}
+
 struct Converter<'a> {
     res: LexedStr<'a>,
     offset: usize,
@@ -121,29 +178,45 @@ impl<'a> Converter<'a> {
         self.res
     }
 
-    fn push(&mut self, kind: SyntaxKind, len: usize, err: Option<&str>) {
+    fn push(&mut self, kind: SyntaxKind, len: usize, err: Option<(&str, LexErrorKind)>) {
+        let range = self.offset..self.offset + len;
         self.res.push(kind, self.offset);
         self.offset += len;
 
-        if let Some(err) = err {
-            let token = self.res.len() as u32;
-            let msg = err.to_owned();
-            self.res.error.push(LexError { msg, token });
+        if let Some((err, err_kind)) = err {
+            self.push_error(range, err.to_owned(), err_kind);
         }
     }
 
+    /// Adds an error of the last token.
+    fn push_error(&mut self, range: ops::Range<usize>, msg: String, kind: LexErrorKind) {
+        let token = self.res.len() as u32;
+        let range = range.start as u32..range.end as u32;
+        self.res.error.push(LexError { msg, kind, token, range });
+    }
+
     fn extend_token(&mut self, kind: &lexer::TokenKind, token_text: &str) {
         // A note on an intended tradeoff:
         // We drop some useful information here (see patterns with double dots `..`)
         // Storing that info in `SyntaxKind` is not possible due to its layout requirements of
         // being `u16` that come from `rowan::SyntaxKind`.
         let mut err = "";
+        let mut err_kind = LexErrorKind::Other;
+        let mut escape_errors = Vec::new();
 
         let syntax_kind = match kind {
             lexer::TokenKind::LineComment => COMMENT,
+            lexer::TokenKind::DocComment => DOC_COMMENT,
+            lexer::TokenKind::BlockComment { terminated } => {
+                if !terminated {
+                    err = "missing trailing `*/` symbols to terminate the block comment";
+                }
+                COMMENT
+            }
             lexer::TokenKind::Whitespace => WHITESPACE,
             lexer::TokenKind::InvalidLineEnding => {
                 err = "unexpected `\\r`, only Unix-style line endings allowed";
+                err_kind = LexErrorKind::InvalidLineEnding;
                 WHITESPACE
             }
             lexer::TokenKind::Ident => IDENT,
@@ -155,7 +228,7 @@ impl<'a> Converter<'a> {
                     let text = &self.res.text[self.offset + 1..][..len - 1];
                     let i = text.rfind('\'').unwrap();
                     let text = &text[..i];
-                    err = unescape_string_error_message(text);
+                    escape_errors = unescape_string_errors(text);
                 }
                 STRING
             }
@@ -170,27 +243,45 @@ impl<'a> Converter<'a> {
             lexer::TokenKind::Eof => EOF,
         };
 
-        let err = if err.is_empty() { None } else { Some(err) };
+        let token_range = self.offset..self.offset + token_text.len();
+        let err = if err.is_empty() { None } else { Some((err, err_kind)) };
         self.push(syntax_kind, token_text.len(), err);
+        for (range, msg) in escape_errors {
+            // Ranges are relative to the contents, after the opening quote.
+            let range = if range.is_empty() {
+                token_range.clone()
+            } else {
+                token_range.start + 1 + range.start..token_range.start + 1 + range.end
+            };
+            self.push_error(range, msg, LexErrorKind::Other);
+        }
     }
 }
 
-fn error_to_diagnostic_message(error: EscapeError) -> &'static str {
+fn error_to_diagnostic_message(error: EscapeError, escape: &str) -> String {
     match error {
-        EscapeError::ZeroChars => "empty token literal",
-        EscapeError::LoneSlash => "",
-        EscapeError::InvalidEscape => "unknown character escape",
-        EscapeError::BareCarriageReturn => "",
-        EscapeError::EscapeOnlyChar => "character constant must be escaped",
+        EscapeError::ZeroChars => "empty token literal".to_owned(),
+        EscapeError::LoneSlash => {
+            "incomplete character escape, expected a character after `\\`".to_owned()
+        }
+        EscapeError::InvalidEscape => format!(
+            "unknown character escape: `\\{}`, only `\\\\` and `\\'` are allowed",
+            escape[1..].escape_debug()
+        ),
+        EscapeError::BareCarriageReturn => "bare CR not allowed in token literals".to_owned(),
+        EscapeError::EscapeOnlyChar => "`'` must be escaped as `\\'`".to_owned(),
     }
 }
 
-fn unescape_string_error_message(text: &str) -> &'static str {
-    let mut error_message = "";
-    lexer::unescape::unescape::<_, char>(text, &mut |_, res| {
-        if let Err(e) = res {
-            error_message = error_to_diagnostic_message(e);
+/// Returns every escape error of the contents of a token literal, with its
+/// range in `text`.
+fn unescape_string_errors(text: &str) -> Vec<(ops::Range<usize>, String)> {
+    let mut res = Vec::new();
+    lexer::unescape::unescape::<_, char>(text, &mut |range, it| {
+        if let Err(e) = it {
+            let msg = error_to_diagnostic_message(e, &text[range.clone()]);
+            res.push((range, msg));
         }
     });
-    error_message
+    res
 }
@@ -32,10 +32,13 @@ mod tests;
 
 pub(crate) use token_set::TokenSet;
 
+pub use lexer::LineEndings;
+pub use limit::LimitStats;
+
 pub use crate::{
-    edition::Edition,
+    edition::{Edition, ParseEditionError},
     input::Input,
-    lexed_str::LexedStr,
+    lexed_str::{LexErrorKind, LexedStr},
     output::{Output, Step},
     shortcuts::StrStep,
     syntax_kind::SyntaxKind,
@@ -55,16 +58,47 @@ pub enum TopEntryPoint {
     Grammar,
 }
 
+/// Bounds on the work of a single parse. Exceeding one of them stops the
+/// parse with an error, the rest of the input ends up in an error node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParserLimits {
+    /// How many times the parser may look at the input without consuming a
+    /// token, which guards against bugs which make it loop.
+    pub steps: usize,
+    /// How deeply rules may be nested, e.g. in parentheses, which guards
+    /// against stack overflows.
+    pub depth: usize,
+}
+
+impl Default for ParserLimits {
+    fn default() -> Self {
+        ParserLimits { steps: 15_000_000, depth: 256 }
+    }
+}
+
 impl TopEntryPoint {
     pub fn parse(&self, input: &Input, edition: Edition) -> Output {
+        self.parse_with_limits(input, edition, ParserLimits::default())
+    }
+
+    /// Like [`TopEntryPoint::parse`], but with the given limits rather than
+    /// the default ones.
+    pub fn parse_with_limits(
+        &self,
+        input: &Input,
+        edition: Edition,
+        limits: ParserLimits,
+    ) -> Output {
         let _p = tracing::span!(tracing::Level::INFO, "TopEntryPoint::parse", ?self).entered();
         let entry_point: fn(&'_ mut parser::Parser<'_>) = match self {
             TopEntryPoint::Grammar => grammar::entry::top::grammar,
         };
-        let mut p = parser::Parser::new(input, edition);
+        let mut p = parser::Parser::new(input, edition, limits);
         entry_point(&mut p);
+        let limit_stats = p.limit_stats();
         let events = p.finish();
-        let res = event::process(events);
+        let mut res = event::process(events);
+        res.set_limit_stats(limit_stats);
 
         if cfg!(debug_assertions) {
             let mut depth = 0;
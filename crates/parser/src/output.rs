@@ -1,5 +1,7 @@
 //! See [`Output`]
 
+use limit::LimitStats;
+
 use crate::SyntaxKind;
 
 /// Output of the parser -- a DFS traversal of a concrete syntax tree.
@@ -20,6 +22,7 @@ pub struct Output {
     ///
     event: Vec<u32>,
     error: Vec<String>,
+    limit_stats: Vec<LimitStats>,
 }
 
 #[derive(Debug)]
@@ -45,6 +48,16 @@ impl Output {
     const ENTER_EVENT: u8 = 1;
     const EXIT_EVENT: u8 = 2;
 
+    /// The limits of the parse, see [`crate::ParserLimits`]. The largest
+    /// values reached are only known with the `tracking` feature.
+    pub fn limit_stats(&self) -> &[LimitStats] {
+        &self.limit_stats
+    }
+
+    pub(crate) fn set_limit_stats(&mut self, limit_stats: Vec<LimitStats>) {
+        self.limit_stats = limit_stats;
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = Step<'_>> {
         self.event.iter().map(|&event| {
             if event & Self::EVENT_MASK == 0 {
@@ -3,13 +3,13 @@
 use std::cell::Cell;
 
 use drop_bomb::DropBomb;
-use limit::Limit;
+use limit::{Limit, LimitId, LimitRegistry, LimitStats};
 
 use crate::{
     event::Event,
     input::Input,
-    Edition,
-    SyntaxKind::{self, EOF, ERROR, TOMBSTONE},
+    Edition, ParserLimits,
+    SyntaxKind::{self, EOF, ERROR, STRING, TOMBSTONE},
     TokenSet, T,
 };
 
@@ -27,14 +27,44 @@ pub(crate) struct Parser<'t> {
     pos: usize,
     events: Vec<Event>,
     steps: Cell<u32>,
-    _edition: Edition,
+    depth: usize,
+    limits: LimitRegistry,
+    step_limit: LimitId,
+    depth_limit: LimitId,
+    /// The error of an exceeded limit. Once it is set, the parser only sees
+    /// `EOF` and drops further errors, so the grammar unwinds quickly.
+    abort: Cell<Option<&'static str>>,
+    edition: Edition,
 }
 
-static PARSER_STEP_LIMIT: Limit = Limit::new(15_000_000);
-
 impl<'t> Parser<'t> {
-    pub(super) fn new(inp: &'t Input, edition: Edition) -> Parser<'t> {
-        Parser { inp, pos: 0, events: Vec::new(), steps: Cell::new(0), _edition: edition }
+    pub(super) fn new(inp: &'t Input, edition: Edition, limits: ParserLimits) -> Parser<'t> {
+        let mut registry = LimitRegistry::default();
+        let step_limit = registry.register("steps", new_limit(limits.steps));
+        let depth_limit = registry.register("depth", new_limit(limits.depth));
+        Parser {
+            inp,
+            pos: 0,
+            events: Vec::new(),
+            steps: Cell::new(0),
+            depth: 0,
+            limits: registry,
+            step_limit,
+            depth_limit,
+            abort: Cell::new(None),
+            edition,
+        }
+    }
+
+    /// Returns the limits of the parse, with the largest values reached if
+    /// the `tracking` feature is enabled.
+    pub(crate) fn limit_stats(&self) -> Vec<LimitStats> {
+        self.limits.stats()
+    }
+
+    /// The edition of the file, which decides the syntax it may use.
+    pub(crate) fn edition(&self) -> Edition {
+        self.edition
     }
 
     pub(crate) fn finish(self) -> Vec<Event> {
@@ -54,10 +84,20 @@ impl<'t> Parser<'t> {
         assert!(n <= 3);
 
         let steps = self.steps.get();
-        assert!(PARSER_STEP_LIMIT.check(steps as usize).is_ok(), "the parser seems stuck");
+        if self.limits[self.step_limit].check(steps as usize).is_err() {
+            self.abort.set(Some("the parser seems stuck, step limit exceeded"));
+        }
         self.steps.set(steps + 1);
 
-        self.inp.kind(self.pos + n)
+        self.kind(n)
+    }
+
+    /// Returns the kind of the nth token, or `EOF` if the parse is aborted.
+    fn kind(&self, n: usize) -> SyntaxKind {
+        match self.abort.get() {
+            Some(_) => EOF,
+            None => self.inp.kind(self.pos + n),
+        }
     }
 
     /// Checks if the current token is `kind`.
@@ -67,8 +107,11 @@ impl<'t> Parser<'t> {
 
     pub(crate) fn nth_at(&self, n: usize, kind: SyntaxKind) -> bool {
         match kind {
-            SyntaxKind::END_OF_NODE => self.at_weak_composite2(n, SyntaxKind::IDENT, T![=]),
-            _ => self.inp.kind(self.pos + n) == kind,
+            SyntaxKind::END_OF_NODE => {
+                self.at_weak_composite2(n, SyntaxKind::IDENT, T![=])
+                    || (self.edition >= Edition::V2 && self.nth_at_include(n))
+            }
+            _ => self.kind(n) == kind,
         }
     }
 
@@ -82,8 +125,55 @@ impl<'t> Parser<'t> {
         true
     }
 
+    /// Checks if the nth token starts an `include 'path'` item.
+    fn nth_at_include(&self, n: usize) -> bool {
+        self.nth_at_contextual_kw(n, T![include]) && self.kind(n + 1) == STRING
+    }
+
+    /// Checks if the current token is contextual keyword `kw`.
+    pub(crate) fn at_contextual_kw(&self, kw: SyntaxKind) -> bool {
+        self.nth_at_contextual_kw(0, kw)
+    }
+
+    /// Checks if the nth token is contextual keyword `kw`.
+    pub(crate) fn nth_at_contextual_kw(&self, n: usize, kw: SyntaxKind) -> bool {
+        self.abort.get().is_none() && self.inp.contextual_kind(self.pos + n) == kw
+    }
+
     fn at_weak_composite2(&self, n: usize, k1: SyntaxKind, k2: SyntaxKind) -> bool {
-        self.inp.kind(self.pos + n) == k1 && self.inp.kind(self.pos + n + 1) == k2
+        self.kind(n) == k1 && self.kind(n + 1) == k2
+    }
+
+    /// Enters a nested rule. Returns `false`, aborting the parse, if rules
+    /// are nested deeper than the limit allows. Each successful call must be
+    /// paired with a [`Parser::leave`].
+    pub(crate) fn enter(&mut self) -> bool {
+        if self.limits[self.depth_limit].check(self.depth + 1).is_err() {
+            self.abort.set(Some("rules are nested too deeply, depth limit exceeded"));
+            return false;
+        }
+        self.depth += 1;
+        true
+    }
+
+    pub(crate) fn leave(&mut self) {
+        self.depth -= 1;
+    }
+
+    /// If a limit was exceeded, reports it and puts the rest of the input
+    /// into an error node, so the tree still covers the whole text.
+    pub(crate) fn recover_from_abort(&mut self) {
+        let Some(msg) = self.abort.take() else { return };
+        self.steps.set(0);
+        self.error(msg);
+        if self.at(EOF) {
+            return;
+        }
+        let m = self.start();
+        while !self.at(EOF) {
+            self.do_bump(self.current(), 1);
+        }
+        m.complete(self, ERROR);
     }
 
     /// Checks if the current token is in `kinds`.
@@ -114,11 +204,27 @@ impl<'t> Parser<'t> {
         self.do_bump(kind, 1);
     }
 
+    /// Advances the parser by one token, remapping its kind.
+    /// This is useful to create contextual keywords from
+    /// identifiers. For example, the lexer creates an `include`
+    /// *identifier* token, but the parser remaps it to the
+    /// `include` keyword.
+    pub(crate) fn bump_remap(&mut self, kind: SyntaxKind) {
+        if self.nth(0) == EOF {
+            // FIXME: panic!?
+            return;
+        }
+        self.do_bump(kind, 1);
+    }
+
     /// Emit error with the `message`
     /// FIXME: this should be much more fancy and support
     /// structured errors with spans and notes, like rustc
     /// does.
     pub(crate) fn error<T: Into<String>>(&mut self, message: T) {
+        if self.abort.get().is_some() {
+            return;
+        }
         let msg = message.into();
         self.push_event(Event::Error { msg });
     }
@@ -167,6 +273,13 @@ impl<'t> Parser<'t> {
     }
 }
 
+fn new_limit(upper_bound: usize) -> Limit {
+    #[cfg(feature = "tracking")]
+    return Limit::new_tracking(upper_bound);
+    #[cfg(not(feature = "tracking"))]
+    return Limit::new(upper_bound);
+}
+
 /// See [`Parser::start`].
 pub(crate) struct Marker {
     pos: u32,
@@ -34,7 +34,14 @@ impl LexedStr<'_> {
                 if was_joint {
                     res.was_joint();
                 }
-                res.push(kind);
+                if kind == SyntaxKind::IDENT {
+                    let token_text = self.text(i);
+                    let contextual_kw = SyntaxKind::from_contextual_keyword(token_text)
+                        .unwrap_or(SyntaxKind::IDENT);
+                    res.push_ident(contextual_kw);
+                } else {
+                    res.push(kind);
+                }
                 was_joint = true;
             }
         }
@@ -157,9 +164,37 @@ impl Builder<'_, '_> {
     }
 }
 
+/// Returns how many of the trivia right before a node, given in reverse
+/// order, belong to the node itself.
+///
+/// A `NODE` owns the comments directly above it, so its documentation is part
+/// of its subtree. A blank line ends the comments, unless a doc comment comes
+/// above it.
 fn n_attached_trivia<'a>(
-    _kind: SyntaxKind,
-    _trivia: impl Iterator<Item = (SyntaxKind, &'a str)>,
+    kind: SyntaxKind,
+    trivia: impl Iterator<Item = (SyntaxKind, &'a str)>,
 ) -> usize {
-    0
+    match kind {
+        SyntaxKind::NODE => {
+            let mut res = 0;
+            let mut trivia = trivia.enumerate().peekable();
+
+            while let Some((i, (kind, text))) = trivia.next() {
+                match kind {
+                    SyntaxKind::WHITESPACE if text.contains("\n\n") || text.contains("\n\r\n") => {
+                        // we check whether the next token is a doc-comment
+                        // and skip the whitespace in this case
+                        if let Some((_, (SyntaxKind::DOC_COMMENT, _))) = trivia.peek() {
+                            continue;
+                        }
+                        break;
+                    }
+                    SyntaxKind::COMMENT | SyntaxKind::DOC_COMMENT => res = i + 1,
+                    _ => (),
+                }
+            }
+            res
+        }
+        _ => 0,
+    }
 }
@@ -1,10 +1,12 @@
 //! Generated by `sourcegen_ast`, do not edit by hand.
 
 #![allow(bad_style, missing_docs, unreachable_pub)]
-#[doc = r" The kind of syntax node, e.g. `NODE`, `TOKEN`, or `STAR`."]
+/// The kind of a syntax node or token.
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
 #[repr(u16)]
 pub enum SyntaxKind {
+    // Technical SyntaxKinds: they appear temporally during parsing,
+    // but never end up in the final tree
     #[doc(hidden)]
     TOMBSTONE,
     #[doc(hidden)]
@@ -16,15 +18,19 @@ pub enum SyntaxKind {
     COLON,
     L_PAREN,
     R_PAREN,
+    INCLUDE_KW,
     ERROR,
     IDENT,
     STRING,
     WHITESPACE,
     COMMENT,
+    DOC_COMMENT,
+    // Technical kind so that we can mark the end of tokens
     #[doc(hidden)]
     __FIRST,
     GRAMMAR,
     NODE,
+    INCLUDE,
     LABEL,
     PAREN_RULE,
     NAME,
@@ -36,16 +42,27 @@ pub enum SyntaxKind {
     OPT_RULE,
     REP_RULE,
     END_OF_NODE,
+    // Technical kind so that we can cast from u16 safely
     #[doc(hidden)]
     __LAST,
 }
 use self::SyntaxKind::*;
+
 impl SyntaxKind {
     pub fn is_punct(self) -> bool {
         matches!(self, EQ | STAR | PIPE | QUESTION | COLON | L_PAREN | R_PAREN)
     }
+
+    pub fn from_contextual_keyword(ident: &str) -> Option<SyntaxKind> {
+        let kind = match ident {
+            "include" => INCLUDE_KW,
+            _ => return None,
+        };
+        Some(kind)
+    }
+
     pub fn from_char(c: char) -> Option<SyntaxKind> {
-        let tok = match c {
+        let kind = match c {
             '=' => EQ,
             '*' => STAR,
             '|' => PIPE,
@@ -55,8 +72,18 @@ impl SyntaxKind {
             ')' => R_PAREN,
             _ => return None,
         };
-        Some(tok)
+        Some(kind)
     }
 }
+
 #[macro_export]
-macro_rules ! T { [=] => { $ crate :: SyntaxKind :: EQ } ; [*] => { $ crate :: SyntaxKind :: STAR } ; [|] => { $ crate :: SyntaxKind :: PIPE } ; [?] => { $ crate :: SyntaxKind :: QUESTION } ; [:] => { $ crate :: SyntaxKind :: COLON } ; ['('] => { $ crate :: SyntaxKind :: L_PAREN } ; [')'] => { $ crate :: SyntaxKind :: R_PAREN } ; }
+macro_rules! T {
+    [=] => { $crate::SyntaxKind::EQ };
+    [*] => { $crate::SyntaxKind::STAR };
+    [|] => { $crate::SyntaxKind::PIPE };
+    [?] => { $crate::SyntaxKind::QUESTION };
+    [:] => { $crate::SyntaxKind::COLON };
+    ['('] => { $crate::SyntaxKind::L_PAREN };
+    [')'] => { $crate::SyntaxKind::R_PAREN };
+    [include] => { $crate::SyntaxKind::INCLUDE_KW };
+}
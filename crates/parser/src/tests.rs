@@ -42,10 +42,19 @@ fn lex(text: &str) -> String {
     for i in 0..lexed.len() {
         let kind = lexed.kind(i);
         let text = lexed.text(i);
-        let error = lexed.error(i);
-
-        let error = error.map(|err| format!(" error: {err}")).unwrap_or_default();
-        writeln!(res, "{kind:?} {text:?}{error}").unwrap();
+        write!(res, "{kind:?} {text:?}").unwrap();
+        for (range, err) in lexed.token_errors(i) {
+            // Errors of a part of the token show the range of that part.
+            match range == lexed.text_range(i) {
+                true => write!(res, " error: {err}").unwrap(),
+                false => {
+                    let start = lexed.text_start(i);
+                    write!(res, " error {}..{}: {err}", range.start - start, range.end - start)
+                        .unwrap()
+                }
+            }
+        }
+        res.push('\n');
     }
     res
 }
@@ -105,7 +114,11 @@ fn parse_inline_err() {
 fn parse(entry: TopEntryPoint, text: &str) -> (String, bool) {
     let lexed = LexedStr::new(text);
     let input = lexed.to_input();
-    let output = entry.parse(&input, crate::Edition::CURRENT);
+    let edition = match lexed.edition_marker() {
+        Some((_, Ok(edition))) => edition,
+        _ => crate::Edition::CURRENT,
+    };
+    let output = entry.parse(&input, edition);
 
     let mut buf = String::new();
     let mut errors = Vec::new();
@@ -143,9 +156,8 @@ fn parse(entry: TopEntryPoint, text: &str) -> (String, bool) {
         text
     );
 
-    for (token, msg) in lexed.errors() {
-        let pos = lexed.text_start(token);
-        errors.push(format!("error {pos}: {msg}\n"));
+    for (range, msg) in lexed.errors() {
+        errors.push(format!("error {}: {msg}\n", range.start));
     }
 
     let has_errors = !errors.is_empty();
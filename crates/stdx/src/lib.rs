@@ -1,3 +1,60 @@
 //! Missing batteries for standard libraries.
 
 pub mod panic_context;
+
+/// Converts `CamelCase` into `camel_case`, keeping runs of capitals such as
+/// `HTTPRequest` together (`http_request`).
+pub fn to_lower_snake_case(s: &str) -> String {
+    to_snake_case(s, char::to_ascii_lowercase)
+}
+
+/// Converts `CamelCase` into `CAMEL_CASE`.
+pub fn to_upper_snake_case(s: &str) -> String {
+    to_snake_case(s, char::to_ascii_uppercase)
+}
+
+fn to_snake_case<F: Fn(&char) -> char>(s: &str, change_case: F) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let chars = s.chars().collect::<Vec<_>>();
+    for (i, &c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(char::is_ascii_lowercase);
+            if prev.is_ascii_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_ascii_uppercase() && next_is_lower)
+            {
+                buf.push('_');
+            }
+        }
+        buf.push(change_case(&c));
+    }
+    buf
+}
+
+/// Converts `snake_case` into `SnakeCase`.
+pub fn to_camel_case(s: &str) -> String {
+    let mut buf = String::with_capacity(s.len());
+    let mut upper = true;
+    for c in s.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            buf.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            buf.push(c);
+        }
+    }
+    buf
+}
+
+#[test]
+fn test_to_snake_case() {
+    assert_eq!(to_lower_snake_case("SourceFile"), "source_file");
+    assert_eq!(to_lower_snake_case("HTTPRequest"), "http_request");
+    assert_eq!(to_lower_snake_case("Expr2Kind"), "expr2_kind");
+    assert_eq!(to_lower_snake_case("expr"), "expr");
+    assert_eq!(to_upper_snake_case("NameRef"), "NAME_REF");
+    assert_eq!(to_camel_case("name_ref"), "NameRef");
+}
@@ -0,0 +1,28 @@
+//! Abstract Syntax Tree, layered on top of untyped `SyntaxNode`s
+
+mod node_ext;
+mod nodes;
+mod tokens;
+
+pub use rowan::ast::{AstChildren, AstNode};
+
+use crate::{SyntaxKind, SyntaxToken};
+
+pub use self::{nodes::*, tokens::*};
+
+/// Like `AstNode`, but wraps tokens rather than interior nodes.
+pub trait AstToken {
+    fn can_cast(token: SyntaxKind) -> bool
+    where
+        Self: Sized;
+
+    fn cast(syntax: SyntaxToken) -> Option<Self>
+    where
+        Self: Sized;
+
+    fn syntax(&self) -> &SyntaxToken;
+
+    fn text(&self) -> &str {
+        self.syntax().text()
+    }
+}
@@ -0,0 +1,73 @@
+//! Various extension methods to ast Nodes, which are hard to code-generate.
+
+use crate::{
+    ast::{self, AstNode, AstToken},
+    SyntaxToken,
+};
+
+impl ast::Name {
+    pub fn text(&self) -> String {
+        text_of_first_token(self.syntax())
+    }
+}
+
+impl ast::Node {
+    /// Returns the `///` comments above the definition.
+    pub fn doc_comments(&self) -> impl Iterator<Item = ast::DocComment> {
+        self.syntax()
+            .children_with_tokens()
+            .filter_map(|it| it.into_token())
+            .filter_map(ast::DocComment::cast)
+    }
+
+    /// Returns the documentation of the node, one line per doc comment, or
+    /// `None` if it has no doc comments.
+    pub fn docs(&self) -> Option<String> {
+        let lines = self.doc_comments().map(|it| it.doc_text().to_owned()).collect::<Vec<_>>();
+        (!lines.is_empty()).then(|| lines.join("\n"))
+    }
+}
+
+impl ast::NameRef {
+    pub fn text(&self) -> String {
+        text_of_first_token(self.syntax())
+    }
+}
+
+impl ast::Label {
+    pub fn text(&self) -> String {
+        text_of_first_token(self.syntax())
+    }
+}
+
+impl ast::Token {
+    pub fn string(&self) -> Option<ast::String> {
+        self.string_token().and_then(ast::String::cast)
+    }
+
+    /// The unescaped contents of the literal, see [`ast::String::value`].
+    pub fn value(&self) -> Option<String> {
+        Some(self.string()?.value()?.into_owned())
+    }
+}
+
+impl ast::Include {
+    pub fn string(&self) -> Option<ast::String> {
+        self.string_token().and_then(ast::String::cast)
+    }
+}
+
+impl ast::Rule {
+    /// Returns the rule with any number of surrounding parentheses removed.
+    pub fn strip_parens(self) -> Option<ast::Rule> {
+        let mut rule = self;
+        while let ast::Rule::ParenRule(paren) = rule {
+            rule = paren.rule()?;
+        }
+        Some(rule)
+    }
+}
+
+fn text_of_first_token(node: &crate::SyntaxNode) -> String {
+    node.first_token().as_ref().map(SyntaxToken::text).unwrap_or_default().to_owned()
+}
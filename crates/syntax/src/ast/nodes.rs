@@ -0,0 +1,246 @@
+//! Typed wrappers for the syntax nodes produced by the parser.
+
+use rowan::ast::support;
+
+use crate::{
+    ast::{AstChildren, AstNode},
+    SyntaxKind::{self, *},
+    SyntaxNode, SyntaxToken, UngrammarLanguage, T,
+};
+
+macro_rules! ast_nodes {
+    ($($(#[$attr:meta])* $name:ident => $kind:ident,)*) => {$(
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name {
+            pub(crate) syntax: SyntaxNode,
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.syntax, f)
+            }
+        }
+
+        impl AstNode for $name {
+            type Language = UngrammarLanguage;
+
+            fn can_cast(kind: SyntaxKind) -> bool {
+                kind == $kind
+            }
+            fn cast(syntax: SyntaxNode) -> Option<Self> {
+                if Self::can_cast(syntax.kind()) {
+                    Some(Self { syntax })
+                } else {
+                    None
+                }
+            }
+            fn syntax(&self) -> &SyntaxNode {
+                &self.syntax
+            }
+        }
+    )*};
+}
+
+ast_nodes! {
+    /// The root of a `.ungram` file.
+    Grammar => GRAMMAR,
+    /// `Name = Rule`
+    Node => NODE,
+    /// `include 'path'`, which makes the nodes of another file visible.
+    Include => INCLUDE,
+    /// The name being defined by a [`Node`].
+    Name => NAME,
+    /// A reference to a [`Node`] from inside a rule.
+    NameRef => NAME_REF,
+    /// The `label` in `label:Rule`.
+    Label => LABEL,
+    /// A token literal used as a rule, e.g. `'fn'`.
+    Token => TOKEN,
+    /// `Rule Rule ...`
+    SeqRule => SEQ_RULE,
+    /// `Rule | Rule`
+    AltRule => ALT_RULE,
+    /// `Rule?`
+    OptRule => OPT_RULE,
+    /// `Rule*`
+    RepRule => REP_RULE,
+    /// `(Rule)`
+    ParenRule => PAREN_RULE,
+    /// `label:Rule`
+    LabeledRule => LABELED_RULE,
+}
+
+impl Grammar {
+    pub fn nodes(&self) -> AstChildren<Node> {
+        support::children(&self.syntax)
+    }
+    pub fn includes(&self) -> AstChildren<Include> {
+        support::children(&self.syntax)
+    }
+}
+
+impl Include {
+    pub fn include_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![include])
+    }
+    pub fn string_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, STRING)
+    }
+}
+
+impl Node {
+    pub fn name(&self) -> Option<Name> {
+        support::child(&self.syntax)
+    }
+    pub fn eq_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![=])
+    }
+    pub fn rule(&self) -> Option<Rule> {
+        support::child(&self.syntax)
+    }
+}
+
+impl Name {
+    pub fn ident_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+}
+
+impl NameRef {
+    pub fn ident_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+}
+
+impl Label {
+    pub fn ident_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, IDENT)
+    }
+}
+
+impl Token {
+    pub fn string_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, STRING)
+    }
+}
+
+impl SeqRule {
+    pub fn rules(&self) -> AstChildren<Rule> {
+        support::children(&self.syntax)
+    }
+}
+
+impl AltRule {
+    pub fn rules(&self) -> AstChildren<Rule> {
+        support::children(&self.syntax)
+    }
+    pub fn pipe_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![|])
+    }
+}
+
+impl OptRule {
+    pub fn rule(&self) -> Option<Rule> {
+        support::child(&self.syntax)
+    }
+    pub fn question_mark_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![?])
+    }
+}
+
+impl RepRule {
+    pub fn rule(&self) -> Option<Rule> {
+        support::child(&self.syntax)
+    }
+    pub fn star_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![*])
+    }
+}
+
+impl ParenRule {
+    pub fn l_paren_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T!['('])
+    }
+    pub fn rule(&self) -> Option<Rule> {
+        support::child(&self.syntax)
+    }
+    pub fn r_paren_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![')'])
+    }
+}
+
+impl LabeledRule {
+    pub fn label(&self) -> Option<Label> {
+        support::child(&self.syntax)
+    }
+    pub fn colon_token(&self) -> Option<SyntaxToken> {
+        support::token(&self.syntax, T![:])
+    }
+    pub fn rule(&self) -> Option<Rule> {
+        support::child(&self.syntax)
+    }
+}
+
+/// Any rule that can appear on the right-hand side of a [`Node`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Rule {
+    AltRule(AltRule),
+    LabeledRule(LabeledRule),
+    NameRef(NameRef),
+    OptRule(OptRule),
+    ParenRule(ParenRule),
+    RepRule(RepRule),
+    SeqRule(SeqRule),
+    Token(Token),
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self.syntax(), f)
+    }
+}
+
+impl AstNode for Rule {
+    type Language = UngrammarLanguage;
+
+    fn can_cast(kind: SyntaxKind) -> bool {
+        matches!(
+            kind,
+            ALT_RULE
+                | LABELED_RULE
+                | NAME_REF
+                | OPT_RULE
+                | PAREN_RULE
+                | REP_RULE
+                | SEQ_RULE
+                | TOKEN
+        )
+    }
+    fn cast(syntax: SyntaxNode) -> Option<Self> {
+        let res = match syntax.kind() {
+            ALT_RULE => Rule::AltRule(AltRule { syntax }),
+            LABELED_RULE => Rule::LabeledRule(LabeledRule { syntax }),
+            NAME_REF => Rule::NameRef(NameRef { syntax }),
+            OPT_RULE => Rule::OptRule(OptRule { syntax }),
+            PAREN_RULE => Rule::ParenRule(ParenRule { syntax }),
+            REP_RULE => Rule::RepRule(RepRule { syntax }),
+            SEQ_RULE => Rule::SeqRule(SeqRule { syntax }),
+            TOKEN => Rule::Token(Token { syntax }),
+            _ => return None,
+        };
+        Some(res)
+    }
+    fn syntax(&self) -> &SyntaxNode {
+        match self {
+            Rule::AltRule(it) => &it.syntax,
+            Rule::LabeledRule(it) => &it.syntax,
+            Rule::NameRef(it) => &it.syntax,
+            Rule::OptRule(it) => &it.syntax,
+            Rule::ParenRule(it) => &it.syntax,
+            Rule::RepRule(it) => &it.syntax,
+            Rule::SeqRule(it) => &it.syntax,
+            Rule::Token(it) => &it.syntax,
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! There are many AstNodes, but only a few tokens, so we hand-write them here.
+
+use std::borrow::Cow;
+
+use lexer::unescape::EscapeError;
+
+use crate::{ast::AstToken, SyntaxKind, SyntaxToken, TextRange, TextSize};
+
+macro_rules! ast_tokens {
+    ($($(#[$attr:meta])* $name:ident => $kind:ident,)*) => {$(
+        $(#[$attr])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub struct $name {
+            pub(crate) syntax: SyntaxToken,
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                std::fmt::Display::fmt(&self.syntax, f)
+            }
+        }
+
+        impl AstToken for $name {
+            fn can_cast(kind: SyntaxKind) -> bool {
+                kind == SyntaxKind::$kind
+            }
+            fn cast(syntax: SyntaxToken) -> Option<Self> {
+                if Self::can_cast(syntax.kind()) {
+                    Some(Self { syntax })
+                } else {
+                    None
+                }
+            }
+            fn syntax(&self) -> &SyntaxToken {
+                &self.syntax
+            }
+        }
+    )*};
+}
+
+ast_tokens! {
+    /// `// comment`
+    Comment => COMMENT,
+    /// `/// doc comment`
+    DocComment => DOC_COMMENT,
+    Whitespace => WHITESPACE,
+    Ident => IDENT,
+    /// A quoted token literal, e.g. `'fn'`.
+    String => STRING,
+}
+
+impl DocComment {
+    /// The text of the comment without the leading `///` and the space after
+    /// it.
+    pub fn doc_text(&self) -> &str {
+        let text = self.text().strip_prefix("///").unwrap_or(self.text());
+        text.strip_prefix(' ').unwrap_or(text)
+    }
+}
+
+impl String {
+    /// The text between the quotes, with escapes left as they are.
+    pub fn text_without_quotes(&self) -> &str {
+        let text = self.text();
+        let text = text.strip_prefix('\'').unwrap_or(text);
+        text.strip_suffix('\'').unwrap_or(text)
+    }
+
+    /// The contents of the literal with the escapes resolved, e.g. `'` for
+    /// `'\''`, or `None` if it contains an invalid escape.
+    pub fn value(&self) -> Option<Cow<'_, str>> {
+        let text = self.text_without_quotes();
+        if !text.contains('\\') {
+            return (!text.is_empty() && !text.contains('\r')).then_some(Cow::Borrowed(text));
+        }
+        let unescaped = self.unescaped();
+        unescaped.is_valid().then_some(Cow::Owned(unescaped.value))
+    }
+
+    /// Like [`String::value`], but resolves an invalid escape to the
+    /// character after the `\\`, and remembers where each character of the
+    /// value comes from.
+    pub fn unescaped(&self) -> Unescaped {
+        let text = self.text_without_quotes();
+        let contents_start = self.syntax.text_range().start() + TextSize::of('\'');
+        let mut res = Unescaped {
+            value: std::string::String::with_capacity(text.len()),
+            chars: Vec::new(),
+            contents_end: contents_start + TextSize::of(text),
+            is_valid: true,
+        };
+        lexer::unescape::unescape::<_, char>(text, &mut |range, it| {
+            let source = TextRange::new(
+                contents_start + TextSize::from(range.start as u32),
+                contents_start + TextSize::from(range.end as u32),
+            );
+            let offset = TextSize::of(&*res.value);
+            match it {
+                Ok(c) => res.value.push(c),
+                Err(err) => {
+                    res.is_valid = false;
+                    let text = &text[range];
+                    match err {
+                        EscapeError::InvalidEscape => res.value.push_str(&text[1..]),
+                        _ => res.value.push_str(text),
+                    }
+                }
+            }
+            res.chars.push((offset, source));
+        });
+        res
+    }
+}
+
+/// The unescaped contents of a token literal, see [`String::unescaped`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unescaped {
+    value: std::string::String,
+    /// The offset in the value and the range in the file of each escape or
+    /// character, in order.
+    chars: Vec<(TextSize, TextRange)>,
+    contents_end: TextSize,
+    is_valid: bool,
+}
+
+impl Unescaped {
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// Returns `false` if the literal is empty or contains invalid escapes.
+    pub fn is_valid(&self) -> bool {
+        self.is_valid
+    }
+
+    /// Maps an offset in the value to the offset in the file. Returns `None`
+    /// for offsets which aren't at a character boundary of the value.
+    pub fn source_offset(&self, offset: TextSize) -> Option<TextSize> {
+        if offset == TextSize::of(&*self.value) {
+            return Some(self.contents_end);
+        }
+        let idx = self.chars.binary_search_by_key(&offset, |&(it, _)| it).ok()?;
+        Some(self.chars[idx].1.start())
+    }
+
+    /// Maps a range in the value to the range in the file, e.g. the range of
+    /// `\'` for the range of `'`.
+    pub fn source_range(&self, range: TextRange) -> Option<TextRange> {
+        Some(TextRange::new(self.source_offset(range.start())?, self.source_offset(range.end())?))
+    }
+}
@@ -0,0 +1,151 @@
+//! Syntax Tree library used throughout the Ungrammar analyzer.
+//!
+//! Properties:
+//!   - graceful handling of errors
+//!   - full-fidelity representation (*any* text can be precisely represented as
+//!     a syntax tree)
+//!
+//! The untyped tree is a [`rowan`] tree built from the events produced by the
+//! `parser` crate. The [`ast`] module provides a typed API on top of it.
+
+mod parsing;
+mod syntax_error;
+mod syntax_node;
+
+pub mod ast;
+
+#[cfg(test)]
+mod tests;
+
+use std::{marker::PhantomData, sync::Arc};
+
+pub use parser::{Edition, LimitStats, LineEndings, ParserLimits, SyntaxKind, T};
+pub use rowan::{Direction, GreenNode, NodeOrToken, TextRange, TextSize, TokenAtOffset, WalkEvent};
+
+pub use crate::{
+    ast::{AstNode, AstToken},
+    syntax_error::{SyntaxError, SyntaxErrorKind},
+    syntax_node::{
+        AstPtr, PreorderWithTokens, SyntaxElement, SyntaxElementChildren, SyntaxNode,
+        SyntaxNodeChildren, SyntaxNodePtr, SyntaxToken, SyntaxTreeBuilder, UngrammarLanguage,
+    },
+};
+
+/// `Parse` is the result of the parsing: a syntax tree and a collection of
+/// errors.
+///
+/// Note that we always produce a syntax tree, even for completely invalid
+/// files.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Parse<T> {
+    green: GreenNode,
+    errors: Arc<[SyntaxError]>,
+    _ty: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Parse<T> {
+    fn clone(&self) -> Parse<T> {
+        Parse { green: self.green.clone(), errors: self.errors.clone(), _ty: PhantomData }
+    }
+}
+
+impl<T> Parse<T> {
+    fn new(green: GreenNode, errors: Vec<SyntaxError>) -> Parse<T> {
+        Parse { green, errors: errors.into(), _ty: PhantomData }
+    }
+
+    pub fn syntax_node(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
+
+impl<T: AstNode<Language = UngrammarLanguage>> Parse<T> {
+    pub fn tree(&self) -> T {
+        T::cast(self.syntax_node()).unwrap()
+    }
+
+    pub fn ok(self) -> Result<T, Arc<[SyntaxError]>> {
+        if self.errors.is_empty() {
+            Ok(self.tree())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+impl Parse<SyntaxNode> {
+    pub fn cast<N: AstNode<Language = UngrammarLanguage>>(self) -> Option<Parse<N>> {
+        if N::cast(self.syntax_node()).is_some() {
+            Some(Parse { green: self.green, errors: self.errors, _ty: PhantomData })
+        } else {
+            None
+        }
+    }
+}
+
+impl Parse<ast::Grammar> {
+    pub fn debug_dump(&self) -> String {
+        let mut buf = format!("{:#?}", self.tree().syntax());
+        for err in self.errors.iter() {
+            buf.push_str(&format!("error {:?}: {}\n", err.range(), err));
+        }
+        buf
+    }
+}
+
+/// Settings which change how the text of a file is parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseConfig {
+    /// The edition of the files which don't select one themselves.
+    pub default_edition: Edition,
+    pub line_endings: LineEndings,
+    pub limits: ParserLimits,
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        ParseConfig {
+            default_edition: Edition::CURRENT,
+            line_endings: LineEndings::Unix,
+            limits: ParserLimits::default(),
+        }
+    }
+}
+
+impl ast::Grammar {
+    /// Parses `text` with the edition it selects, or the latest one.
+    pub fn parse(text: &str) -> Parse<ast::Grammar> {
+        ast::Grammar::parse_with_config(text, ParseConfig::default())
+    }
+
+    /// Parses `text` with the edition it selects, or `default_edition` if it
+    /// doesn't select one.
+    pub fn parse_with_edition(text: &str, default_edition: Edition) -> Parse<ast::Grammar> {
+        ast::Grammar::parse_with_config(text, ParseConfig { default_edition, ..Default::default() })
+    }
+
+    /// Parses `text` with the edition it selects, or the default one of
+    /// `config`, accepting the line endings of `config`.
+    pub fn parse_with_config(text: &str, config: ParseConfig) -> Parse<ast::Grammar> {
+        ast::Grammar::parse_with_stats(text, config).0
+    }
+
+    /// Like [`ast::Grammar::parse_with_config`], but also returns the limits
+    /// of the parse. The largest values reached are only known with the
+    /// `tracking` feature.
+    pub fn parse_with_stats(
+        text: &str,
+        config: ParseConfig,
+    ) -> (Parse<ast::Grammar>, Vec<LimitStats>) {
+        let _p = tracing::span!(tracing::Level::INFO, "Grammar::parse").entered();
+        let (green, errors, limit_stats) = parsing::parse_text(text, config);
+        let root = SyntaxNode::new_root(green.clone());
+
+        assert_eq!(root.kind(), SyntaxKind::GRAMMAR);
+        (Parse::new(green, errors), limit_stats)
+    }
+}
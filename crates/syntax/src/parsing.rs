@@ -0,0 +1,59 @@
+//! Lexing, bridging to parser (which does the actual parsing) and
+//! incremental reparsing.
+
+use parser::{LimitStats, StrStep};
+
+use crate::{syntax_node::SyntaxTreeBuilder, GreenNode, ParseConfig, SyntaxError, TextRange};
+
+/// Parses `text` with the edition selected by its `// edition: N` comment,
+/// or with the default edition of `config` if there is none.
+pub(crate) fn parse_text(
+    text: &str,
+    config: ParseConfig,
+) -> (GreenNode, Vec<SyntaxError>, Vec<LimitStats>) {
+    let _p = tracing::span!(tracing::Level::INFO, "parse_text").entered();
+    let lexed = parser::LexedStr::new_with_line_endings(text, config.line_endings);
+    let default_edition = config.default_edition;
+    let (edition, marker_error) = match lexed.edition_marker() {
+        Some((_, Ok(edition))) => (edition, None),
+        Some((i, Err(err))) => {
+            (default_edition, Some(SyntaxError::new(err.to_string(), text_range(&lexed, i))))
+        }
+        None => (default_edition, None),
+    };
+    let parser_input = lexed.to_input();
+    let parser_output =
+        parser::TopEntryPoint::Grammar.parse_with_limits(&parser_input, edition, config.limits);
+    let limit_stats = parser_output.limit_stats().to_vec();
+    let (node, mut errors, _eof) = build_tree(lexed, parser_output);
+    errors.extend(marker_error);
+    (node, errors, limit_stats)
+}
+
+pub(crate) fn build_tree(
+    lexed: parser::LexedStr<'_>,
+    parser_output: parser::Output,
+) -> (GreenNode, Vec<SyntaxError>, bool) {
+    let _p = tracing::span!(tracing::Level::INFO, "build_tree").entered();
+    let mut builder = SyntaxTreeBuilder::default();
+
+    let is_eof = lexed.intersperse_trivia(&parser_output, &mut |step| match step {
+        StrStep::Token { kind, text } => builder.token(kind, text),
+        StrStep::Enter { kind } => builder.start_node(kind),
+        StrStep::Exit => builder.finish_node(),
+        StrStep::Error { msg, pos } => builder.error(msg.to_owned(), pos.try_into().unwrap()),
+    });
+
+    let (node, mut errors) = builder.finish_raw();
+    for (range, err, kind) in lexed.errors_with_kinds() {
+        let range = TextRange::new(range.start.try_into().unwrap(), range.end.try_into().unwrap());
+        errors.push(SyntaxError::new(err, range).with_kind(kind.into()))
+    }
+
+    (node, errors, is_eof)
+}
+
+fn text_range(lexed: &parser::LexedStr<'_>, i: usize) -> TextRange {
+    let range = lexed.text_range(i);
+    TextRange::new(range.start.try_into().unwrap(), range.end.try_into().unwrap())
+}
@@ -0,0 +1,64 @@
+//! See docs for `SyntaxError`.
+
+use std::fmt;
+
+use parser::LexErrorKind;
+
+use crate::{TextRange, TextSize};
+
+/// Represents the result of unsuccessful tokenization or parsing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SyntaxError(String, TextRange, SyntaxErrorKind);
+
+/// The errors which tools may want to handle differently from the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SyntaxErrorKind {
+    /// A `\r` outside of a token literal which isn't part of an accepted
+    /// line ending.
+    InvalidLineEnding,
+    Other,
+}
+
+impl From<LexErrorKind> for SyntaxErrorKind {
+    fn from(kind: LexErrorKind) -> SyntaxErrorKind {
+        match kind {
+            LexErrorKind::InvalidLineEnding => SyntaxErrorKind::InvalidLineEnding,
+            LexErrorKind::Other => SyntaxErrorKind::Other,
+        }
+    }
+}
+
+impl SyntaxError {
+    pub fn new(message: impl Into<String>, range: TextRange) -> Self {
+        Self(message.into(), range, SyntaxErrorKind::Other)
+    }
+    pub fn new_at_offset(message: impl Into<String>, offset: TextSize) -> Self {
+        Self(message.into(), TextRange::empty(offset), SyntaxErrorKind::Other)
+    }
+
+    pub fn range(&self) -> TextRange {
+        self.1
+    }
+
+    pub fn kind(&self) -> SyntaxErrorKind {
+        self.2
+    }
+
+    pub fn with_kind(mut self, kind: SyntaxErrorKind) -> Self {
+        self.2 = kind;
+        self
+    }
+
+    pub fn with_range(mut self, range: TextRange) -> Self {
+        self.1 = range;
+        self
+    }
+}
+
+impl fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for SyntaxError {}
@@ -0,0 +1,153 @@
+use expect_test::expect;
+
+use crate::{ast, AstNode, Edition, LineEndings, ParseConfig, ParserLimits, TextRange};
+
+#[test]
+fn parse_roundtrips_text() {
+    let text = "// comment\nA = 'a' B* | (c:C)?\nB =\n";
+    let parse = ast::Grammar::parse(text);
+    assert_eq!(parse.syntax_node().to_string(), text);
+    assert_eq!(parse.errors().len(), 1);
+}
+
+#[test]
+fn ast_accessors() {
+    let parse = ast::Grammar::parse("A = 'a' b:B* | ('c')?");
+    let node = parse.tree().nodes().next().unwrap();
+    assert_eq!(node.name().unwrap().text(), "A");
+
+    let ast::Rule::AltRule(alt) = node.rule().unwrap() else { panic!("expected an ALT_RULE") };
+    let rules = alt.rules().map(|it| format!("{:?}", it.syntax().kind())).collect::<Vec<_>>();
+    assert_eq!(rules, ["SEQ_RULE", "OPT_RULE"]);
+
+    let ast::Rule::OptRule(opt) = alt.rules().nth(1).unwrap() else { unreachable!() };
+    let ast::Rule::Token(token) = opt.rule().unwrap().strip_parens().unwrap() else {
+        panic!("expected a TOKEN")
+    };
+    assert_eq!(token.string().unwrap().text_without_quotes(), "c");
+}
+
+#[test]
+fn node_docs() {
+    let parse =
+        ast::Grammar::parse("/// An expression.\n///\n///  Indented.\nExpr = 'e'\n\nA = 'a'");
+    let docs = parse.tree().nodes().map(|it| it.docs()).collect::<Vec<_>>();
+    assert_eq!(docs, [Some("An expression.\n\n Indented.".to_owned()), None]);
+}
+
+#[test]
+fn debug_dump() {
+    let parse = ast::Grammar::parse("A = B |");
+    expect![[r#"
+        GRAMMAR@0..7
+          NODE@0..7
+            NAME@0..1
+              IDENT@0..1 "A"
+            WHITESPACE@1..2 " "
+            EQ@2..3 "="
+            WHITESPACE@3..4 " "
+            ALT_RULE@4..7
+              NAME_REF@4..5
+                IDENT@4..5 "B"
+              WHITESPACE@5..6 " "
+              PIPE@6..7 "|"
+              ERROR@7..7
+        error 7..7: expected rule
+    "#]]
+    .assert_eq(&parse.debug_dump());
+}
+
+#[test]
+fn edition_selection() {
+    let errors = |text: &str, default_edition| {
+        let parse = ast::Grammar::parse_with_edition(text, default_edition);
+        parse.errors().iter().map(|it| format!("{:?} {it}", it.range())).collect::<Vec<_>>()
+    };
+    let include = "include 'tokens.ungram'\n";
+    assert!(errors(include, Edition::V2).is_empty());
+    assert_eq!(errors(include, Edition::V1), ["0..0 `include` items require edition 2 or later"]);
+
+    // The marker takes precedence over the default.
+    assert!(errors(&format!("// edition: 2\n{include}"), Edition::V1).is_empty());
+    assert_eq!(
+        errors(&format!("\n// edition:1\n{include}"), Edition::V2),
+        ["14..14 `include` items require edition 2 or later"]
+    );
+    // Only comments before the first item count.
+    assert!(errors(&format!("{include}// edition: 1\n"), Edition::V2).is_empty());
+
+    assert_eq!(errors("// edition: 3\n", Edition::V2), ["0..13 invalid edition: \"3\""]);
+}
+
+#[test]
+fn crlf_line_endings() {
+    let text = "/// Docs.\r\nA = 'a'\r\n\r\nB = 'b'\r";
+    let parse = ast::Grammar::parse(text);
+    assert!(!parse.errors().is_empty());
+
+    let config = ParseConfig { line_endings: LineEndings::Crlf, ..Default::default() };
+    let parse = ast::Grammar::parse_with_config(text, config);
+    let errors =
+        parse.errors().iter().map(|it| format!("{:?} {it}", it.range())).collect::<Vec<_>>();
+    assert_eq!(errors, ["29..30 unexpected `\\r`, only Unix-style line endings allowed"]);
+    assert_eq!(parse.tree().nodes().next().unwrap().docs().as_deref(), Some("Docs."));
+    assert_eq!(parse.syntax_node().to_string(), text);
+}
+
+#[test]
+fn token_values() {
+    let parse = ast::Grammar::parse(r"A = 'a\'b' '\\' '🦀x' 'a\zb' ''");
+    let strings = parse
+        .syntax_node()
+        .descendants()
+        .filter_map(ast::Token::cast)
+        .map(|it| it.string().unwrap())
+        .collect::<Vec<_>>();
+    let values = strings.iter().map(|it| it.value()).collect::<Vec<_>>();
+    assert_eq!(values, [Some("a'b".into()), Some("\\".into()), Some("🦀x".into()), None, None]);
+
+    let unescaped = strings[0].unescaped();
+    let quote = TextRange::at(1.into(), 1.into());
+    assert_eq!(unescaped.source_range(quote), Some(TextRange::new(6.into(), 8.into())));
+    assert_eq!(unescaped.source_offset(3.into()), Some(9.into()));
+
+    let unescaped = strings[2].unescaped();
+    assert_eq!(unescaped.source_offset(4.into()), Some(21.into()));
+    assert_eq!(unescaped.source_offset(2.into()), None);
+
+    let unescaped = strings[3].unescaped();
+    assert_eq!(unescaped.value(), "azb");
+    assert!(!unescaped.is_valid());
+}
+
+#[test]
+fn parser_limits() {
+    let errors = |text: &str, limits| {
+        let parse =
+            ast::Grammar::parse_with_config(text, ParseConfig { limits, ..Default::default() });
+        assert_eq!(parse.syntax_node().to_string(), text);
+        parse.errors().iter().map(|it| format!("{:?} {it}", it.range())).collect::<Vec<_>>()
+    };
+
+    // Deep nesting doesn't overflow the stack.
+    let text = format!("A = {}'a'{}\nB = 'b'\n", "(".repeat(100_000), ")".repeat(100_000));
+    assert_eq!(
+        errors(&text, ParserLimits::default()),
+        ["260..260 rules are nested too deeply, depth limit exceeded"]
+    );
+
+    let text = "A = (('a'))\nB = 'b'\n";
+    assert!(errors(text, ParserLimits::default()).is_empty());
+    let limits = ParserLimits { depth: 2, ..Default::default() };
+    assert_eq!(errors(text, limits), ["6..6 rules are nested too deeply, depth limit exceeded"]);
+    let limits = ParserLimits { steps: 2, ..Default::default() };
+    assert_eq!(errors(text, limits), ["4..4 the parser seems stuck, step limit exceeded"]);
+}
+
+#[test]
+#[cfg(feature = "tracking")]
+fn limit_stats() {
+    let (_, stats) = ast::Grammar::parse_with_stats("A = (('a') | B)\n", ParseConfig::default());
+    let stats = stats.iter().map(|it| (it.name, it.max)).collect::<Vec<_>>();
+    assert_eq!(stats, [("steps", Some(3)), ("depth", Some(3))]);
+}
@@ -0,0 +1,434 @@
+//! Generates rust-analyzer style Rust code for the syntax trees of a grammar.
+//!
+//! There are two outputs:
+//!
+//! * the `SyntaxKind` enum with a kind for each token and node, plus the `T!`
+//!   macro which names the kinds of tokens by their text,
+//! * the typed AST: a struct for each node with an accessor for each of its
+//!   [fields](hir::fields), an enum for each node which is an alternative of
+//!   other nodes, and a struct for each token which isn't a keyword or
+//!   punctuation.
+//!
+//! The generated AST expects the crate it is included in to look like the
+//! `syntax` crate of rust-analyzer: the crate root defines the rowan
+//! `Language` (named `Language`), the `SyntaxNode` and `SyntaxToken` aliases
+//! and the `SyntaxKind` enum, and `crate::ast` defines the `AstToken` trait.
+
+mod kinds;
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{
+    fields::{self, Cardinality, FieldTy},
+    Grammar, Node, Rule, RuleData,
+};
+use stdx::{to_camel_case, to_upper_snake_case};
+
+pub use self::kinds::{derive_kinds, render_kinds_src, KindIssue, KindsSrc};
+
+/// Generates the `SyntaxKind` enum and the `T!` macro.
+pub fn generate_syntax_kinds(kinds: &KindsSrc) -> String {
+    let punct = kinds.punct.iter().map(|(_, name)| name.clone()).collect::<Vec<_>>();
+    let keyword_kind = |kw: &String| format!("{}_KW", kw.to_uppercase());
+    let keywords = kinds.keywords.iter().map(keyword_kind).collect::<Vec<_>>();
+    let contextual_keywords =
+        kinds.contextual_keywords.iter().map(keyword_kind).collect::<Vec<_>>();
+
+    let mut buf = String::new();
+    buf.push_str("#![allow(bad_style, missing_docs, unreachable_pub)]\n");
+    buf.push_str("/// The kind of a syntax node or token.\n");
+    buf.push_str("#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]\n");
+    buf.push_str("#[repr(u16)]\n");
+    buf.push_str("pub enum SyntaxKind {\n");
+    buf.push_str("    // Technical SyntaxKinds: they appear temporally during parsing,\n");
+    buf.push_str("    // but never end up in the final tree\n");
+    buf.push_str("    #[doc(hidden)]\n    TOMBSTONE,\n    #[doc(hidden)]\n    EOF,\n");
+    for kind in punct.iter().chain(&keywords).chain(&contextual_keywords).chain(&kinds.tokens) {
+        writeln!(buf, "    {kind},").unwrap();
+    }
+    buf.push_str("    // Technical kind so that we can mark the end of tokens\n");
+    buf.push_str("    #[doc(hidden)]\n    __FIRST,\n");
+    for kind in &kinds.nodes {
+        writeln!(buf, "    {kind},").unwrap();
+    }
+    buf.push_str("    // Technical kind so that we can cast from u16 safely\n");
+    buf.push_str("    #[doc(hidden)]\n    __LAST,\n");
+    buf.push_str("}\n");
+    buf.push_str("use self::SyntaxKind::*;\n\n");
+
+    buf.push_str("impl SyntaxKind {\n");
+    if !keywords.is_empty() {
+        writeln!(
+            buf,
+            "    pub fn is_keyword(self) -> bool {{\n        {}\n    }}\n",
+            matches(&keywords)
+        )
+        .unwrap();
+    }
+    writeln!(buf, "    pub fn is_punct(self) -> bool {{\n        {}\n    }}\n", matches(&punct))
+        .unwrap();
+    if !keywords.is_empty() {
+        let arms = kinds.keywords.iter().zip(&keywords).map(|(kw, kind)| (format!("{kw:?}"), kind));
+        from_text(&mut buf, "from_keyword", "ident: &str", "ident", arms);
+        buf.push('\n');
+    }
+    let arms = kinds.contextual_keywords.iter().zip(&contextual_keywords);
+    let arms = arms.map(|(kw, kind)| (format!("{kw:?}"), kind));
+    from_text(&mut buf, "from_contextual_keyword", "ident: &str", "ident", arms);
+    buf.push('\n');
+    let arms = kinds.punct.iter().filter_map(|(text, name)| {
+        let mut chars = text.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Some((format!("{c:?}"), name)),
+            _ => None,
+        }
+    });
+    from_text(&mut buf, "from_char", "c: char", "c", arms);
+    buf.push_str("}\n");
+
+    let mut rules = Vec::new();
+    for (text, name) in &kinds.punct {
+        if let Some(pattern) = macro_pattern(text) {
+            rules.push((pattern, name));
+        }
+    }
+    let all_keywords = kinds.keywords.iter().chain(&kinds.contextual_keywords);
+    for (kw, kind) in all_keywords.zip(keywords.iter().chain(&contextual_keywords)) {
+        rules.push((kw.clone(), kind));
+    }
+    if !rules.is_empty() {
+        buf.push_str("\n#[macro_export]\nmacro_rules! T {\n");
+        for (pattern, kind) in rules {
+            writeln!(buf, "    [{pattern}] => {{ $crate::SyntaxKind::{kind} }};").unwrap();
+        }
+        buf.push_str("}\n");
+    }
+    buf
+}
+
+/// Generates the typed AST of `grammar`, whose tokens are named by `kinds`.
+pub fn generate_ast(grammar: &Grammar, kinds: &KindsSrc) -> String {
+    let mut nodes = String::new();
+    let mut enums = String::new();
+    let mut display = String::new();
+    let mut uses_children = false;
+    let mut uses_tokens = false;
+
+    for node in grammar.iter() {
+        let name = &grammar[node].name;
+        writeln!(
+            display,
+            "\nimpl std::fmt::Display for {name} {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        std::fmt::Display::fmt(self.syntax(), f)
+    }}
+}}"
+        )
+        .unwrap();
+        if fields::is_enum(grammar, node) {
+            generate_enum(&mut enums, grammar, node);
+            continue;
+        }
+
+        let kind = to_upper_snake_case(name);
+        writeln!(
+            nodes,
+            "\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct {name} {{
+    pub(crate) syntax: SyntaxNode,
+}}"
+        )
+        .unwrap();
+
+        let accessors = accessors(grammar, node, kinds);
+        if !accessors.is_empty() {
+            writeln!(nodes, "\nimpl {name} {{").unwrap();
+            for accessor in accessors {
+                let (ty, body) = match accessor.ty {
+                    AccessorTy::Node(ty) if accessor.many => {
+                        uses_children = true;
+                        (format!("AstChildren<{ty}>"), "support::children(&self.syntax)".to_owned())
+                    }
+                    AccessorTy::Node(ty) => {
+                        (format!("Option<{ty}>"), "support::child(&self.syntax)".to_owned())
+                    }
+                    AccessorTy::Token(kinds) => {
+                        uses_tokens = true;
+                        let body = match kinds.as_slice() {
+                            [kind] => format!("support::token(&self.syntax, {kind})"),
+                            _ => format!(
+                                "self.syntax\n            .children_with_tokens()\n            \
+                                 .filter_map(|it| it.into_token())\n            \
+                                 .find(|it| {})",
+                                matches_kind(&kinds)
+                            ),
+                        };
+                        ("Option<SyntaxToken>".to_owned(), body)
+                    }
+                };
+                let method = method_name(&accessor.name);
+                writeln!(nodes, "    pub fn {method}(&self) -> {ty} {{\n        {body}\n    }}")
+                    .unwrap();
+            }
+            nodes.push_str("}\n");
+        }
+
+        writeln!(
+            nodes,
+            "\nimpl AstNode for {name} {{
+    type Language = Language;
+
+    fn can_cast(kind: SyntaxKind) -> bool {{
+        kind == {kind}
+    }}
+    fn cast(syntax: SyntaxNode) -> Option<Self> {{
+        if Self::can_cast(syntax.kind()) {{
+            Some(Self {{ syntax }})
+        }} else {{
+            None
+        }}
+    }}
+    fn syntax(&self) -> &SyntaxNode {{
+        &self.syntax
+    }}
+}}"
+        )
+        .unwrap();
+    }
+
+    let mut tokens = String::new();
+    for kind in kinds.tokens.iter().filter(|&it| it != "ERROR") {
+        let name = to_camel_case(&kind.to_lowercase());
+        uses_tokens = true;
+        writeln!(
+            tokens,
+            "\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct {name} {{
+    pub(crate) syntax: SyntaxToken,
+}}
+
+impl AstToken for {name} {{
+    fn can_cast(kind: SyntaxKind) -> bool {{
+        kind == {kind}
+    }}
+    fn cast(syntax: SyntaxToken) -> Option<Self> {{
+        if Self::can_cast(syntax.kind()) {{
+            Some(Self {{ syntax }})
+        }} else {{
+            None
+        }}
+    }}
+    fn syntax(&self) -> &SyntaxToken {{
+        &self.syntax
+    }}
+}}
+
+impl std::fmt::Display for {name} {{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{
+        std::fmt::Display::fmt(&self.syntax, f)
+    }}
+}}"
+        )
+        .unwrap();
+    }
+
+    let mut buf = String::new();
+    let rowan_items =
+        if uses_children { "support, AstChildren, AstNode" } else { "support, AstNode" };
+    writeln!(buf, "use rowan::ast::{{{rowan_items}}};\n").unwrap();
+    let ast_token = if tokens.is_empty() { "" } else { "ast::AstToken, " };
+    let syntax_token = if uses_tokens { ", SyntaxToken" } else { "" };
+    writeln!(
+        buf,
+        "use crate::{{{ast_token}Language, SyntaxKind::{{self, *}}, SyntaxNode{syntax_token}}};"
+    )
+    .unwrap();
+    buf.push_str(&nodes);
+    buf.push_str(&enums);
+    buf.push_str(&tokens);
+    buf.push_str(&display);
+    buf
+}
+
+fn generate_enum(buf: &mut String, grammar: &Grammar, node: Node) {
+    let name = &grammar[node].name;
+    let variants = grammar
+        .node_refs(node)
+        .into_iter()
+        .map(|it| grammar[it.target].name.as_str())
+        .fold(Vec::new(), |mut acc, it| {
+            if !acc.contains(&it) {
+                acc.push(it);
+            }
+            acc
+        });
+
+    writeln!(buf, "\n#[derive(Debug, Clone, PartialEq, Eq, Hash)]\npub enum {name} {{").unwrap();
+    for variant in &variants {
+        writeln!(buf, "    {variant}({variant}),").unwrap();
+    }
+    buf.push_str("}\n");
+
+    for variant in &variants {
+        writeln!(
+            buf,
+            "\nimpl From<{variant}> for {name} {{
+    fn from(node: {variant}) -> {name} {{
+        {name}::{variant}(node)
+    }}
+}}"
+        )
+        .unwrap();
+    }
+
+    let can_cast = variants.iter().map(|it| format!("{it}::can_cast(kind)")).collect::<Vec<_>>();
+    writeln!(
+        buf,
+        "\nimpl AstNode for {name} {{
+    type Language = Language;
+
+    fn can_cast(kind: SyntaxKind) -> bool {{
+        {}
+    }}
+    fn cast(syntax: SyntaxNode) -> Option<Self> {{",
+        can_cast.join(" || "),
+    )
+    .unwrap();
+    for variant in &variants {
+        writeln!(
+            buf,
+            "        if {variant}::can_cast(syntax.kind()) {{
+            return {variant}::cast(syntax).map({name}::{variant});
+        }}"
+        )
+        .unwrap();
+    }
+    buf.push_str(
+        "        None\n    }\n    fn syntax(&self) -> &SyntaxNode {\n        match self {\n",
+    );
+    for variant in &variants {
+        writeln!(buf, "            {name}::{variant}(it) => it.syntax(),").unwrap();
+    }
+    buf.push_str("        }\n    }\n}\n");
+}
+
+struct Accessor {
+    name: String,
+    ty: AccessorTy,
+    many: bool,
+}
+
+enum AccessorTy {
+    Node(String),
+    /// The names of the kinds of the token, more than one for a label over
+    /// several tokens, as in `op:('+' | '-')`.
+    Token(Vec<String>),
+}
+
+/// Returns the accessors of a struct node. Fields which can't be told apart
+/// are skipped: those whose name clashes, and node fields of a type which
+/// another field also has, as `support::child` would return the same node
+/// for both. Token fields are skipped unless every token has a kind.
+fn accessors(grammar: &Grammar, node: Node, kinds: &KindsSrc) -> Vec<Accessor> {
+    let node_fields = fields::node_fields(grammar, node);
+    let clashes = |name: &str| node_fields.clashes.iter().any(|it| it.name == name);
+    let shared = |ty: FieldTy| node_fields.fields.iter().filter(|it| it.ty == ty).count() > 1;
+
+    let mut res = Vec::new();
+    for field in &node_fields.fields {
+        if clashes(&field.name) {
+            continue;
+        }
+        let many = field.cardinality == Cardinality::Many;
+        let ty = match field.ty {
+            FieldTy::Node(_) if shared(field.ty) => continue,
+            FieldTy::Node(target) => AccessorTy::Node(grammar[target].name.clone()),
+            FieldTy::Token(_) => match token_kinds(grammar, &field.atoms, kinds) {
+                Some(kinds) => AccessorTy::Token(kinds),
+                None => continue,
+            },
+        };
+        res.push(Accessor { name: field.name.clone(), ty, many });
+    }
+    res
+}
+
+/// Returns the kinds of the token `atoms` of a field, without duplicates.
+fn token_kinds(grammar: &Grammar, atoms: &[Rule], kinds: &KindsSrc) -> Option<Vec<String>> {
+    let mut res = Vec::new();
+    for &atom in atoms {
+        let RuleData::Token(token) = grammar[atom] else { continue };
+        let kind = kinds.token_kind(&grammar[token].name)?;
+        if !res.contains(&kind) {
+            res.push(kind);
+        }
+    }
+    Some(res)
+}
+
+/// Escapes field names which are Rust keywords.
+fn method_name(name: &str) -> String {
+    match name {
+        "self" | "Self" | "super" | "crate" => format!("{name}_"),
+        _ if RUST_KEYWORDS.contains(&name) => format!("r#{name}"),
+        _ => name.to_owned(),
+    }
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let",
+    "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return",
+    "static", "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use",
+    "virtual", "where", "while", "yield",
+];
+
+/// Returns the `T!` pattern for a punctuation, or `None` if the punctuation
+/// can't be written in a macro pattern.
+fn macro_pattern(text: &str) -> Option<String> {
+    if matches!(text, "(" | ")" | "[" | "]" | "{" | "}" | "'") {
+        let c = text.chars().next().unwrap();
+        return Some(format!("{c:?}"));
+    }
+    // `$` is special in macros and `//` or `/*` would start a comment.
+    let valid = !text.is_empty()
+        && text.chars().all(|c| "=<>!~+-*/%^&|@.,;:#?".contains(c))
+        && !text.contains("//")
+        && !text.contains("/*");
+    valid.then(|| text.to_owned())
+}
+
+/// Like [`matches`], for the kind of the token `it`.
+fn matches_kind(kinds: &[String]) -> String {
+    format!("matches!(it.kind(), {})", kinds.join(" | "))
+}
+
+fn matches(kinds: &[String]) -> String {
+    if kinds.is_empty() {
+        return "false".to_owned();
+    }
+    format!("matches!(self, {})", kinds.join(" | "))
+}
+
+fn from_text<'a>(
+    buf: &mut String,
+    name: &str,
+    param: &str,
+    scrutinee: &str,
+    arms: impl Iterator<Item = (String, &'a String)>,
+) {
+    let arms = arms.collect::<Vec<_>>();
+    writeln!(buf, "    pub fn {name}({param}) -> Option<SyntaxKind> {{").unwrap();
+    if arms.is_empty() {
+        writeln!(buf, "        let _ = {scrutinee};\n        None\n    }}").unwrap();
+        return;
+    }
+    writeln!(buf, "        let kind = match {scrutinee} {{").unwrap();
+    for (pattern, kind) in arms {
+        writeln!(buf, "            {pattern} => {kind},").unwrap();
+    }
+    buf.push_str("            _ => return None,\n        };\n        Some(kind)\n    }\n");
+}
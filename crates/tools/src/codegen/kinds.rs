@@ -0,0 +1,201 @@
+//! Derives the syntax kinds of a grammar from its token literals and nodes.
+//!
+//! Ungrammar doesn't say what a token literal stands for, so the kinds are
+//! guessed by convention, the one rust-analyzer's grammar follows:
+//!
+//! * punctuation, e.g. `'('` or `'->'`, is spelled with ASCII symbols,
+//! * keywords, e.g. `'fn'`, look like identifiers,
+//! * literals naming a kind of token rather than its text, e.g. `'ident'` or
+//!   `'int_number'`, are either well-known names or written in upper case,
+//!   e.g. `'IDENT'`.
+//!
+//! Every node which isn't an enum gets a node kind. Literals which fit none
+//! of the conventions, or only doubtfully, are reported so they can be
+//! classified by hand.
+
+use std::fmt::Write;
+
+use hir::{fields, Grammar};
+use rustc_hash::FxHashMap;
+use stdx::to_upper_snake_case;
+
+/// The kinds of the tokens and nodes of a grammar.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct KindsSrc {
+    /// The text and kind name of punctuation, e.g. `("(", "L_PAREN")`.
+    pub punct: Vec<(String, String)>,
+    /// Identifiers which are always keywords, e.g. `fn`.
+    pub keywords: Vec<String>,
+    /// Identifiers which are keywords only in some positions, so they can
+    /// still be used as names.
+    pub contextual_keywords: Vec<String>,
+    /// Tokens which aren't spelled the same way every time, e.g. `IDENT`.
+    pub tokens: Vec<String>,
+    pub nodes: Vec<String>,
+}
+
+/// A token literal or node whose kind needs a decision by hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KindIssue {
+    /// The literal, quoted, or the name of the node.
+    pub subject: String,
+    pub message: String,
+}
+
+impl KindsSrc {
+    /// Derives the kinds of a grammar, see [`derive_kinds`].
+    pub fn from_grammar(grammar: &Grammar) -> KindsSrc {
+        derive_kinds(grammar).0
+    }
+
+    /// Returns the name of the kind of the token spelled `text`.
+    pub fn token_kind(&self, text: &str) -> Option<String> {
+        if let Some((_, name)) = self.punct.iter().find(|(punct, _)| punct == text) {
+            return Some(name.clone());
+        }
+        if self.keywords.iter().chain(&self.contextual_keywords).any(|kw| kw == text) {
+            return Some(format!("{}_KW", text.to_uppercase()));
+        }
+        let kind = text.to_uppercase();
+        self.tokens.contains(&kind).then_some(kind)
+    }
+}
+
+/// Classifies the token literals and nodes of a grammar, reporting the
+/// literals which don't follow the conventions and the kinds which clash.
+///
+/// Unclassified literals get no kind. The tokens always include `ERROR`,
+/// `WHITESPACE` and `COMMENT`, which a lexer produces without the grammar
+/// mentioning them.
+pub fn derive_kinds(grammar: &Grammar) -> (KindsSrc, Vec<KindIssue>) {
+    let mut kinds = KindsSrc {
+        tokens: ["ERROR", "WHITESPACE", "COMMENT"].map(String::from).to_vec(),
+        ..KindsSrc::default()
+    };
+    let mut issues = Vec::new();
+    for token in grammar.tokens() {
+        let text = &grammar[token].name;
+        let subject = format!("'{text}'");
+        match classify(text) {
+            Ok(TokenClass::Punct) => {
+                kinds.punct.push((text.clone(), fields::token_name(text).to_uppercase()))
+            }
+            Ok(TokenClass::Keyword) => {
+                if text.contains('_') {
+                    let message = "classified as a keyword, but may name a kind of token";
+                    issues.push(KindIssue { subject, message: message.to_owned() });
+                }
+                kinds.keywords.push(text.clone());
+            }
+            Ok(TokenClass::Token) => {
+                let kind = text.to_uppercase();
+                if !kinds.tokens.contains(&kind) {
+                    kinds.tokens.push(kind);
+                }
+            }
+            Err(message) => issues.push(KindIssue { subject, message: message.to_owned() }),
+        }
+    }
+    let nodes = grammar.iter().filter(|&node| !fields::is_enum(grammar, node));
+    let nodes = nodes.map(|node| &grammar[node].name).collect::<Vec<_>>();
+    kinds.nodes = nodes.iter().map(|name| to_upper_snake_case(name)).collect();
+
+    let mut owners = FxHashMap::<String, String>::default();
+    let punct = kinds.punct.iter().map(|(text, kind)| (format!("'{text}'"), kind.clone()));
+    let keywords =
+        kinds.keywords.iter().map(|kw| (format!("'{kw}'"), format!("{}_KW", kw.to_uppercase())));
+    let tokens = kinds.tokens.iter().map(|kind| (kind.clone(), kind.clone()));
+    let nodes = nodes.iter().zip(&kinds.nodes).map(|(&name, kind)| (name.clone(), kind.clone()));
+    for (subject, kind) in punct.chain(keywords).chain(tokens).chain(nodes) {
+        match owners.get(&kind) {
+            Some(owner) if *owner != subject => issues.push(KindIssue {
+                subject,
+                message: format!("uses the kind `{kind}`, like {owner}"),
+            }),
+            Some(_) => (),
+            None => {
+                owners.insert(kind, subject);
+            }
+        }
+    }
+    (kinds, issues)
+}
+
+/// Renders the kinds like the hand-written table they replace, as a
+/// `KindsSrc` of string slices.
+pub fn render_kinds_src(kinds: &KindsSrc) -> String {
+    let mut buf = String::from("KindsSrc {\n    punct: &[\n");
+    for (text, name) in &kinds.punct {
+        writeln!(buf, "        ({text:?}, {name:?}),").unwrap();
+    }
+    buf.push_str("    ],\n");
+    for (field, values) in [
+        ("keywords", &kinds.keywords),
+        ("contextual_keywords", &kinds.contextual_keywords),
+        ("tokens", &kinds.tokens),
+        ("nodes", &kinds.nodes),
+    ] {
+        if values.is_empty() {
+            writeln!(buf, "    {field}: &[],").unwrap();
+            continue;
+        }
+        writeln!(buf, "    {field}: &[").unwrap();
+        for value in values {
+            writeln!(buf, "        {value:?},").unwrap();
+        }
+        buf.push_str("    ],\n");
+    }
+    buf.push_str("}\n");
+    buf
+}
+
+enum TokenClass {
+    Punct,
+    Keyword,
+    Token,
+}
+
+fn classify(text: &str) -> Result<TokenClass, &'static str> {
+    if text.is_empty() {
+        return Err("is empty, so no lexer can produce it");
+    }
+    if is_ident(text) {
+        let upper_case = text.chars().all(|c| !c.is_ascii_lowercase());
+        if upper_case || TOKEN_NAMES.contains(&text) {
+            return Ok(TokenClass::Token);
+        }
+        return Ok(TokenClass::Keyword);
+    }
+    if text.chars().all(|c| c.is_ascii_punctuation()) {
+        return Ok(TokenClass::Punct);
+    }
+    if text.chars().any(char::is_whitespace) {
+        return Err("contains whitespace, so it isn't a single token");
+    }
+    if !text.is_ascii() {
+        return Err("contains non-ASCII characters");
+    }
+    Err("mixes letters and punctuation")
+}
+
+fn is_ident(text: &str) -> bool {
+    text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && text != "_"
+}
+
+/// Literals which name a kind of token in rust-analyzer's grammar, and in
+/// grammars imitating it.
+const TOKEN_NAMES: &[&str] = &[
+    "ident",
+    "lifetime_ident",
+    "int_number",
+    "float_number",
+    "number",
+    "string",
+    "byte_string",
+    "c_string",
+    "char",
+    "byte",
+    "shebang",
+];
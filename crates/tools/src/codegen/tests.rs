@@ -0,0 +1,923 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    codegen::{derive_kinds, generate_ast, generate_syntax_kinds, render_kinds_src, KindsSrc},
+    lower,
+};
+
+fn check_kinds(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    expect.assert_eq(&generate_syntax_kinds(&KindsSrc::from_grammar(&grammar)));
+}
+
+fn check_ast(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    expect.assert_eq(&generate_ast(&grammar, &KindsSrc::from_grammar(&grammar)));
+}
+
+#[test]
+fn syntax_kinds() {
+    check_kinds(
+        r#"
+Fn = 'fn' Name ParamList '->' Type
+ParamList = '(' Param* ')'
+"#,
+        expect![[r##"
+            #![allow(bad_style, missing_docs, unreachable_pub)]
+            /// The kind of a syntax node or token.
+            #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+            #[repr(u16)]
+            pub enum SyntaxKind {
+                // Technical SyntaxKinds: they appear temporally during parsing,
+                // but never end up in the final tree
+                #[doc(hidden)]
+                TOMBSTONE,
+                #[doc(hidden)]
+                EOF,
+                THIN_ARROW,
+                L_PAREN,
+                R_PAREN,
+                FN_KW,
+                ERROR,
+                WHITESPACE,
+                COMMENT,
+                // Technical kind so that we can mark the end of tokens
+                #[doc(hidden)]
+                __FIRST,
+                FN,
+                PARAM_LIST,
+                NAME,
+                TYPE,
+                PARAM,
+                // Technical kind so that we can cast from u16 safely
+                #[doc(hidden)]
+                __LAST,
+            }
+            use self::SyntaxKind::*;
+
+            impl SyntaxKind {
+                pub fn is_keyword(self) -> bool {
+                    matches!(self, FN_KW)
+                }
+
+                pub fn is_punct(self) -> bool {
+                    matches!(self, THIN_ARROW | L_PAREN | R_PAREN)
+                }
+
+                pub fn from_keyword(ident: &str) -> Option<SyntaxKind> {
+                    let kind = match ident {
+                        "fn" => FN_KW,
+                        _ => return None,
+                    };
+                    Some(kind)
+                }
+
+                pub fn from_contextual_keyword(ident: &str) -> Option<SyntaxKind> {
+                    let _ = ident;
+                    None
+                }
+
+                pub fn from_char(c: char) -> Option<SyntaxKind> {
+                    let kind = match c {
+                        '(' => L_PAREN,
+                        ')' => R_PAREN,
+                        _ => return None,
+                    };
+                    Some(kind)
+                }
+            }
+
+            #[macro_export]
+            macro_rules! T {
+                [->] => { $crate::SyntaxKind::THIN_ARROW };
+                ['('] => { $crate::SyntaxKind::L_PAREN };
+                [')'] => { $crate::SyntaxKind::R_PAREN };
+                [fn] => { $crate::SyntaxKind::FN_KW };
+            }
+        "##]],
+    );
+}
+
+#[test]
+fn labeled_tokens() {
+    check_ast(
+        r#"
+PrefixExpr = op:('-' | '!' | 'not' | '-') Expr
+"#,
+        expect![[r#"
+            use rowan::ast::{support, AstNode};
+
+            use crate::{ast::AstToken, Language, SyntaxKind::{self, *}, SyntaxNode, SyntaxToken};
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct PrefixExpr {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl PrefixExpr {
+                pub fn op(&self) -> Option<SyntaxToken> {
+                    self.syntax
+                        .children_with_tokens()
+                        .filter_map(|it| it.into_token())
+                        .find(|it| matches!(it.kind(), MINUS | EXCL | NOT_KW))
+                }
+                pub fn expr(&self) -> Option<Expr> {
+                    support::child(&self.syntax)
+                }
+            }
+
+            impl AstNode for PrefixExpr {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == PREFIX_EXPR
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Expr {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Expr {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == EXPR
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Whitespace {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Whitespace {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == WHITESPACE
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Whitespace {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Comment {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Comment {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == COMMENT
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Comment {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            impl std::fmt::Display for PrefixExpr {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Expr {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn struct_nodes() {
+    check_ast(
+        r#"
+Fn = 'fn' Name ParamList ret:Type? body:Block
+ParamList = '(' params:(Param (',' Param)* ','?)? ')'
+BinExpr = lhs:Expr op:'+' rhs:Expr
+Pair = Type Type
+"#,
+        expect![[r#"
+            use rowan::ast::{support, AstChildren, AstNode};
+
+            use crate::{ast::AstToken, Language, SyntaxKind::{self, *}, SyntaxNode, SyntaxToken};
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Fn {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl Fn {
+                pub fn fn_token(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, FN_KW)
+                }
+                pub fn name(&self) -> Option<Name> {
+                    support::child(&self.syntax)
+                }
+                pub fn param_list(&self) -> Option<ParamList> {
+                    support::child(&self.syntax)
+                }
+                pub fn ret(&self) -> Option<Type> {
+                    support::child(&self.syntax)
+                }
+                pub fn body(&self) -> Option<Block> {
+                    support::child(&self.syntax)
+                }
+            }
+
+            impl AstNode for Fn {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == FN
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct ParamList {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl ParamList {
+                pub fn l_paren_token(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, L_PAREN)
+                }
+                pub fn params(&self) -> AstChildren<Param> {
+                    support::children(&self.syntax)
+                }
+                pub fn r_paren_token(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, R_PAREN)
+                }
+            }
+
+            impl AstNode for ParamList {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == PARAM_LIST
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct BinExpr {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl BinExpr {
+                pub fn op(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, PLUS)
+                }
+            }
+
+            impl AstNode for BinExpr {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == BIN_EXPR
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Pair {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Pair {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == PAIR
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Name {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Name {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == NAME
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Type {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Type {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == TYPE
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Block {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Block {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == BLOCK
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Param {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Param {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == PARAM
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Expr {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Expr {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == EXPR
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Whitespace {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Whitespace {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == WHITESPACE
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Whitespace {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Comment {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Comment {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == COMMENT
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Comment {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            impl std::fmt::Display for Fn {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for ParamList {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for BinExpr {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Pair {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Type {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Block {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Param {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Expr {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+        "#]],
+    );
+}
+
+#[test]
+fn enum_nodes() {
+    check_ast(
+        r#"
+Item = Fn | Struct | Fn
+Fn = 'fn' type:Name
+Struct = 'struct' self:Name
+"#,
+        expect![[r#"
+            use rowan::ast::{support, AstNode};
+
+            use crate::{ast::AstToken, Language, SyntaxKind::{self, *}, SyntaxNode, SyntaxToken};
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Fn {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl Fn {
+                pub fn fn_token(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, FN_KW)
+                }
+                pub fn r#type(&self) -> Option<Name> {
+                    support::child(&self.syntax)
+                }
+            }
+
+            impl AstNode for Fn {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == FN
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Struct {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl Struct {
+                pub fn struct_token(&self) -> Option<SyntaxToken> {
+                    support::token(&self.syntax, STRUCT_KW)
+                }
+                pub fn self_(&self) -> Option<Name> {
+                    support::child(&self.syntax)
+                }
+            }
+
+            impl AstNode for Struct {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == STRUCT
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Name {
+                pub(crate) syntax: SyntaxNode,
+            }
+
+            impl AstNode for Name {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == NAME
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    &self.syntax
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub enum Item {
+                Fn(Fn),
+                Struct(Struct),
+            }
+
+            impl From<Fn> for Item {
+                fn from(node: Fn) -> Item {
+                    Item::Fn(node)
+                }
+            }
+
+            impl From<Struct> for Item {
+                fn from(node: Struct) -> Item {
+                    Item::Struct(node)
+                }
+            }
+
+            impl AstNode for Item {
+                type Language = Language;
+
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    Fn::can_cast(kind) || Struct::can_cast(kind)
+                }
+                fn cast(syntax: SyntaxNode) -> Option<Self> {
+                    if Fn::can_cast(syntax.kind()) {
+                        return Fn::cast(syntax).map(Item::Fn);
+                    }
+                    if Struct::can_cast(syntax.kind()) {
+                        return Struct::cast(syntax).map(Item::Struct);
+                    }
+                    None
+                }
+                fn syntax(&self) -> &SyntaxNode {
+                    match self {
+                        Item::Fn(it) => it.syntax(),
+                        Item::Struct(it) => it.syntax(),
+                    }
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Whitespace {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Whitespace {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == WHITESPACE
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Whitespace {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+            pub struct Comment {
+                pub(crate) syntax: SyntaxToken,
+            }
+
+            impl AstToken for Comment {
+                fn can_cast(kind: SyntaxKind) -> bool {
+                    kind == COMMENT
+                }
+                fn cast(syntax: SyntaxToken) -> Option<Self> {
+                    if Self::can_cast(syntax.kind()) {
+                        Some(Self { syntax })
+                    } else {
+                        None
+                    }
+                }
+                fn syntax(&self) -> &SyntaxToken {
+                    &self.syntax
+                }
+            }
+
+            impl std::fmt::Display for Comment {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(&self.syntax, f)
+                }
+            }
+
+            impl std::fmt::Display for Item {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Fn {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Struct {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+
+            impl std::fmt::Display for Name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    std::fmt::Display::fmt(self.syntax(), f)
+                }
+            }
+        "#]],
+    );
+}
+
+fn check_derived_kinds(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    let (kinds, issues) = derive_kinds(&grammar);
+    let mut actual = render_kinds_src(&kinds);
+    for issue in issues {
+        actual.push_str(&format!("{}: {}\n", issue.subject, issue.message));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn derived_kinds() {
+    check_derived_kinds(
+        r#"
+Fn = 'fn' Name ParamList '->' RetType? Block
+Name = 'ident' | 'IDENT'
+Literal = 'int_number' | 'string'
+Block = '{' Stmt* '}'
+Stmt = Fn | Block
+MacroRules = 'macro_rules' '!'
+"#,
+        expect![[r#"
+            KindsSrc {
+                punct: &[
+                    ("->", "THIN_ARROW"),
+                    ("{", "L_CURLY"),
+                    ("}", "R_CURLY"),
+                    ("!", "EXCL"),
+                ],
+                keywords: &[
+                    "fn",
+                    "macro_rules",
+                ],
+                contextual_keywords: &[],
+                tokens: &[
+                    "ERROR",
+                    "WHITESPACE",
+                    "COMMENT",
+                    "IDENT",
+                    "INT_NUMBER",
+                    "STRING",
+                ],
+                nodes: &[
+                    "FN",
+                    "NAME",
+                    "LITERAL",
+                    "BLOCK",
+                    "MACRO_RULES",
+                    "PARAM_LIST",
+                    "RET_TYPE",
+                ],
+            }
+            'macro_rules': classified as a keyword, but may name a kind of token
+        "#]],
+    );
+}
+
+#[test]
+fn unclassified_kinds() {
+    check_derived_kinds(
+        r#"
+Odd = '' 'a b' 'é' 'r#' '_'
+Comma = ','
+Underscore = 'UNDERSCORE'
+"#,
+        expect![[r#"
+            KindsSrc {
+                punct: &[
+                    ("_", "UNDERSCORE"),
+                    (",", "COMMA"),
+                ],
+                keywords: &[],
+                contextual_keywords: &[],
+                tokens: &[
+                    "ERROR",
+                    "WHITESPACE",
+                    "COMMENT",
+                    "UNDERSCORE",
+                ],
+                nodes: &[
+                    "ODD",
+                    "COMMA",
+                    "UNDERSCORE",
+                ],
+            }
+            '': is empty, so no lexer can produce it
+            'a b': contains whitespace, so it isn't a single token
+            'é': contains non-ASCII characters
+            'r#': mixes letters and punctuation
+            UNDERSCORE: uses the kind `UNDERSCORE`, like '_'
+            Comma: uses the kind `COMMA`, like ','
+            Underscore: uses the kind `UNDERSCORE`, like '_'
+        "#]],
+    );
+}
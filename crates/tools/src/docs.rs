@@ -0,0 +1,298 @@
+//! Generates the reference documentation of a grammar, as Markdown or HTML.
+//!
+//! The reference has an index of the nodes and a page for each node with its
+//! doc comment, its pretty-printed rule, the fields the typed AST infers for
+//! it (see [`hir::fields`]), the nodes it references and the nodes which
+//! reference it. Doc comments are copied as they are, they are expected to
+//! be Markdown.
+//!
+//! Pages are first built as a list of [`Block`]s, so both formats share the
+//! structure and only differ in how it is rendered.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{
+    fields::{self, Cardinality, FieldClashKind, FieldTy},
+    Grammar, GrammarSourceMap, Node,
+};
+use syntax::{ast, AstNode};
+
+use crate::{
+    railroad::escape,
+    ungrammar::{production_source, GrammarRule},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocsFormat {
+    Markdown,
+    Html,
+}
+
+impl DocsFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DocsFormat::Markdown => "md",
+            DocsFormat::Html => "html",
+        }
+    }
+}
+
+/// Returns the pages of the reference of `file`, as pairs of a file name and
+/// its contents: `index.md` or `index.html`, and a page named after each
+/// node.
+pub fn reference(file: &ast::Grammar, title: &str, format: DocsFormat) -> Vec<(String, String)> {
+    let (grammar, source_map) = Grammar::lower(file);
+    let gen = Generator { grammar: &grammar, source_map: &source_map, file, format };
+    let mut res = vec![(format!("index.{}", format.extension()), gen.render(title, &gen.index()))];
+    for node in grammar.iter() {
+        let name = &grammar[node].name;
+        res.push((gen.page_name(node), gen.render(name, &gen.node_page(node))));
+    }
+    res
+}
+
+/// Returns the rule of `node` in Ungrammar notation, e.g. `Fn = 'fn' Name`,
+/// or `None` if the node isn't defined. Alternatives which don't fit on one
+/// line are put on lines of their own.
+pub fn node_source(grammar: &Grammar, node: Node) -> Option<String> {
+    let rule = grammar[node].rule?;
+    Some(production_source(&grammar[node].name, &GrammarRule { grammar, rule }))
+}
+
+enum Block {
+    Heading(String),
+    Paragraph(Vec<Inline>),
+    /// A doc comment, in Markdown.
+    Docs(String),
+    Code(String),
+    List(Vec<Vec<Inline>>),
+}
+
+enum Inline {
+    Text(String),
+    Code(String),
+    Link(Node),
+}
+
+struct Generator<'a> {
+    grammar: &'a Grammar,
+    source_map: &'a GrammarSourceMap,
+    file: &'a ast::Grammar,
+    format: DocsFormat,
+}
+
+impl Generator<'_> {
+    fn page_name(&self, node: Node) -> String {
+        format!("{}.{}", self.grammar[node].name, self.format.extension())
+    }
+
+    fn docs(&self, node: Node) -> Option<String> {
+        let def = self.source_map.node_def(node)?;
+        def.to_node(self.file.syntax()).docs()
+    }
+
+    fn index(&self) -> Vec<Block> {
+        let items = self
+            .grammar
+            .iter()
+            .map(|node| {
+                let mut item = vec![Inline::Link(node)];
+                // The first line of the docs serves as a summary.
+                if let Some(summary) = self.docs(node).as_deref().and_then(|it| it.lines().next()) {
+                    item.push(Inline::Text(format!(": {}", summary.trim())));
+                }
+                item
+            })
+            .collect();
+        vec![Block::List(items)]
+    }
+
+    fn node_page(&self, node: Node) -> Vec<Block> {
+        let grammar = self.grammar;
+        let name = &grammar[node].name;
+        let mut res = Vec::new();
+        let Some(source) = node_source(grammar, node) else {
+            res.push(Block::Paragraph(vec![
+                Inline::Code(name.clone()),
+                Inline::Text(" is referenced but not defined.".to_owned()),
+            ]));
+            res.extend(self.node_list("Referenced by", self.referenced_by(node)));
+            return res;
+        };
+        if let Some(docs) = self.docs(node) {
+            res.push(Block::Docs(docs));
+        }
+        res.push(Block::Code(source));
+
+        res.push(Block::Heading("Fields".to_owned()));
+        let node_fields = fields::node_fields(grammar, node);
+        if fields::is_enum(grammar, node) {
+            res.push(Block::Paragraph(vec![
+                Inline::Code(name.clone()),
+                Inline::Text(" is an enum of the nodes it references.".to_owned()),
+            ]));
+        } else if node_fields.fields.is_empty() {
+            res.push(Block::Paragraph(vec![Inline::Text("None.".to_owned())]));
+        } else {
+            let items = node_fields.fields.iter().map(|field| {
+                let mut item =
+                    vec![Inline::Code(field.name.clone()), Inline::Text(": ".to_owned())];
+                item.push(match field.ty {
+                    FieldTy::Node(node) => Inline::Link(node),
+                    FieldTy::Token(token) => Inline::Code(format!("'{}'", grammar[token].name)),
+                });
+                if field.cardinality == Cardinality::Many {
+                    item.push(Inline::Text(", repeated".to_owned()));
+                }
+                let clash = node_fields.clashes.iter().find(|it| it.name == field.name);
+                match clash.map(|it| it.kind) {
+                    Some(FieldClashKind::Repeated) => item
+                        .push(Inline::Text(", ambiguous as it occurs more than once".to_owned())),
+                    Some(FieldClashKind::Conflicting) => {
+                        item.push(Inline::Text(", ambiguous as its types differ".to_owned()))
+                    }
+                    None => (),
+                }
+                item
+            });
+            res.push(Block::List(items.collect()));
+        }
+
+        let mut references = Vec::new();
+        for it in grammar.node_refs(node) {
+            if !references.contains(&it.target) {
+                references.push(it.target);
+            }
+        }
+        res.extend(self.node_list("References", references));
+        res.extend(self.node_list("Referenced by", self.referenced_by(node)));
+        res
+    }
+
+    fn referenced_by(&self, node: Node) -> Vec<Node> {
+        let mut res = Vec::new();
+        for it in self.grammar.usages(node) {
+            if !res.contains(&it.container) {
+                res.push(it.container);
+            }
+        }
+        res
+    }
+
+    fn node_list(&self, heading: &str, nodes: Vec<Node>) -> Vec<Block> {
+        if nodes.is_empty() {
+            return Vec::new();
+        }
+        let items = nodes.into_iter().map(|it| vec![Inline::Link(it)]).collect();
+        vec![Block::Heading(heading.to_owned()), Block::List(items)]
+    }
+
+    fn render(&self, title: &str, blocks: &[Block]) -> String {
+        match self.format {
+            DocsFormat::Markdown => self.render_markdown(title, blocks),
+            DocsFormat::Html => self.render_html(title, blocks),
+        }
+    }
+
+    fn render_markdown(&self, title: &str, blocks: &[Block]) -> String {
+        let mut buf = format!("# {title}\n");
+        for block in blocks {
+            buf.push('\n');
+            match block {
+                Block::Heading(text) => writeln!(buf, "## {text}").unwrap(),
+                Block::Paragraph(inlines) => {
+                    writeln!(buf, "{}", self.markdown_inlines(inlines)).unwrap()
+                }
+                Block::Docs(docs) => writeln!(buf, "{docs}").unwrap(),
+                Block::Code(code) => {
+                    // The fence has to be longer than any run of backticks in
+                    // the code.
+                    let fence = "`".repeat(longest_backtick_run(code).max(2) + 1);
+                    writeln!(buf, "{fence}ungrammar\n{code}\n{fence}").unwrap();
+                }
+                Block::List(items) => {
+                    for item in items {
+                        writeln!(buf, "- {}", self.markdown_inlines(item)).unwrap();
+                    }
+                }
+            }
+        }
+        buf
+    }
+
+    fn markdown_inlines(&self, inlines: &[Inline]) -> String {
+        let mut buf = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => buf.push_str(text),
+                Inline::Code(code) => {
+                    let ticks = "`".repeat(longest_backtick_run(code) + 1);
+                    let pad = if code.starts_with('`') || code.ends_with('`') { " " } else { "" };
+                    write!(buf, "{ticks}{pad}{code}{pad}{ticks}").unwrap();
+                }
+                Inline::Link(node) => {
+                    write!(buf, "[{}]({})", self.grammar[*node].name, self.page_name(*node))
+                        .unwrap()
+                }
+            }
+        }
+        buf
+    }
+
+    fn render_html(&self, title: &str, blocks: &[Block]) -> String {
+        let title = escape(title);
+        let mut buf = String::new();
+        writeln!(buf, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+        writeln!(buf, "<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>").unwrap();
+        for block in blocks {
+            match block {
+                Block::Heading(text) => writeln!(buf, "<h2>{}</h2>", escape(text)).unwrap(),
+                Block::Paragraph(inlines) => {
+                    writeln!(buf, "<p>{}</p>", self.html_inlines(inlines)).unwrap()
+                }
+                // Rendering Markdown is out of scope, so the docs are kept as
+                // paragraphs of plain text.
+                Block::Docs(docs) => {
+                    for paragraph in docs.split("\n\n").map(str::trim).filter(|it| !it.is_empty()) {
+                        writeln!(buf, "<p>{}</p>", escape(paragraph)).unwrap();
+                    }
+                }
+                Block::Code(code) => {
+                    writeln!(buf, "<pre><code>{}</code></pre>", escape(code)).unwrap()
+                }
+                Block::List(items) => {
+                    buf.push_str("<ul>\n");
+                    for item in items {
+                        writeln!(buf, "<li>{}</li>", self.html_inlines(item)).unwrap();
+                    }
+                    buf.push_str("</ul>\n");
+                }
+            }
+        }
+        buf.push_str("</body>\n</html>\n");
+        buf
+    }
+
+    fn html_inlines(&self, inlines: &[Inline]) -> String {
+        let mut buf = String::new();
+        for inline in inlines {
+            match inline {
+                Inline::Text(text) => buf.push_str(&escape(text)),
+                Inline::Code(code) => write!(buf, "<code>{}</code>", escape(code)).unwrap(),
+                Inline::Link(node) => {
+                    let name = escape(&self.grammar[*node].name);
+                    write!(buf, "<a href=\"{}\">{name}</a>", escape(&self.page_name(*node)))
+                        .unwrap()
+                }
+            }
+        }
+        buf
+    }
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
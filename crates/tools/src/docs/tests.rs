@@ -0,0 +1,198 @@
+use expect_test::{expect, Expect};
+use syntax::ast;
+
+use crate::{
+    docs::{node_source, reference, DocsFormat},
+    lower,
+};
+
+fn check_page(ungrammar: &str, format: DocsFormat, page: &str, expect: Expect) {
+    let parse = ast::Grammar::parse(ungrammar);
+    let pages = reference(&parse.tree(), "test", format);
+    let (_, contents) = pages.iter().find(|(name, _)| name == page).unwrap();
+    expect.assert_eq(contents);
+}
+
+fn check_source(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    let sources = grammar.iter().filter_map(|node| node_source(&grammar, node));
+    expect.assert_eq(&sources.collect::<Vec<_>>().join("\n"));
+}
+
+const GRAMMAR: &str = r#"
+/// An item of a file.
+Item = Fn | Struct
+
+/// A function definition.
+///
+/// Functions without a body are declarations.
+Fn = 'fn' name:Name ParamList ('->' ret:Type)? body:Block?
+ParamList = '(' (Param (',' Param)* ','?)? ')'
+Param = Name ':' Type
+Struct = 'struct' Name '{' '}'
+Name = 'ident'
+"#;
+
+#[test]
+fn markdown_index() {
+    check_page(
+        GRAMMAR,
+        DocsFormat::Markdown,
+        "index.md",
+        expect![[r#"
+        # test
+
+        - [Item](Item.md): An item of a file.
+        - [Fn](Fn.md): A function definition.
+        - [ParamList](ParamList.md)
+        - [Param](Param.md)
+        - [Struct](Struct.md)
+        - [Name](Name.md)
+        - [Type](Type.md)
+        - [Block](Block.md)
+    "#]],
+    );
+}
+
+#[test]
+fn markdown_page() {
+    check_page(
+        GRAMMAR,
+        DocsFormat::Markdown,
+        "Fn.md",
+        expect![[r#"
+        # Fn
+
+        A function definition.
+
+        Functions without a body are declarations.
+
+        ```ungrammar
+        Fn = 'fn' name:Name ParamList ('->' ret:Type)? body:Block?
+        ```
+
+        ## Fields
+
+        - `fn_token`: `'fn'`
+        - `name`: [Name](Name.md)
+        - `param_list`: [ParamList](ParamList.md)
+        - `thin_arrow_token`: `'->'`
+        - `ret`: [Type](Type.md)
+        - `body`: [Block](Block.md)
+
+        ## References
+
+        - [Name](Name.md)
+        - [ParamList](ParamList.md)
+        - [Type](Type.md)
+        - [Block](Block.md)
+
+        ## Referenced by
+
+        - [Item](Item.md)
+    "#]],
+    );
+}
+
+#[test]
+fn markdown_enum_and_undefined() {
+    check_page(
+        GRAMMAR,
+        DocsFormat::Markdown,
+        "Item.md",
+        expect![[r#"
+        # Item
+
+        An item of a file.
+
+        ```ungrammar
+        Item = Fn | Struct
+        ```
+
+        ## Fields
+
+        `Item` is an enum of the nodes it references.
+
+        ## References
+
+        - [Fn](Fn.md)
+        - [Struct](Struct.md)
+    "#]],
+    );
+    check_page(
+        GRAMMAR,
+        DocsFormat::Markdown,
+        "Type.md",
+        expect![[r#"
+        # Type
+
+        `Type` is referenced but not defined.
+
+        ## Referenced by
+
+        - [Fn](Fn.md)
+        - [Param](Param.md)
+    "#]],
+    );
+}
+
+#[test]
+fn html_page() {
+    check_page(
+        GRAMMAR,
+        DocsFormat::Html,
+        "Fn.html",
+        expect![[r#"
+        <!DOCTYPE html>
+        <html>
+        <head>
+        <meta charset="utf-8">
+        <title>Fn</title>
+        </head>
+        <body>
+        <h1>Fn</h1>
+        <p>A function definition.</p>
+        <p>Functions without a body are declarations.</p>
+        <pre><code>Fn = 'fn' name:Name ParamList ('-&gt;' ret:Type)? body:Block?</code></pre>
+        <h2>Fields</h2>
+        <ul>
+        <li><code>fn_token</code>: <code>'fn'</code></li>
+        <li><code>name</code>: <a href="Name.html">Name</a></li>
+        <li><code>param_list</code>: <a href="ParamList.html">ParamList</a></li>
+        <li><code>thin_arrow_token</code>: <code>'-&gt;'</code></li>
+        <li><code>ret</code>: <a href="Type.html">Type</a></li>
+        <li><code>body</code>: <a href="Block.html">Block</a></li>
+        </ul>
+        <h2>References</h2>
+        <ul>
+        <li><a href="Name.html">Name</a></li>
+        <li><a href="ParamList.html">ParamList</a></li>
+        <li><a href="Type.html">Type</a></li>
+        <li><a href="Block.html">Block</a></li>
+        </ul>
+        <h2>Referenced by</h2>
+        <ul>
+        <li><a href="Item.html">Item</a></li>
+        </ul>
+        </body>
+        </html>
+    "#]],
+    );
+}
+
+#[test]
+fn source() {
+    check_source(
+        r#"
+A = l:B* (l:B)* (B | C)? B (C D) '\'' '\\'
+E = Aaaaaaaaaaaaaaaaaaaa | Bbbbbbbbbbbbbbbbbbbbbbb | Cccccccccccccccccccccc | Dddddddd D
+"#,
+        expect![[r#"
+            A = l:B* (l:B)* (B | C)? B (C D) '\'' '\\'
+            E =
+              Aaaaaaaaaaaaaaaaaaaa
+            | Bbbbbbbbbbbbbbbbbbbbbbb
+            | Cccccccccccccccccccccc
+            | Dddddddd D"#]],
+    );
+}
@@ -0,0 +1,264 @@
+//! Exports the dependency graph of the nodes of a grammar in the DOT language
+//! of Graphviz.
+//!
+//! There is an edge for each reference from the rule of a node to another
+//! node, labeled with the label of the reference, if any, and `?` or `*` if
+//! the reference is optional or repeated. Enums are drawn with rounded
+//! corners and nodes which are referenced but never defined with a dashed
+//! border.
+//!
+//! Large grammars make for unreadable graphs, so the graph can be restricted
+//! to the neighborhood of a node, and strongly connected components, like
+//! the mutually recursive expressions of a language, can be collapsed into a
+//! single vertex. The references between the nodes of a collapsed component
+//! are drawn as loops on its vertex.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{fields, Grammar, Node, Rule, RuleData};
+use rustc_hash::{FxHashMap, FxHashSet};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotConfig {
+    /// Only show the nodes within `depth` references of this node, in either
+    /// direction.
+    pub focus: Option<Node>,
+    pub depth: usize,
+    /// Draw each cycle of references as a single vertex.
+    pub collapse_sccs: bool,
+}
+
+impl Default for DotConfig {
+    fn default() -> DotConfig {
+        DotConfig { focus: None, depth: 1, collapse_sccs: false }
+    }
+}
+
+pub fn to_dot(grammar: &Grammar, config: &DotConfig) -> String {
+    let edges = grammar.iter().flat_map(|node| edges(grammar, node)).collect::<Vec<_>>();
+    let nodes = match config.focus {
+        Some(focus) => neighborhood(&edges, focus, config.depth),
+        None => grammar.iter().collect(),
+    };
+    let edges = edges
+        .into_iter()
+        .filter(|it| nodes.contains(&it.from) && nodes.contains(&it.to))
+        .collect::<Vec<_>>();
+
+    // Every node is drawn as the vertex of its component. Without collapsing,
+    // each node is a component of its own.
+    let components = if config.collapse_sccs {
+        sccs(&nodes, &edges)
+    } else {
+        nodes.iter().map(|&node| vec![node]).collect()
+    };
+    let mut component_of = FxHashMap::default();
+    for (i, component) in components.iter().enumerate() {
+        component.iter().for_each(|&node| {
+            component_of.insert(node, i);
+        });
+    }
+    let vertex_name = |i: usize| {
+        let names = components[i].iter().map(|&it| grammar[it].name.as_str());
+        names.collect::<Vec<_>>().join(" | ")
+    };
+
+    let mut buf = String::from("digraph grammar {\n    node [shape=box];\n");
+    for (i, component) in components.iter().enumerate() {
+        let mut attrs = Vec::new();
+        if let [node] = component[..] {
+            if grammar[node].rule.is_none() {
+                attrs.push("style=dashed");
+            } else if fields::is_enum(grammar, node) {
+                attrs.push("style=rounded");
+            }
+        } else {
+            attrs.push("style=filled");
+            attrs.push("fillcolor=\"#eeeeee\"");
+        }
+        if config.focus.is_some_and(|it| component.contains(&it)) {
+            attrs.push("penwidth=2");
+        }
+        write!(buf, "    {}", quote(&vertex_name(i))).unwrap();
+        if !attrs.is_empty() {
+            write!(buf, " [{}]", attrs.join(", ")).unwrap();
+        }
+        buf.push_str(";\n");
+    }
+    let mut seen = FxHashSet::default();
+    for edge in &edges {
+        // The edges within a collapsed component become loops, like the
+        // references of a node to itself.
+        let (from, to) = (component_of[&edge.from], component_of[&edge.to]);
+        let label = edge.label();
+        if !seen.insert((from, to, label.clone())) {
+            continue;
+        }
+        write!(buf, "    {} -> {}", quote(&vertex_name(from)), quote(&vertex_name(to))).unwrap();
+        if !label.is_empty() {
+            write!(buf, " [label={}]", quote(&label)).unwrap();
+        }
+        buf.push_str(";\n");
+    }
+    buf.push_str("}\n");
+    buf
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Cardinality {
+    One,
+    Optional,
+    Many,
+}
+
+struct Edge<'a> {
+    from: Node,
+    to: Node,
+    label: Option<&'a str>,
+    cardinality: Cardinality,
+}
+
+impl Edge<'_> {
+    fn label(&self) -> String {
+        let suffix = match self.cardinality {
+            Cardinality::One => "",
+            Cardinality::Optional => "?",
+            Cardinality::Many => "*",
+        };
+        format!("{}{suffix}", self.label.unwrap_or_default())
+    }
+}
+
+fn edges(grammar: &Grammar, node: Node) -> Vec<Edge<'_>> {
+    fn go<'a>(
+        grammar: &'a Grammar,
+        from: Node,
+        rule: Rule,
+        label: Option<&'a str>,
+        cardinality: Cardinality,
+        acc: &mut Vec<Edge<'a>>,
+    ) {
+        match &grammar[rule] {
+            RuleData::Labeled { label, rule } => {
+                go(grammar, from, *rule, Some(label), cardinality, acc)
+            }
+            RuleData::Node(to) => acc.push(Edge { from, to: *to, label, cardinality }),
+            RuleData::Token(_) => (),
+            RuleData::Seq(rules) | RuleData::Alt(rules) => {
+                rules.iter().for_each(|&it| go(grammar, from, it, label, cardinality, acc))
+            }
+            RuleData::Opt(rule) => {
+                go(grammar, from, *rule, label, cardinality.max(Cardinality::Optional), acc)
+            }
+            RuleData::Rep(rule) => go(grammar, from, *rule, label, Cardinality::Many, acc),
+        }
+    }
+
+    let mut res = Vec::new();
+    if let Some(rule) = grammar[node].rule {
+        go(grammar, node, rule, None, Cardinality::One, &mut res);
+    }
+    res
+}
+
+/// Returns the nodes within `depth` edges of `focus`, following the edges in
+/// both directions, in the order they are found.
+fn neighborhood(edges: &[Edge<'_>], focus: Node, depth: usize) -> Vec<Node> {
+    let mut res = vec![focus];
+    let mut frontier = vec![focus];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for &node in &frontier {
+            for edge in edges {
+                let other = match node {
+                    _ if edge.from == node => edge.to,
+                    _ if edge.to == node => edge.from,
+                    _ => continue,
+                };
+                if !res.contains(&other) {
+                    res.push(other);
+                    next.push(other);
+                }
+            }
+        }
+        frontier = next;
+    }
+    res
+}
+
+/// Returns the strongly connected components of the graph, with Tarjan's
+/// algorithm. The nodes of each component, and the components, are ordered
+/// like `nodes`.
+fn sccs(nodes: &[Node], edges: &[Edge<'_>]) -> Vec<Vec<Node>> {
+    struct Tarjan<'a> {
+        successors: FxHashMap<Node, Vec<Node>>,
+        index: FxHashMap<Node, usize>,
+        low_link: FxHashMap<Node, usize>,
+        stack: Vec<Node>,
+        on_stack: FxHashSet<Node>,
+        res: Vec<Vec<Node>>,
+        nodes: &'a [Node],
+    }
+
+    impl Tarjan<'_> {
+        fn visit(&mut self, node: Node) {
+            let index = self.index.len();
+            self.index.insert(node, index);
+            self.low_link.insert(node, index);
+            self.stack.push(node);
+            self.on_stack.insert(node);
+            for next in self.successors.get(&node).cloned().unwrap_or_default() {
+                if !self.index.contains_key(&next) {
+                    self.visit(next);
+                    let low_link = self.low_link[&node].min(self.low_link[&next]);
+                    self.low_link.insert(node, low_link);
+                } else if self.on_stack.contains(&next) {
+                    let low_link = self.low_link[&node].min(self.index[&next]);
+                    self.low_link.insert(node, low_link);
+                }
+            }
+            if self.low_link[&node] == self.index[&node] {
+                let mut component = Vec::new();
+                while let Some(it) = self.stack.pop() {
+                    self.on_stack.remove(&it);
+                    component.push(it);
+                    if it == node {
+                        break;
+                    }
+                }
+                let position = |it: &Node| self.nodes.iter().position(|node| node == it);
+                component.sort_by_key(position);
+                self.res.push(component);
+            }
+        }
+    }
+
+    let mut successors = FxHashMap::<Node, Vec<Node>>::default();
+    for edge in edges {
+        successors.entry(edge.from).or_default().push(edge.to);
+    }
+    let mut tarjan = Tarjan {
+        successors,
+        index: FxHashMap::default(),
+        low_link: FxHashMap::default(),
+        stack: Vec::new(),
+        on_stack: FxHashSet::default(),
+        res: Vec::new(),
+        nodes,
+    };
+    for &node in nodes {
+        if !tarjan.index.contains_key(&node) {
+            tarjan.visit(node);
+        }
+    }
+    let mut res = tarjan.res;
+    res.sort_by_key(|component| nodes.iter().position(|it| *it == component[0]));
+    res
+}
+
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
@@ -0,0 +1,169 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    dot::{to_dot, DotConfig},
+    lower,
+};
+
+const GRAMMAR: &str = r#"
+SourceFile = Item*
+Item = Fn | Const
+Fn = 'fn' name:Name ParamList ('->' ret:Type)? body:Block
+ParamList = '(' Param* ')'
+Param = Name ':' Type
+Const = 'const' Name '=' Expr
+Block = '{' Expr? '}'
+Expr = Block | BinExpr | 'int_number'
+BinExpr = lhs:Expr '+' rhs:Expr
+Name = 'ident'
+"#;
+
+fn check(focus: Option<&str>, depth: usize, collapse_sccs: bool, expect: Expect) {
+    check_grammar(GRAMMAR, focus, depth, collapse_sccs, expect);
+}
+
+fn check_grammar(
+    grammar: &str,
+    focus: Option<&str>,
+    depth: usize,
+    collapse_sccs: bool,
+    expect: Expect,
+) {
+    let grammar = lower(grammar);
+    let focus = focus.map(|name| grammar.find_node(name).unwrap());
+    let actual = to_dot(&grammar, &DotConfig { focus, depth, collapse_sccs });
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn whole_grammar() {
+    check(
+        None,
+        1,
+        false,
+        expect![[r#"
+        digraph grammar {
+            node [shape=box];
+            "SourceFile";
+            "Item" [style=rounded];
+            "Fn";
+            "ParamList";
+            "Param";
+            "Const";
+            "Block";
+            "Expr";
+            "BinExpr";
+            "Name";
+            "Type" [style=dashed];
+            "SourceFile" -> "Item" [label="*"];
+            "Item" -> "Fn";
+            "Item" -> "Const";
+            "Fn" -> "Name" [label="name"];
+            "Fn" -> "ParamList";
+            "Fn" -> "Type" [label="ret?"];
+            "Fn" -> "Block" [label="body"];
+            "ParamList" -> "Param" [label="*"];
+            "Param" -> "Name";
+            "Param" -> "Type";
+            "Const" -> "Name";
+            "Const" -> "Expr";
+            "Block" -> "Expr" [label="?"];
+            "Expr" -> "Block";
+            "Expr" -> "BinExpr";
+            "BinExpr" -> "Expr" [label="lhs"];
+            "BinExpr" -> "Expr" [label="rhs"];
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn neighborhood() {
+    check(
+        Some("Fn"),
+        1,
+        false,
+        expect![[r#"
+        digraph grammar {
+            node [shape=box];
+            "Fn" [penwidth=2];
+            "Item" [style=rounded];
+            "Name";
+            "ParamList";
+            "Type" [style=dashed];
+            "Block";
+            "Item" -> "Fn";
+            "Fn" -> "Name" [label="name"];
+            "Fn" -> "ParamList";
+            "Fn" -> "Type" [label="ret?"];
+            "Fn" -> "Block" [label="body"];
+        }
+    "#]],
+    );
+}
+
+#[test]
+fn collapsed_sccs() {
+    check(
+        None,
+        1,
+        true,
+        expect![[r##"
+        digraph grammar {
+            node [shape=box];
+            "SourceFile";
+            "Item" [style=rounded];
+            "Fn";
+            "ParamList";
+            "Param";
+            "Const";
+            "Block | Expr | BinExpr" [style=filled, fillcolor="#eeeeee"];
+            "Name";
+            "Type" [style=dashed];
+            "SourceFile" -> "Item" [label="*"];
+            "Item" -> "Fn";
+            "Item" -> "Const";
+            "Fn" -> "Name" [label="name"];
+            "Fn" -> "ParamList";
+            "Fn" -> "Type" [label="ret?"];
+            "Fn" -> "Block | Expr | BinExpr" [label="body"];
+            "ParamList" -> "Param" [label="*"];
+            "Param" -> "Name";
+            "Param" -> "Type";
+            "Const" -> "Name";
+            "Const" -> "Block | Expr | BinExpr";
+            "Block | Expr | BinExpr" -> "Block | Expr | BinExpr" [label="?"];
+            "Block | Expr | BinExpr" -> "Block | Expr | BinExpr";
+            "Block | Expr | BinExpr" -> "Block | Expr | BinExpr" [label="lhs"];
+            "Block | Expr | BinExpr" -> "Block | Expr | BinExpr" [label="rhs"];
+        }
+    "##]],
+    );
+}
+
+#[test]
+fn collapsed_sccs_loops() {
+    check_grammar(
+        r#"
+Type = Type '*' | Path
+Path = 'ident'
+Expr = Paren | 'int_number'
+Paren = '(' inner:Expr ')'
+"#,
+        None,
+        1,
+        true,
+        expect![[r##"
+        digraph grammar {
+            node [shape=box];
+            "Type";
+            "Path";
+            "Expr | Paren" [style=filled, fillcolor="#eeeeee"];
+            "Type" -> "Type";
+            "Type" -> "Path";
+            "Expr | Paren" -> "Expr | Paren";
+            "Expr | Paren" -> "Expr | Paren" [label="inner"];
+        }
+    "##]],
+    );
+}
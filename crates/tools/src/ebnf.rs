@@ -0,0 +1,158 @@
+//! Exports a grammar as EBNF, in the notation of the W3C (as used by the XML
+//! specification) or of ISO/IEC 14977.
+//!
+//! Both notations have no labels, so they are either dropped or kept as a
+//! comment before the labeled rule. Nodes which are referenced but never
+//! defined have no production.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{Grammar, Rule, RuleData};
+
+use crate::ungrammar::Prec;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// `Fn ::= 'fn' Name ( '->' Type )?`
+    W3c,
+    /// `Fn = 'fn', Name, [ '->', Type ];`
+    Iso,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EbnfConfig {
+    pub flavor: Flavor,
+    /// Keep labels as comments, e.g. `/* ret */ Type`, rather than dropping
+    /// them.
+    pub label_comments: bool,
+}
+
+/// Returns a production for each defined node, in the order of the grammar.
+pub fn to_ebnf(grammar: &Grammar, config: &EbnfConfig) -> String {
+    let mut buf = String::new();
+    for node in grammar.iter() {
+        let Some(rule) = grammar[node].rule else { continue };
+        let name = &grammar[node].name;
+        let mut printer = Printer { grammar, config, buf: String::new() };
+        printer.rule(rule, Prec::Alt);
+        match config.flavor {
+            Flavor::W3c => writeln!(buf, "{name} ::= {}", printer.buf).unwrap(),
+            Flavor::Iso => writeln!(buf, "{name} = {};", printer.buf).unwrap(),
+        }
+    }
+    buf
+}
+
+struct Printer<'a> {
+    grammar: &'a Grammar,
+    config: &'a EbnfConfig,
+    buf: String,
+}
+
+impl Printer<'_> {
+    fn rule(&mut self, rule: Rule, min_prec: Prec) {
+        if let RuleData::Labeled { label, rule } = &self.grammar[rule] {
+            // The comment goes before the parentheses, so it reads as
+            // labeling the whole group.
+            if self.config.label_comments {
+                match self.config.flavor {
+                    Flavor::W3c => write!(self.buf, "/* {label} */ ").unwrap(),
+                    Flavor::Iso => write!(self.buf, "(* {label} *) ").unwrap(),
+                }
+            }
+            return self.rule(*rule, min_prec);
+        }
+        let parens = self.prec(rule) < min_prec;
+        if parens {
+            self.buf.push_str("( ");
+        }
+        match &self.grammar[rule] {
+            RuleData::Labeled { .. } => unreachable!(),
+            RuleData::Node(node) => self.buf.push_str(&self.grammar[*node].name),
+            RuleData::Token(token) => self.terminal(&self.grammar[*token].name),
+            RuleData::Seq(rules) => {
+                let sep = match self.config.flavor {
+                    Flavor::W3c => " ",
+                    Flavor::Iso => ", ",
+                };
+                self.list(rules, sep, Prec::Postfix);
+            }
+            RuleData::Alt(rules) => self.list(rules, " | ", Prec::Seq),
+            RuleData::Opt(inner) | RuleData::Rep(inner) => {
+                let opt = matches!(self.grammar[rule], RuleData::Opt(_));
+                match self.config.flavor {
+                    Flavor::W3c => {
+                        self.rule(*inner, Prec::Atom);
+                        self.buf.push(if opt { '?' } else { '*' });
+                    }
+                    Flavor::Iso => {
+                        self.buf.push_str(if opt { "[ " } else { "{ " });
+                        self.rule(*inner, Prec::Alt);
+                        self.buf.push_str(if opt { " ]" } else { " }" });
+                    }
+                }
+            }
+        }
+        if parens {
+            self.buf.push_str(" )");
+        }
+    }
+
+    fn list(&mut self, rules: &[Rule], sep: &str, min_prec: Prec) {
+        for (i, &rule) in rules.iter().enumerate() {
+            if i > 0 {
+                self.buf.push_str(sep);
+            }
+            self.rule(rule, min_prec);
+        }
+    }
+
+    fn prec(&self, rule: Rule) -> Prec {
+        match &self.grammar[rule] {
+            RuleData::Labeled { rule, .. } => self.prec(*rule),
+            RuleData::Node(_) => Prec::Atom,
+            RuleData::Token(token) if quote(&self.grammar[*token].name).len() > 1 => Prec::Seq,
+            RuleData::Token(_) => Prec::Atom,
+            RuleData::Seq(_) => Prec::Seq,
+            RuleData::Alt(_) => Prec::Alt,
+            // The brackets of ISO EBNF group like parentheses.
+            RuleData::Opt(_) | RuleData::Rep(_) if self.config.flavor == Flavor::Iso => Prec::Atom,
+            RuleData::Opt(_) | RuleData::Rep(_) => Prec::Postfix,
+        }
+    }
+
+    fn terminal(&mut self, text: &str) {
+        let sep = match self.config.flavor {
+            Flavor::W3c => " ",
+            Flavor::Iso => ", ",
+        };
+        self.buf.push_str(&quote(text).join(sep));
+    }
+}
+
+/// Quotes a literal. Neither notation can escape quotes, so a literal with
+/// both kinds of quotes is split into a sequence of terminals.
+fn quote(text: &str) -> Vec<String> {
+    let mut res = Vec::new();
+    let mut chunk = String::new();
+    for c in text.chars() {
+        let other = match c {
+            '\'' => '"',
+            '"' => '\'',
+            _ => '\0',
+        };
+        if chunk.contains(other) {
+            res.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    if !chunk.is_empty() || res.is_empty() {
+        res.push(chunk);
+    }
+    res.into_iter()
+        .map(|it| if it.contains('\'') { format!("\"{it}\"") } else { format!("'{it}'") })
+        .collect()
+}
@@ -0,0 +1,78 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    ebnf::{to_ebnf, EbnfConfig, Flavor},
+    lower,
+};
+
+fn check(flavor: Flavor, label_comments: bool, ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    expect.assert_eq(&to_ebnf(&grammar, &EbnfConfig { flavor, label_comments }));
+}
+
+const GRAMMAR: &str = r#"
+Fn = 'fn' name:Name ParamList ('->' ret:Type)? body:(Block | ';')
+ParamList = '(' (Param (',' Param)* ','?)? ')'
+BinExpr = lhs:Expr op:('+' | '-') rhs:Expr
+Quotes = '\'' '"' 'it\'s "quoted"'
+"#;
+
+#[test]
+fn w3c() {
+    check(
+        Flavor::W3c,
+        false,
+        GRAMMAR,
+        expect![[r#"
+        Fn ::= 'fn' Name ParamList ( '->' Type )? ( Block | ';' )
+        ParamList ::= '(' ( Param ( ',' Param )* ','? )? ')'
+        BinExpr ::= Expr ( '+' | '-' ) Expr
+        Quotes ::= "'" '"' ( "it's " '"quoted"' )
+    "#]],
+    );
+}
+
+#[test]
+fn w3c_label_comments() {
+    check(
+        Flavor::W3c,
+        true,
+        GRAMMAR,
+        expect![[r#"
+        Fn ::= 'fn' /* name */ Name ParamList ( '->' /* ret */ Type )? /* body */ ( Block | ';' )
+        ParamList ::= '(' ( Param ( ',' Param )* ','? )? ')'
+        BinExpr ::= /* lhs */ Expr /* op */ ( '+' | '-' ) /* rhs */ Expr
+        Quotes ::= "'" '"' ( "it's " '"quoted"' )
+    "#]],
+    );
+}
+
+#[test]
+fn iso() {
+    check(
+        Flavor::Iso,
+        false,
+        GRAMMAR,
+        expect![[r#"
+        Fn = 'fn', Name, ParamList, [ '->', Type ], ( Block | ';' );
+        ParamList = '(', [ Param, { ',', Param }, [ ',' ] ], ')';
+        BinExpr = Expr, ( '+' | '-' ), Expr;
+        Quotes = "'", '"', ( "it's ", '"quoted"' );
+    "#]],
+    );
+}
+
+#[test]
+fn iso_label_comments() {
+    check(
+        Flavor::Iso,
+        true,
+        GRAMMAR,
+        expect![[r#"
+        Fn = 'fn', (* name *) Name, ParamList, [ '->', (* ret *) Type ], (* body *) ( Block | ';' );
+        ParamList = '(', [ Param, { ',', Param }, [ ',' ] ], ')';
+        BinExpr = (* lhs *) Expr, (* op *) ( '+' | '-' ), (* rhs *) Expr;
+        Quotes = "'", '"', ( "it's ", '"quoted"' );
+    "#]],
+    );
+}
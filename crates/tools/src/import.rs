@@ -0,0 +1,467 @@
+//! Converts grammars written for other tools into Ungrammar, as a first pass
+//! when migrating them.
+//!
+//! Each importer understands a subset of its format:
+//!
+//! * W3C EBNF, as used by the XML specification: `Name ::= ...` productions,
+//! * pest: `name = { ... }` rules, with their modifiers, tags and comments,
+//! * LALRPOP: nonterminals with their alternatives, ignoring types, actions
+//!   and the rest of the file.
+//!
+//! Ungrammar only describes the shape of trees, so constructs with no
+//! counterpart are dropped or approximated, with a diagnostic: predicates,
+//! actions, character classes (which become a token of their source text),
+//! bounded repetitions and so on. The result is meant to be reviewed by hand.
+//!
+//! Names follow the conventions of Ungrammar: nodes are `CamelCase`, labels
+//! `snake_case`. Ungrammar identifiers can't contain digits, so those are
+//! spelled out, e.g. `expr2` becomes `ExprTwo`. A node whose converted name
+//! is empty or already taken gets a new one, with a diagnostic.
+
+mod ebnf;
+mod lalrpop;
+mod pest;
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{TextRange, TextSize};
+
+use crate::ungrammar::{production_source, Shape, ToShape};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    W3cEbnf,
+    Pest,
+    Lalrpop,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The Ungrammar source.
+    pub text: String,
+    /// Ordered by their position in the imported text.
+    pub diagnostics: Vec<ImportDiagnostic>,
+}
+
+/// A construct which couldn't be imported as is, with its range in the
+/// imported text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDiagnostic {
+    pub range: TextRange,
+    pub message: String,
+}
+
+/// Converts `text`, written in `format`, into Ungrammar.
+pub fn import(text: &str, format: ImportFormat) -> Import {
+    let mut p = Parser::new(text);
+    let productions = match format {
+        ImportFormat::W3cEbnf => ebnf::productions(&mut p),
+        ImportFormat::Pest => pest::productions(&mut p),
+        ImportFormat::Lalrpop => lalrpop::productions(&mut p),
+    };
+
+    let names = node_names(&mut p, &productions);
+    let mut buf = String::new();
+    for mut production in productions {
+        let name = &names[&production.name];
+        production.expr.rename(&names);
+        if production.expr == Expr::Empty {
+            p.error(production.range, format!("`{name}` only matches the empty string"));
+            continue;
+        }
+        if !buf.is_empty() {
+            buf.push('\n');
+        }
+        for line in &production.docs {
+            writeln!(buf, "///{line}").unwrap();
+        }
+        writeln!(buf, "{}", production_source(name, &&production.expr)).unwrap();
+    }
+    let mut diagnostics = p.diagnostics;
+    diagnostics.sort_by_key(|it| it.range.start());
+    Import { text: buf, diagnostics }
+}
+
+struct Production {
+    /// The name in the imported text, see [`node_names`].
+    name: String,
+    docs: Vec<String>,
+    /// The range of the name, for diagnostics about the whole production.
+    range: TextRange,
+    expr: Expr,
+}
+
+/// A rule of Ungrammar, which may also be empty. The constructors simplify
+/// the rule, so that empty rules only remain at the top. Nodes keep their
+/// name in the imported text until they are renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Expr {
+    Empty,
+    Node(String),
+    Token(String),
+    Labeled(String, Box<Expr>),
+    Seq(Vec<Expr>),
+    Alt(Vec<Expr>),
+    Opt(Box<Expr>),
+    Rep(Box<Expr>),
+}
+
+impl Expr {
+    fn seq(exprs: Vec<Expr>) -> Expr {
+        let mut res = Vec::new();
+        for expr in exprs {
+            match expr {
+                Expr::Empty => (),
+                Expr::Seq(exprs) => res.extend(exprs),
+                expr => res.push(expr),
+            }
+        }
+        match res.len() {
+            0 => Expr::Empty,
+            1 => res.pop().unwrap(),
+            _ => Expr::Seq(res),
+        }
+    }
+
+    /// An alternative with an empty branch is optional.
+    fn alt(exprs: Vec<Expr>) -> Expr {
+        let mut res = Vec::new();
+        let mut optional = false;
+        for expr in exprs {
+            match expr {
+                Expr::Empty => optional = true,
+                Expr::Alt(exprs) => res.extend(exprs),
+                expr => res.push(expr),
+            }
+        }
+        let res = match res.len() {
+            0 => Expr::Empty,
+            1 => res.pop().unwrap(),
+            _ => Expr::Alt(res),
+        };
+        if optional {
+            Expr::opt(res)
+        } else {
+            res
+        }
+    }
+
+    fn node_refs<'a>(&'a self, acc: &mut Vec<&'a str>) {
+        match self {
+            Expr::Node(name) => acc.push(name),
+            Expr::Empty | Expr::Token(_) => (),
+            Expr::Labeled(_, expr) | Expr::Opt(expr) | Expr::Rep(expr) => expr.node_refs(acc),
+            Expr::Seq(exprs) | Expr::Alt(exprs) => exprs.iter().for_each(|it| it.node_refs(acc)),
+        }
+    }
+
+    fn rename(&mut self, names: &FxHashMap<String, String>) {
+        match self {
+            Expr::Node(name) => *name = names[name.as_str()].clone(),
+            Expr::Empty | Expr::Token(_) => (),
+            Expr::Labeled(_, expr) | Expr::Opt(expr) | Expr::Rep(expr) => expr.rename(names),
+            Expr::Seq(exprs) | Expr::Alt(exprs) => exprs.iter_mut().for_each(|it| it.rename(names)),
+        }
+    }
+
+    fn opt(expr: Expr) -> Expr {
+        match expr {
+            Expr::Empty | Expr::Opt(_) | Expr::Rep(_) => expr,
+            expr => Expr::Opt(Box::new(expr)),
+        }
+    }
+
+    fn rep(expr: Expr) -> Expr {
+        match expr {
+            Expr::Empty | Expr::Rep(_) => expr,
+            Expr::Opt(expr) => Expr::Rep(expr),
+            expr => Expr::Rep(Box::new(expr)),
+        }
+    }
+
+    /// `expr+`, which Ungrammar spells `expr expr*`.
+    fn one_or_more(expr: Expr) -> Expr {
+        Expr::seq(vec![expr.clone(), Expr::rep(expr)])
+    }
+
+    fn labeled(label: &str, expr: Expr) -> Expr {
+        match expr {
+            Expr::Empty => expr,
+            expr => Expr::Labeled(label_name(label), Box::new(expr)),
+        }
+    }
+}
+
+impl ToShape for &Expr {
+    fn shape(&self) -> Shape<'_, Self> {
+        match self {
+            Expr::Empty => unreachable!("empty rules are simplified away"),
+            Expr::Node(name) => Shape::Node(name),
+            Expr::Token(text) => Shape::Token(text),
+            Expr::Labeled(label, expr) => Shape::Labeled(label, expr),
+            Expr::Seq(exprs) => Shape::Seq(exprs.iter().collect()),
+            Expr::Alt(exprs) => Shape::Alt(exprs.iter().collect()),
+            Expr::Opt(expr) => Shape::Opt(expr),
+            Expr::Rep(expr) => Shape::Rep(expr),
+        }
+    }
+}
+
+/// Maps the names of the nodes in the imported text to distinct names of
+/// Ungrammar nodes, with a diagnostic for the definitions which can't keep
+/// the converted name: either nothing is left of it, or it is the converted
+/// name of another node too, as `expr2` and `expr_2` both become `ExprTwo`.
+fn node_names(p: &mut Parser<'_>, productions: &[Production]) -> FxHashMap<String, String> {
+    let mut refs = Vec::new();
+    productions.iter().for_each(|it| it.expr.node_refs(&mut refs));
+    let defs = productions.iter().map(|it| (it.name.as_str(), Some(it.range)));
+    let mut res = FxHashMap::default();
+    let mut taken = FxHashSet::default();
+    for (name, range) in defs.chain(refs.into_iter().map(|it| (it, None))) {
+        if res.contains_key(name) {
+            continue;
+        }
+        let converted = node_name(name);
+        let base = if converted.is_empty() { "Node".to_owned() } else { converted.clone() };
+        let mut unique = base.clone();
+        for n in 2.. {
+            if taken.insert(unique.clone()) {
+                break;
+            }
+            unique = format!("{base}{}", node_name(&n.to_string()));
+        }
+        if let Some(range) = range.filter(|_| unique != converted) {
+            let message = if converted.is_empty() {
+                format!("`{name}` has no letters to name a node with, renamed to `{unique}`")
+            } else {
+                format!("`{converted}` already names another node, renamed to `{unique}`")
+            };
+            p.error(range, message);
+        }
+        res.insert(name.to_owned(), unique);
+    }
+    res
+}
+
+/// Converts a name of another format into the name of a node, e.g.
+/// `ASCII_DIGIT` into `AsciiDigit`.
+fn node_name(name: &str) -> String {
+    let mut res = String::new();
+    for word in spell_digits(name).split(|c: char| !c.is_ascii_alphabetic()) {
+        let mut chars = word.chars();
+        let Some(first) = chars.next() else { continue };
+        res.push(first.to_ascii_uppercase());
+        if word.chars().all(|c| c.is_ascii_uppercase()) {
+            res.extend(chars.map(|c| c.to_ascii_lowercase()));
+        } else {
+            res.extend(chars);
+        }
+    }
+    res
+}
+
+/// Converts a name of another format into a label, e.g. `lhsExpr` into
+/// `lhs_expr`.
+fn label_name(name: &str) -> String {
+    let name = node_name(name);
+    let name = if name.is_empty() { "label".to_owned() } else { name };
+    stdx::to_lower_snake_case(&name)
+}
+
+fn spell_digits(name: &str) -> String {
+    const DIGITS: [&str; 10] =
+        ["Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine"];
+    let mut res = String::new();
+    for c in name.chars() {
+        match c.to_digit(10) {
+            Some(digit) => write!(res, "_{}_", DIGITS[digit as usize]).unwrap(),
+            None => res.push(c),
+        }
+    }
+    res
+}
+
+/// The token for a string literal from `start` to the current position,
+/// which unescapes to `text`. Empty strings match nothing and are dropped,
+/// and carriage returns, which Ungrammar doesn't allow in token literals, are
+/// spelled out.
+fn token(p: &mut Parser<'_>, start: usize, text: String) -> Expr {
+    if text.is_empty() {
+        return Expr::Empty;
+    }
+    if !text.contains('\r') {
+        return Expr::Token(text);
+    }
+    let text = text.escape_debug().to_string();
+    let message = format!(
+        "carriage returns can't be represented in tokens, replaced by the token `'{text}'`"
+    );
+    p.error(p.range(start), message);
+    Expr::Token(text)
+}
+
+/// Replaces the character class from `start` to the current position by a
+/// token of its source text.
+fn char_class(p: &mut Parser<'_>, start: usize) -> Expr {
+    let text = p.text[start..p.pos].trim_end().to_owned();
+    let message =
+        format!("character classes can't be represented, replaced by the token `'{text}'`");
+    p.error(p.range(start), message);
+    Expr::Token(text)
+}
+
+/// A scanner shared by the importers, which parse the text directly rather
+/// than tokenizing it first.
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    /// The `///` comments since the last production.
+    docs: Vec<String>,
+    diagnostics: Vec<ImportDiagnostic>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(text: &'a str) -> Parser<'a> {
+        Parser { text, pos: 0, docs: Vec::new(), diagnostics: Vec::new() }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.text[self.pos..]
+    }
+
+    fn at_eof(&self) -> bool {
+        self.pos == self.text.len()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn at(&self, s: &str) -> bool {
+        self.rest().starts_with(s)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        let res = self.at(s);
+        if res {
+            self.pos += s.len();
+        }
+        res
+    }
+
+    fn eat_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.bump();
+        }
+        &self.text[start..self.pos]
+    }
+
+    /// The range from `start` to the current position, without the trailing
+    /// whitespace.
+    fn range(&self, start: usize) -> TextRange {
+        let end = start + self.text[start..self.pos].trim_end().len();
+        TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+    }
+
+    fn error(&mut self, range: TextRange, message: impl Into<String>) {
+        self.diagnostics.push(ImportDiagnostic { range, message: message.into() });
+    }
+
+    /// Skips whitespace and `/* */` comments, and with `line_comments`, `//`
+    /// comments too. The text of `///` comments is kept in `docs`.
+    fn trivia(&mut self, line_comments: bool) {
+        loop {
+            self.eat_while(char::is_whitespace);
+            if line_comments && self.at("//") {
+                let comment = self.eat_while(|c| c != '\n');
+                if let Some(doc) = comment.strip_prefix("///").filter(|it| !it.starts_with('/')) {
+                    self.docs.push(doc.trim_end().to_owned());
+                }
+            } else if self.eat("/*") {
+                match self.rest().find("*/") {
+                    Some(end) => self.pos += end + 2,
+                    None => self.pos = self.text.len(),
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn ident(&mut self) -> Option<&'a str> {
+        if !self.peek().is_some_and(|c| c.is_ascii_alphabetic() || c == '_') {
+            return None;
+        }
+        Some(self.eat_while(|c| c.is_ascii_alphanumeric() || c == '_'))
+    }
+
+    /// Parses a string literal in `quote`s, with Rust-like escapes if
+    /// `escapes` is set.
+    fn string(&mut self, quote: char, escapes: bool) -> String {
+        let start = self.pos;
+        assert_eq!(self.bump(), Some(quote));
+        let mut res = String::new();
+        loop {
+            match self.bump() {
+                None => {
+                    self.error(self.range(start), "unterminated string");
+                    break;
+                }
+                Some(c) if c == quote => break,
+                Some('\\') if escapes => match self.bump() {
+                    Some('n') => res.push('\n'),
+                    Some('r') => res.push('\r'),
+                    Some('t') => res.push('\t'),
+                    Some('0') => res.push('\0'),
+                    Some('u') if self.eat("{") => {
+                        let hex = self.eat_while(|c| c != '}');
+                        self.eat("}");
+                        match u32::from_str_radix(hex, 16).ok().and_then(char::from_u32) {
+                            Some(c) => res.push(c),
+                            None => self.error(self.range(start), "invalid unicode escape"),
+                        }
+                    }
+                    Some(c) => res.push(c),
+                    None => (),
+                },
+                Some(c) => res.push(c),
+            }
+        }
+        res
+    }
+
+    /// Skips a balanced group which starts at the current `open` character,
+    /// e.g. the body of an `extern` block.
+    fn skip_group(&mut self, open: char, close: char) {
+        let mut depth = 0;
+        while let Some(c) = self.peek() {
+            match c {
+                '"' => {
+                    self.string('"', true);
+                    continue;
+                }
+                c if c == open => depth += 1,
+                c if c == close => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.bump();
+                        return;
+                    }
+                }
+                _ => (),
+            }
+            self.bump();
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! W3C EBNF, as in <https://www.w3.org/TR/xml/#sec-notation>.
+//!
+//! Productions may be numbered like in the XML specification, `[1] document
+//! ::= ...`. Exceptions, `A - B`, lose their `- B`; character classes and
+//! character references become tokens of their source text; well-formedness
+//! and validity constraints are dropped.
+
+use super::{char_class, token, Expr, Parser, Production};
+
+pub(super) fn productions(p: &mut Parser<'_>) -> Vec<Production> {
+    let mut res = Vec::new();
+    trivia(p);
+    while !p.at_eof() {
+        match production(p) {
+            Some(production) => res.push(production),
+            None => {
+                // Skip to the next production.
+                let start = p.pos;
+                while !p.at_eof() && !at_production(p) {
+                    p.bump();
+                    trivia(p);
+                }
+                p.error(p.range(start), "expected a production, `Name ::= ...`");
+            }
+        }
+    }
+    res
+}
+
+fn production(p: &mut Parser<'_>) -> Option<Production> {
+    if !at_production(p) {
+        return None;
+    }
+    number(p);
+    let start = p.pos;
+    let name = name(p)?.to_owned();
+    let range = p.range(start);
+    trivia(p);
+    p.eat("::=");
+    trivia(p);
+    let expr = alt(p);
+    Some(Production { name, docs: Vec::new(), range, expr })
+}
+
+fn at_production(p: &mut Parser<'_>) -> bool {
+    let pos = p.pos;
+    number(p);
+    let res = name(p).is_some() && {
+        trivia(p);
+        p.at("::=")
+    };
+    p.pos = pos;
+    res
+}
+
+/// Skips the number of a production, `[1]`.
+fn number(p: &mut Parser<'_>) {
+    let pos = p.pos;
+    if p.eat("[") && !p.eat_while(|c| c.is_ascii_digit()).is_empty() && p.eat("]") {
+        trivia(p);
+    } else {
+        p.pos = pos;
+    }
+}
+
+fn name<'a>(p: &mut Parser<'a>) -> Option<&'a str> {
+    if !p.peek().is_some_and(|c| c.is_alphabetic() || c == '_') {
+        return None;
+    }
+    Some(p.eat_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.')))
+}
+
+fn trivia(p: &mut Parser<'_>) {
+    p.trivia(false);
+}
+
+fn alt(p: &mut Parser<'_>) -> Expr {
+    let mut alts = vec![seq(p)];
+    while p.eat("|") {
+        trivia(p);
+        alts.push(seq(p));
+    }
+    Expr::alt(alts)
+}
+
+fn seq(p: &mut Parser<'_>) -> Expr {
+    let mut exprs = Vec::new();
+    while !p.at_eof() && !p.at("|") && !p.at(")") && !at_production(p) {
+        let Some(expr) = postfix(p) else { break };
+        exprs.push(expr);
+        if p.at("-") {
+            let start = p.pos;
+            p.bump();
+            trivia(p);
+            postfix(p);
+            p.error(p.range(start), "exceptions can't be represented and were dropped");
+        }
+    }
+    Expr::seq(exprs)
+}
+
+fn postfix(p: &mut Parser<'_>) -> Option<Expr> {
+    let mut expr = atom(p)?;
+    trivia(p);
+    loop {
+        expr = match p.peek() {
+            Some('?') => Expr::opt(expr),
+            Some('*') => Expr::rep(expr),
+            Some('+') => Expr::one_or_more(expr),
+            _ => break,
+        };
+        p.bump();
+        trivia(p);
+    }
+    Some(expr)
+}
+
+fn atom(p: &mut Parser<'_>) -> Option<Expr> {
+    let start = p.pos;
+    let expr = match p.peek()? {
+        '(' => {
+            p.bump();
+            trivia(p);
+            let expr = alt(p);
+            if !p.eat(")") {
+                p.error(p.range(start), "unclosed parenthesis");
+            }
+            expr
+        }
+        quote @ ('\'' | '"') => {
+            let text = p.string(quote, false);
+            token(p, start, text)
+        }
+        '[' => {
+            p.eat_while(|c| c != ']');
+            p.bump();
+            let text = &p.text[start..p.pos];
+            let inner = text[1..].trim_start();
+            if inner.starts_with("wfc:") || inner.starts_with("vc:") {
+                p.error(p.range(start), "constraints can't be represented and were dropped");
+                Expr::Empty
+            } else {
+                char_class(p, start)
+            }
+        }
+        '#' => {
+            p.bump();
+            p.eat_while(|c| c.is_ascii_alphanumeric());
+            char_class(p, start)
+        }
+        _ => Expr::Node(name(p)?.to_owned()),
+    };
+    Some(expr)
+}
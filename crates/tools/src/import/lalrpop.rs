@@ -0,0 +1,333 @@
+//! LALRPOP, as in <https://lalrpop.github.io/lalrpop/>.
+//!
+//! Only the nonterminals are imported, `pub Name: Type = { ... };` or
+//! `Name = ... => action;`, with their types, actions and conditions dropped.
+//! Named symbols, `<name:Symbol>`, become labels. Regular expressions become
+//! tokens of their source text. Macros are imported as nodes, with their
+//! parameters as references to undefined nodes and the arguments of their
+//! uses dropped. The `grammar` declaration and `use` items are skipped,
+//! `extern` and `match` blocks too, with a diagnostic.
+
+use super::{char_class, token, Expr, Parser, Production};
+
+pub(super) fn productions(p: &mut Parser<'_>) -> Vec<Production> {
+    let mut res = Vec::new();
+    trivia(p);
+    while !p.at_eof() {
+        let docs = std::mem::take(&mut p.docs);
+        let start = p.pos;
+        if p.at("#") {
+            p.bump();
+            if p.at("[") {
+                p.skip_group('[', ']');
+            }
+        } else if keyword(p, "grammar") || keyword(p, "use") {
+            skip_item(p);
+        } else if keyword(p, "extern") || keyword(p, "match") {
+            let keyword = p.text[start..p.pos].to_owned();
+            skip_block(p);
+            trivia(p);
+            if keyword == "match" && self::keyword(p, "else") {
+                skip_block(p);
+            }
+            p.eat(";");
+            let message = format!("`{keyword}` blocks can't be represented and were dropped");
+            p.error(p.range(start), message);
+        } else if at_production(p) {
+            res.extend(production(p, docs));
+        } else {
+            p.bump();
+            skip_item(p);
+            p.error(p.range(start), "expected a nonterminal, `Name = ...;`");
+        }
+        trivia(p);
+    }
+    res
+}
+
+fn trivia(p: &mut Parser<'_>) {
+    p.trivia(true);
+}
+
+/// Eats `keyword` if it isn't the prefix of a longer identifier.
+fn keyword(p: &mut Parser<'_>, keyword: &str) -> bool {
+    let pos = p.pos;
+    if p.ident() == Some(keyword) {
+        return true;
+    }
+    p.pos = pos;
+    false
+}
+
+fn at_production(p: &mut Parser<'_>) -> bool {
+    let pos = p.pos;
+    keyword(p, "pub");
+    trivia(p);
+    let res = p.ident().is_some();
+    p.pos = pos;
+    res
+}
+
+/// Skips the braced body of an item, and what comes before it.
+fn skip_block(p: &mut Parser<'_>) {
+    p.eat_while(|c| c != '{' && c != ';');
+    if p.at("{") {
+        p.skip_group('{', '}');
+    }
+}
+
+/// Skips to the end of the item, the next `;` outside of strings.
+fn skip_item(p: &mut Parser<'_>) {
+    while let Some(c) = p.peek() {
+        match c {
+            '"' => {
+                p.string('"', true);
+            }
+            ';' => {
+                p.bump();
+                return;
+            }
+            _ => {
+                p.bump();
+            }
+        }
+    }
+}
+
+fn production(p: &mut Parser<'_>, docs: Vec<String>) -> Option<Production> {
+    keyword(p, "pub");
+    trivia(p);
+    let start = p.pos;
+    let name = p.ident()?;
+    let range = p.range(start);
+    trivia(p);
+    if p.at("<") {
+        let params_start = p.pos;
+        p.skip_group('<', '>');
+        let params = &p.text[params_start + 1..p.pos - 1];
+        let message = format!(
+            "macros can't be represented, `{name}` was imported with its parameters `{}` \
+             undefined",
+            params.trim()
+        );
+        p.error(range, message);
+        trivia(p);
+    }
+    if p.eat(":") {
+        skip_type(p);
+    }
+    if !p.eat("=") {
+        p.error(range, format!("expected the alternatives of `{name}`"));
+        skip_item(p);
+        return None;
+    }
+    trivia(p);
+
+    let mut actions = false;
+    let expr = if p.eat("{") {
+        trivia(p);
+        let mut alts = Vec::new();
+        while !p.at_eof() && !p.at("}") {
+            alts.extend(alternative(p, &mut actions));
+            if !p.eat(",") && !p.at("}") {
+                let start = p.pos;
+                p.bump();
+                p.error(p.range(start), "expected `,` or `}`");
+            }
+            trivia(p);
+        }
+        p.eat("}");
+        trivia(p);
+        p.eat(";");
+        Expr::alt(alts)
+    } else {
+        let expr = alternative(p, &mut actions).unwrap_or(Expr::Empty);
+        if !p.eat(";") {
+            p.error(range, format!("expected the end of `{name}`"));
+            skip_item(p);
+        }
+        expr
+    };
+    if actions {
+        p.error(range, format!("the actions of `{name}` can't be represented and were dropped"));
+    }
+    Some(Production { name: name.to_owned(), docs, range, expr })
+}
+
+/// Skips the type of a nonterminal, up to the `=` which starts its
+/// alternatives.
+fn skip_type(p: &mut Parser<'_>) {
+    let mut depth = 0;
+    while let Some(c) = p.peek() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if p.text[..p.pos].ends_with('-') => (),
+            '>' | ')' | ']' => depth -= 1,
+            '=' if depth == 0 => return,
+            _ => (),
+        }
+        p.bump();
+    }
+}
+
+/// Returns `None` for an alternative which only recovers from errors, like
+/// `@L ! @R`, rather than matching the empty string.
+fn alternative(p: &mut Parser<'_>, actions: &mut bool) -> Option<Expr> {
+    let mut symbols = Vec::new();
+    let mut recovery = false;
+    loop {
+        recovery |= p.at("!");
+        let Some(symbol) = symbol(p) else { break };
+        symbols.push(symbol);
+    }
+    let start = p.pos;
+    if keyword(p, "if") {
+        p.eat_while(|c| !matches!(c, '=' | ',' | ';' | '}'));
+        p.error(p.range(start), "conditions can't be represented and were dropped");
+    }
+    if p.eat("=>") {
+        *actions = true;
+        skip_action(p);
+    }
+    let expr = Expr::seq(symbols);
+    (expr != Expr::Empty || !recovery).then_some(expr)
+}
+
+/// Skips the code of an action, up to the `,`, `}` or `;` which ends its
+/// alternative.
+fn skip_action(p: &mut Parser<'_>) {
+    let mut depth = 0;
+    while let Some(c) = p.peek() {
+        match c {
+            '"' => {
+                p.string('"', true);
+                continue;
+            }
+            // A character literal rather than a lifetime.
+            '\'' if p.rest().chars().nth(2) == Some('\'') || p.at("'\\") => {
+                p.string('\'', true);
+                continue;
+            }
+            '{' | '(' | '[' => depth += 1,
+            '}' if depth == 0 => return,
+            '}' | ')' | ']' => depth -= 1,
+            ',' | ';' if depth == 0 => return,
+            _ => (),
+        }
+        p.bump();
+    }
+}
+
+fn symbol(p: &mut Parser<'_>) -> Option<Expr> {
+    let mut expr = atom(p)?;
+    trivia(p);
+    loop {
+        expr = match p.peek() {
+            Some('?') => Expr::opt(expr),
+            Some('*') => Expr::rep(expr),
+            Some('+') => Expr::one_or_more(expr),
+            _ => break,
+        };
+        p.bump();
+        trivia(p);
+    }
+    Some(expr)
+}
+
+fn atom(p: &mut Parser<'_>) -> Option<Expr> {
+    let start = p.pos;
+    let expr = match p.peek()? {
+        '<' => {
+            p.bump();
+            trivia(p);
+            keyword(p, "mut");
+            trivia(p);
+            // `<name:Symbol>`, or selected symbols without a name.
+            let pos = p.pos;
+            let label = p.ident().filter(|_| {
+                trivia(p);
+                p.eat(":") && !p.at(":")
+            });
+            if label.is_none() {
+                p.pos = pos;
+            }
+            trivia(p);
+            let mut symbols = Vec::new();
+            while let Some(symbol) = symbol(p) {
+                symbols.push(symbol);
+            }
+            p.eat(">");
+            let expr = Expr::seq(symbols);
+            match label {
+                Some(label) => Expr::labeled(label, expr),
+                None => expr,
+            }
+        }
+        '(' => {
+            p.bump();
+            trivia(p);
+            let mut alts = Vec::new();
+            loop {
+                let mut symbols = Vec::new();
+                while let Some(symbol) = symbol(p) {
+                    symbols.push(symbol);
+                }
+                alts.push(Expr::seq(symbols));
+                if !p.eat("|") {
+                    break;
+                }
+                trivia(p);
+            }
+            if !p.eat(")") {
+                p.error(p.range(start), "unclosed parenthesis");
+            }
+            Expr::alt(alts)
+        }
+        '"' => {
+            let text = p.string('"', true);
+            token(p, start, text)
+        }
+        'r' if p.at("r\"") || p.at("r#") => {
+            p.bump();
+            let hashes = p.eat_while(|c| c == '#');
+            let end = format!("\"{hashes}");
+            p.eat("\"");
+            match p.rest().find(&end) {
+                Some(len) => p.pos += len + end.len(),
+                None => p.pos = p.text.len(),
+            }
+            char_class(p, start)
+        }
+        '@' => {
+            // The locations `@L` and `@R` match nothing.
+            p.bump();
+            p.ident();
+            Expr::Empty
+        }
+        '!' => {
+            p.bump();
+            p.error(p.range(start), "error recovery can't be represented and was dropped");
+            Expr::Empty
+        }
+        _ => {
+            let pos = p.pos;
+            let name = p.ident()?;
+            if name == "if" {
+                p.pos = pos;
+                return None;
+            }
+            let end = p.pos;
+            trivia(p);
+            if p.at("<") {
+                p.skip_group('<', '>');
+                let message =
+                    format!("macros can't be represented, the arguments of `{name}` were dropped");
+                p.error(p.range(start), message);
+            } else {
+                p.pos = end;
+            }
+            Expr::Node(name.to_owned())
+        }
+    };
+    Some(expr)
+}
@@ -0,0 +1,190 @@
+//! pest, as in <https://pest.rs/book/grammars/syntax.html>.
+//!
+//! Rule modifiers are ignored and tags, `#name = expr`, become labels. The
+//! implicit `WHITESPACE` and `COMMENT` rules, predicates, the stack and
+//! bounded repetitions can't be represented. Character ranges and built-in
+//! character classes like `ASCII_DIGIT` become tokens of their source text,
+//! `SOI` and `EOI` are dropped.
+
+use super::{char_class, token, Expr, Parser, Production};
+
+pub(super) fn productions(p: &mut Parser<'_>) -> Vec<Production> {
+    let mut res = Vec::new();
+    trivia(p);
+    while !p.at_eof() {
+        // Only the docs right above a rule belong to it.
+        let docs = std::mem::take(&mut p.docs);
+        let start = p.pos;
+        let Some(name) = p.ident() else {
+            p.bump();
+            p.eat_while(|c| !c.is_whitespace());
+            p.error(p.range(start), "expected a rule, `name = { ... }`");
+            trivia(p);
+            continue;
+        };
+        let range = p.range(start);
+        trivia(p);
+        p.eat("=");
+        trivia(p);
+        p.eat_while(|c| matches!(c, '_' | '@' | '$' | '!'));
+        trivia(p);
+        if !p.eat("{") {
+            p.error(range, format!("expected the body of `{name}`"));
+            continue;
+        }
+        trivia(p);
+        let expr = alt(p);
+        if !p.eat("}") {
+            p.error(range, format!("expected the end of `{name}`"));
+            p.eat_while(|c| c != '}');
+            p.bump();
+        }
+        trivia(p);
+        if matches!(name, "WHITESPACE" | "COMMENT") {
+            let message =
+                format!("the implicit `{name}` rule belongs to the lexer and was dropped");
+            p.error(range, message);
+            continue;
+        }
+        res.push(Production { name: name.to_owned(), docs, range, expr });
+    }
+    res
+}
+
+fn trivia(p: &mut Parser<'_>) {
+    p.trivia(true);
+}
+
+fn alt(p: &mut Parser<'_>) -> Expr {
+    let mut alts = vec![seq(p)];
+    while p.eat("|") {
+        trivia(p);
+        alts.push(seq(p));
+    }
+    Expr::alt(alts)
+}
+
+fn seq(p: &mut Parser<'_>) -> Expr {
+    let mut exprs = vec![term(p)];
+    while p.eat("~") {
+        trivia(p);
+        exprs.push(term(p));
+    }
+    Expr::seq(exprs)
+}
+
+fn term(p: &mut Parser<'_>) -> Expr {
+    let start = p.pos;
+    if p.eat("&") || p.eat("!") {
+        trivia(p);
+        term(p);
+        p.error(p.range(start), "predicates can't be represented and were dropped");
+        return Expr::Empty;
+    }
+    if p.eat("#") {
+        let label = p.ident().unwrap_or_default();
+        trivia(p);
+        p.eat("=");
+        trivia(p);
+        return Expr::labeled(label, term(p));
+    }
+    let mut expr = atom(p);
+    trivia(p);
+    loop {
+        let start = p.pos;
+        expr = match p.peek() {
+            Some('?') => Expr::opt(expr),
+            Some('*') => Expr::rep(expr),
+            Some('+') => Expr::one_or_more(expr),
+            Some('{') => {
+                p.eat_while(|c| c != '}');
+                p.bump();
+                let message = "bounded repetitions can't be represented, replaced by `*`";
+                p.error(p.range(start), message);
+                trivia(p);
+                expr = Expr::rep(expr);
+                continue;
+            }
+            _ => break,
+        };
+        p.bump();
+        trivia(p);
+    }
+    expr
+}
+
+fn atom(p: &mut Parser<'_>) -> Expr {
+    let start = p.pos;
+    match p.peek() {
+        Some('(') => {
+            p.bump();
+            trivia(p);
+            let expr = alt(p);
+            if !p.eat(")") {
+                p.error(p.range(start), "unclosed parenthesis");
+            }
+            expr
+        }
+        Some('"') => {
+            let text = p.string('"', true);
+            token(p, start, text)
+        }
+        Some('^') => {
+            p.bump();
+            let text = if p.at("\"") { p.string('"', true) } else { String::new() };
+            let message = "case-insensitive strings can't be represented, only the exact text is \
+                           matched";
+            p.error(p.range(start), message);
+            token(p, start, text)
+        }
+        Some('\'') => {
+            let text = p.string('\'', true);
+            let end = p.pos;
+            trivia(p);
+            if !p.eat("..") {
+                p.pos = end;
+                return token(p, start, text);
+            }
+            trivia(p);
+            if p.at("'") {
+                p.string('\'', true);
+            }
+            char_class(p, start)
+        }
+        _ => {
+            let Some(name) = p.ident() else {
+                if !matches!(p.peek(), None | Some('}' | ')' | '|' | '~')) {
+                    p.bump();
+                }
+                p.error(p.range(start), "expected an expression");
+                return Expr::Empty;
+            };
+            builtin(p, name, start)
+        }
+    }
+}
+
+fn builtin(p: &mut Parser<'_>, name: &str, start: usize) -> Expr {
+    match name {
+        "SOI" | "EOI" => Expr::Empty,
+        "ANY" | "NEWLINE" | "ASCII" => char_class(p, start),
+        _ if name.starts_with("ASCII_") => char_class(p, start),
+        "PUSH" => {
+            trivia(p);
+            let expr = atom(p);
+            p.error(
+                p.range(start),
+                "the stack can't be represented, the pushed expression was kept",
+            );
+            expr
+        }
+        "POP" | "POP_ALL" | "PEEK" | "PEEK_ALL" | "DROP" => {
+            if p.at("[") {
+                p.skip_group('[', ']');
+            }
+            p.error(p.range(start), "the stack can't be represented and was dropped");
+            Expr::Empty
+        }
+        _ => Expr::Node(name.to_owned()),
+    }
+}
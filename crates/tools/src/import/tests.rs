@@ -0,0 +1,209 @@
+use expect_test::{expect, Expect};
+use syntax::ast;
+
+use crate::import::{import, ImportFormat};
+
+fn check(format: ImportFormat, text: &str, expect: Expect) {
+    let import = import(text, format);
+    let errors = ast::Grammar::parse(&import.text).errors().to_vec();
+    assert!(errors.is_empty(), "invalid Ungrammar: {errors:?}\n{}", import.text);
+
+    let mut actual = import.text;
+    for diagnostic in import.diagnostics {
+        let range = diagnostic.range;
+        let source = &text[range];
+        actual.push_str(&format!("\n// {source:?} {range:?}: {}", diagnostic.message));
+    }
+    expect.assert_eq(&actual);
+}
+
+#[test]
+fn w3c_ebnf() {
+    check(
+        ImportFormat::W3cEbnf,
+        r#"
+/* From the XML specification. */
+[1] document ::= prolog element Misc*
+[3] S ::= (#x20 | #x9 | #xD | #xA)+
+[5] Name ::= NameStartChar (NameChar)*
+[10] AttValue ::= '"' ([^<&"] | Reference)* '"'
+    |  "'" ([^<&'] | Reference)* "'"
+[14] CharData ::= [^<&]* - ([^<&]* ']]>' [^<&]*)
+[41] Attribute ::= Name Eq AttValue [ wfc: No External Entity References ]
+Empty ::= ()
+OptionalName ::= '' | Name
+"#,
+        expect![[r##"
+            Document = Prolog Element Misc*
+
+            S = ('#x20' | '#x9' | '#xD' | '#xA') ('#x20' | '#x9' | '#xD' | '#xA')*
+
+            Name = NameStartChar NameChar*
+
+            AttValue = '"' ('[^<&"]' | Reference)* '"' | '\'' ('[^<&\']' | Reference)* '\''
+
+            CharData = '[^<&]'*
+
+            Attribute = Name Eq AttValue
+
+            OptionalName = Name?
+
+            // "#x20" 84..88: character classes can't be represented, replaced by the token `'#x20'`
+            // "#x9" 91..94: character classes can't be represented, replaced by the token `'#x9'`
+            // "#xD" 97..100: character classes can't be represented, replaced by the token `'#xD'`
+            // "#xA" 103..106: character classes can't be represented, replaced by the token `'#xA'`
+            // "[^<&\"]" 171..177: character classes can't be represented, replaced by the token `'[^<&"]'`
+            // "[^<&']" 208..214: character classes can't be represented, replaced by the token `'[^<&']'`
+            // "[^<&]" 251..256: character classes can't be represented, replaced by the token `'[^<&]'`
+            // "- ([^<&]* ']]>' [^<&]*)" 258..281: exceptions can't be represented and were dropped
+            // "[^<&]" 261..266: character classes can't be represented, replaced by the token `'[^<&]'`
+            // "[^<&]" 274..279: character classes can't be represented, replaced by the token `'[^<&]'`
+            // "[ wfc: No External Entity References ]" 318..356: constraints can't be represented and were dropped
+            // "Empty" 357..362: `Empty` only matches the empty string"##]],
+    );
+}
+
+#[test]
+fn pest() {
+    check(
+        ImportFormat::Pest,
+        r#"
+//! A calculator.
+WHITESPACE = _{ " " | "\t" }
+
+/// A whole program.
+program = { SOI ~ stmt* ~ EOI }
+stmt = { expr ~ ";" }
+expr = { #lhs = term ~ (op ~ term)* }
+op = _{ "+" | "-" | ^"mod" }
+term = { number | "(" ~ expr ~ ")" | !"-" ~ ident }
+number = @{ ASCII_DIGIT+ ~ ("." ~ ASCII_DIGIT{1, 3})? }
+ident = @{ ('a'..'z' | "_")+ }
+string = ${ PUSH("\"") ~ (!PEEK ~ ANY)* ~ POP }
+nl = { "\r\n" }
+empty = { "" ~ ident }
+_ = { "y" }
+expr2 = { "2" }
+expr_2 = { expr2 ~ _ }
+"#,
+        expect![[r#"
+            /// A whole program.
+            Program = Stmt*
+
+            Stmt = Expr ';'
+
+            Expr = lhs:Term (Op Term)*
+
+            Op = '+' | '-' | 'mod'
+
+            Term = Number | '(' Expr ')' | Ident
+
+            Number = 'ASCII_DIGIT' 'ASCII_DIGIT'* ('.' 'ASCII_DIGIT'*)?
+
+            Ident = ('\'a\'..\'z\'' | '_') ('\'a\'..\'z\'' | '_')*
+
+            String = '"' 'ANY'*
+
+            Nl = '\\r\\n'
+
+            Empty = Ident
+
+            Node = 'y'
+
+            ExprTwo = '2'
+
+            ExprTwoTwo = ExprTwo Node
+
+            // "WHITESPACE" 19..29: the implicit `WHITESPACE` rule belongs to the lexer and was dropped
+            // "^\"mod\"" 182..188: case-insensitive strings can't be represented, only the exact text is matched
+            // "!\"-\"" 228..232: predicates can't be represented and were dropped
+            // "ASCII_DIGIT" 255..266: character classes can't be represented, replaced by the token `'ASCII_DIGIT'`
+            // "ASCII_DIGIT" 277..288: character classes can't be represented, replaced by the token `'ASCII_DIGIT'`
+            // "{1, 3}" 288..294: bounded repetitions can't be represented, replaced by `*`
+            // "'a'..'z'" 311..319: character classes can't be represented, replaced by the token `''a'..'z''`
+            // "PUSH(\"\\\"\")" 342..352: the stack can't be represented, the pushed expression was kept
+            // "!PEEK" 356..361: predicates can't be represented and were dropped
+            // "PEEK" 357..361: the stack can't be represented and was dropped
+            // "ANY" 364..367: character classes can't be represented, replaced by the token `'ANY'`
+            // "POP" 372..375: the stack can't be represented and was dropped
+            // "\"\\r\\n\"" 385..391: carriage returns can't be represented in tokens, replaced by the token `'\r\n'`
+            // "_" 417..418: `_` has no letters to name a node with, renamed to `Node`
+            // "expr_2" 445..451: `ExprTwo` already names another node, renamed to `ExprTwoTwo`"#]],
+    );
+}
+
+#[test]
+fn lalrpop() {
+    check(
+        ImportFormat::Lalrpop,
+        r#"
+use std::str::FromStr;
+use crate::ast::{Expr, Opcode};
+
+grammar;
+
+/// A comma-separated list.
+Comma<T>: Vec<T> = {
+    <mut v:(<T> ",")*> <e:T?> => match e {
+        None => v,
+        Some(e) => { v.push(e); v }
+    }
+};
+
+pub Exprs = Comma<Expr>;
+
+pub Expr: Box<Expr> = {
+    <l:Expr> <op:ExprOp> <r:Factor> => Box::new(Expr::Op(l, op, r)),
+    Factor,
+};
+
+ExprOp: Opcode = {
+    "+" => Opcode::Add,
+    "-" => Opcode::Sub,
+};
+
+Factor: Box<Expr> = {
+    Num => Box::new(Expr::Number(<>)),
+    "(" <Expr> ")",
+    @L ! @R => Box::new(Expr::Error),
+};
+
+Num: i32 = r"[0-9]+" => i32::from_str(<>).unwrap();
+
+Term2 = { => (), "'" "x" => '}', };
+
+match {
+    r"\s*" => { },
+} else {
+    _
+}
+"#,
+        expect![[r#"
+            /// A comma-separated list.
+            Comma = v:(T ',')* e:T?
+
+            Exprs = Comma
+
+            Expr = l:Expr op:ExprOp r:Factor | Factor
+
+            ExprOp = '+' | '-'
+
+            Factor = Num | '(' Expr ')'
+
+            Num = 'r"[0-9]+"'
+
+            TermTwo = ('\'' 'x')?
+
+            // "Comma" 95..100: macros can't be represented, `Comma` was imported with its parameters `T` undefined
+            // "Comma" 95..100: the actions of `Comma` can't be represented and were dropped
+            // "Comma<Expr>" 236..247: macros can't be represented, the arguments of `Comma` were dropped
+            // "Expr" 254..258: the actions of `Expr` can't be represented and were dropped
+            // "ExprOp" 359..365: the actions of `ExprOp` can't be represented and were dropped
+            // "Factor" 430..436: the actions of `Factor` can't be represented and were dropped
+            // "!" 518..519: error recovery can't be represented and was dropped
+            // "Num" 553..556: the actions of `Num` can't be represented and were dropped
+            // "r\"[0-9]+\"" 564..573: character classes can't be represented, replaced by the token `'r"[0-9]+"'`
+            // "Term2" 606..611: the actions of `Term2` can't be represented and were dropped
+            // "match {\n    r\"\\s*\" => { },\n} else {\n    _\n}" 643..686: `match` blocks can't be represented and were dropped"#]],
+    );
+}
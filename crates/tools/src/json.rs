@@ -0,0 +1,167 @@
+//! Serializes grammars and their syntax trees as JSON, for tools which can't
+//! use the Rust crates.
+//!
+//! The schema is versioned by [`SCHEMA_VERSION`], which changes whenever a
+//! field is removed or changes its meaning; new fields may be added without a
+//! change. A document looks like:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "grammar": {
+//!     "nodes": [{ "name": "Fn", "rule": { "kind": "seq", "rules": [...] } }],
+//!     "tokens": ["fn"]
+//!   },
+//!   "files": [{ "path": "rust.ungram", "tree": {...}, "errors": [...] }]
+//! }
+//! ```
+//!
+//! A rule is an object whose `kind` is one of:
+//!
+//! * `node` with the `name` of the referenced node,
+//! * `token` with the `text` of the literal, unescaped,
+//! * `labeled` with a `label` and the labeled `rule`,
+//! * `seq` and `alt` with a list of `rules`,
+//! * `opt` and `rep` with the optional or repeated `rule`.
+//!
+//! A node which is referenced but never defined has a `null` rule.
+//!
+//! An element of a syntax tree has the name of its `kind`, e.g. `"NODE"`,
+//! and its `range` as a pair of UTF-8 offsets. Nodes have a list of
+//! `children`, tokens have their `text`. An error has a `message` and a
+//! `range`.
+
+#[cfg(test)]
+mod tests;
+
+use hir::{Grammar, Rule, RuleData};
+use serde::Serialize;
+use serde_json::Value;
+use syntax::{ast, NodeOrToken, Parse, SyntaxElement, TextRange};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Returns the document for `grammar`, with the syntax trees of the files it
+/// was lowered from.
+pub fn document(grammar: &Grammar, files: &[(&str, &Parse<ast::Grammar>)]) -> Value {
+    let files = files
+        .iter()
+        .map(|&(path, parse)| {
+            let mut file = syntax_json(parse);
+            file.as_object_mut().unwrap().insert("path".to_owned(), path.into());
+            file
+        })
+        .collect::<Vec<_>>();
+    serde_json::json!({
+        "version": SCHEMA_VERSION,
+        "grammar": grammar_json(grammar),
+        "files": files,
+    })
+}
+
+/// Returns the `grammar` object of a document.
+pub fn grammar_json(grammar: &Grammar) -> Value {
+    let nodes = grammar
+        .iter()
+        .map(|node| NodeJson {
+            name: &grammar[node].name,
+            rule: grammar[node].rule.map(|rule| RuleJson::new(grammar, rule)),
+        })
+        .collect();
+    let tokens = grammar.tokens().map(|token| grammar[token].name.as_str()).collect();
+    serde_json::to_value(GrammarJson { nodes, tokens }).unwrap()
+}
+
+/// Returns the syntax tree and the errors of a file, as in the `files` of a
+/// document but without the `path`.
+pub fn syntax_json(parse: &Parse<ast::Grammar>) -> Value {
+    let tree = ElementJson::new(parse.syntax_node().into());
+    let errors = parse
+        .errors()
+        .iter()
+        .map(|err| ErrorJson { message: err.to_string(), range: range(err.range()) })
+        .collect();
+    serde_json::to_value(SyntaxJson { tree, errors }).unwrap()
+}
+
+#[derive(Serialize)]
+struct GrammarJson<'a> {
+    nodes: Vec<NodeJson<'a>>,
+    tokens: Vec<&'a str>,
+}
+
+#[derive(Serialize)]
+struct NodeJson<'a> {
+    name: &'a str,
+    rule: Option<RuleJson<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum RuleJson<'a> {
+    Labeled { label: &'a str, rule: Box<RuleJson<'a>> },
+    Node { name: &'a str },
+    Token { text: &'a str },
+    Seq { rules: Vec<RuleJson<'a>> },
+    Alt { rules: Vec<RuleJson<'a>> },
+    Opt { rule: Box<RuleJson<'a>> },
+    Rep { rule: Box<RuleJson<'a>> },
+}
+
+impl<'a> RuleJson<'a> {
+    fn new(grammar: &'a Grammar, rule: Rule) -> RuleJson<'a> {
+        let boxed = |rule: &Rule| Box::new(RuleJson::new(grammar, *rule));
+        let list = |rules: &[Rule]| rules.iter().map(|&it| RuleJson::new(grammar, it)).collect();
+        match &grammar[rule] {
+            RuleData::Labeled { label, rule } => RuleJson::Labeled { label, rule: boxed(rule) },
+            RuleData::Node(node) => RuleJson::Node { name: &grammar[*node].name },
+            RuleData::Token(token) => RuleJson::Token { text: &grammar[*token].name },
+            RuleData::Seq(rules) => RuleJson::Seq { rules: list(rules) },
+            RuleData::Alt(rules) => RuleJson::Alt { rules: list(rules) },
+            RuleData::Opt(rule) => RuleJson::Opt { rule: boxed(rule) },
+            RuleData::Rep(rule) => RuleJson::Rep { rule: boxed(rule) },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SyntaxJson {
+    tree: ElementJson,
+    errors: Vec<ErrorJson>,
+}
+
+#[derive(Serialize)]
+struct ElementJson {
+    kind: String,
+    range: [u32; 2],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    children: Option<Vec<ElementJson>>,
+}
+
+impl ElementJson {
+    fn new(element: SyntaxElement) -> ElementJson {
+        let kind = format!("{:?}", element.kind());
+        let range = range(element.text_range());
+        match element {
+            NodeOrToken::Node(node) => {
+                let children = node.children_with_tokens().map(ElementJson::new).collect();
+                ElementJson { kind, range, text: None, children: Some(children) }
+            }
+            NodeOrToken::Token(token) => {
+                ElementJson { kind, range, text: Some(token.text().to_owned()), children: None }
+            }
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorJson {
+    message: String,
+    range: [u32; 2],
+}
+
+fn range(range: TextRange) -> [u32; 2] {
+    [range.start().into(), range.end().into()]
+}
@@ -0,0 +1,166 @@
+use expect_test::expect;
+use syntax::ast;
+
+use crate::{
+    json::{document, grammar_json},
+    lower,
+};
+
+#[test]
+fn grammar_model() {
+    let grammar = lower("Fn = 'fn' name:Name? (Param | 'self')* Body\nName = 'ident'\n");
+    let json = serde_json::to_string_pretty(&grammar_json(&grammar)).unwrap();
+    expect![[r#"
+        {
+          "nodes": [
+            {
+              "name": "Fn",
+              "rule": {
+                "kind": "seq",
+                "rules": [
+                  {
+                    "kind": "token",
+                    "text": "fn"
+                  },
+                  {
+                    "kind": "labeled",
+                    "label": "name",
+                    "rule": {
+                      "kind": "opt",
+                      "rule": {
+                        "kind": "node",
+                        "name": "Name"
+                      }
+                    }
+                  },
+                  {
+                    "kind": "rep",
+                    "rule": {
+                      "kind": "alt",
+                      "rules": [
+                        {
+                          "kind": "node",
+                          "name": "Param"
+                        },
+                        {
+                          "kind": "token",
+                          "text": "self"
+                        }
+                      ]
+                    }
+                  },
+                  {
+                    "kind": "node",
+                    "name": "Body"
+                  }
+                ]
+              }
+            },
+            {
+              "name": "Name",
+              "rule": {
+                "kind": "token",
+                "text": "ident"
+              }
+            },
+            {
+              "name": "Param",
+              "rule": null
+            },
+            {
+              "name": "Body",
+              "rule": null
+            }
+          ],
+          "tokens": [
+            "fn",
+            "self",
+            "ident"
+          ]
+        }"#]]
+    .assert_eq(&json);
+}
+
+#[test]
+fn syntax_tree_with_errors() {
+    let text = "A =";
+    let parse = ast::Grammar::parse(text);
+    let grammar = lower(text);
+    let json = document(&grammar, &[("a.ungram", &parse)]);
+    let json = serde_json::to_string_pretty(&json["files"]).unwrap();
+    expect![[r#"
+        [
+          {
+            "errors": [
+              {
+                "message": "expected rule",
+                "range": [
+                  3,
+                  3
+                ]
+              }
+            ],
+            "path": "a.ungram",
+            "tree": {
+              "children": [
+                {
+                  "children": [
+                    {
+                      "children": [
+                        {
+                          "kind": "IDENT",
+                          "range": [
+                            0,
+                            1
+                          ],
+                          "text": "A"
+                        }
+                      ],
+                      "kind": "NAME",
+                      "range": [
+                        0,
+                        1
+                      ]
+                    },
+                    {
+                      "kind": "WHITESPACE",
+                      "range": [
+                        1,
+                        2
+                      ],
+                      "text": " "
+                    },
+                    {
+                      "kind": "EQ",
+                      "range": [
+                        2,
+                        3
+                      ],
+                      "text": "="
+                    },
+                    {
+                      "children": [],
+                      "kind": "ERROR",
+                      "range": [
+                        3,
+                        3
+                      ]
+                    }
+                  ],
+                  "kind": "NODE",
+                  "range": [
+                    0,
+                    3
+                  ]
+                }
+              ],
+              "kind": "GRAMMAR",
+              "range": [
+                0,
+                3
+              ]
+            }
+          }
+        ]"#]]
+    .assert_eq(&json);
+}
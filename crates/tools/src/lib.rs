@@ -0,0 +1,26 @@
+//! Tools which turn a grammar into other artifacts, e.g. the Rust code of
+//! its syntax trees.
+//!
+//! Unlike the `ide` crate, the tools don't care about editing: they work on
+//! the semantic model of a whole grammar, a [`hir::Grammar`], and produce
+//! text. The command line of the analyzer exposes them as subcommands.
+
+pub mod codegen;
+pub mod docs;
+pub mod dot;
+pub mod ebnf;
+pub mod import;
+pub mod json;
+pub mod railroad;
+pub mod sentences;
+pub mod tree_sitter;
+mod ungrammar;
+
+use hir::Grammar;
+use syntax::ast;
+
+/// Parses and lowers `text`, for tools which don't need the syntax.
+pub fn lower(text: &str) -> Grammar {
+    let parse = ast::Grammar::parse(text);
+    Grammar::lower(&parse.tree()).0
+}
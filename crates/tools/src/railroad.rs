@@ -0,0 +1,329 @@
+//! Renders the rules of a grammar as railroad (syntax) diagrams, as SVG.
+//!
+//! The layout follows the usual conventions: a sequence is a track of boxes,
+//! an alternative branches off below the track, an optional rule gets a
+//! bypass above it, and a repetition loops back below. Token literals are
+//! drawn as rounded boxes and node references as square ones, which link to
+//! the diagram of the node. Labels are written above the labeled rule.
+//!
+//! There is no font to measure text with, so the width of a box is estimated
+//! from the number of characters, for a monospace font of [`FONT_SIZE`].
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{Grammar, Node, Rule, RuleData};
+
+pub const FONT_SIZE: i32 = 13;
+
+const CHAR_WIDTH: i32 = 8;
+const LABEL_CHAR_WIDTH: i32 = 7;
+const LABEL_HEIGHT: i32 = 14;
+const BOX_HEIGHT: i32 = 22;
+const BOX_PADDING: i32 = 10;
+/// The radius of the arcs connecting tracks.
+const ARC: i32 = 10;
+/// The horizontal gap between the items of a sequence.
+const GAP: i32 = 10;
+/// The minimal vertical gap between tracks.
+const TRACK_GAP: i32 = 10;
+const PADDING: i32 = 10;
+
+const STYLE: &str = "\
+path { fill: none; stroke: #333; stroke-width: 2; }
+rect { stroke: #333; stroke-width: 2; }
+rect.terminal { fill: #e8f5e8; }
+rect.nonterminal { fill: #e8ecf8; }
+text { font-family: monospace; font-size: 13px; text-anchor: middle; }
+text.label { font-size: 11px; font-style: italic; text-anchor: start; fill: #555; }
+a text { fill: #1a3c8c; }";
+
+/// Returns the diagram of the rule of `node` as an SVG document, or `None` if
+/// the node isn't defined. Node references link to `{href_prefix}#Name`.
+pub fn node_diagram(grammar: &Grammar, node: Node, href_prefix: &str) -> Option<String> {
+    let rule = grammar[node].rule?;
+    let element = Element::from_rule(grammar, rule);
+
+    let width = element.width + 2 * PADDING + 2 * GAP;
+    let height = element.up + element.down + 2 * PADDING;
+    let y = PADDING + element.up;
+    let mut buf = String::new();
+    writeln!(
+        buf,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" class="railroad" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    )
+    .unwrap();
+    writeln!(buf, "<style>\n{STYLE}\n</style>").unwrap();
+    let mut renderer = Renderer { buf, href_prefix };
+    // The start and the end of the diagram are marked by vertical bars.
+    renderer.path(format!(
+        "M{PADDING} {} v{BOX_HEIGHT} m0 {} h{GAP}",
+        y - BOX_HEIGHT / 2,
+        -BOX_HEIGHT / 2
+    ));
+    renderer.element(&element, PADDING + GAP, y);
+    let end = PADDING + GAP + element.width;
+    renderer.path(format!("M{end} {y} h{GAP} m0 {} v{BOX_HEIGHT}", -BOX_HEIGHT / 2));
+    renderer.buf.push_str("</svg>\n");
+    Some(renderer.buf)
+}
+
+/// Returns an HTML page with the diagrams of all defined nodes, preceded by
+/// an index linking to each of them.
+pub fn to_html(grammar: &Grammar, title: &str) -> String {
+    let nodes = grammar.iter().filter(|&node| grammar[node].rule.is_some()).collect::<Vec<_>>();
+    let title = escape(title);
+    let mut buf = String::new();
+    writeln!(buf, "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">").unwrap();
+    writeln!(buf, "<title>{title}</title>\n</head>\n<body>\n<h1>{title}</h1>").unwrap();
+    buf.push_str("<ul>\n");
+    for &node in &nodes {
+        let name = escape(&grammar[node].name);
+        writeln!(buf, "<li><a href=\"#{name}\">{name}</a></li>").unwrap();
+    }
+    buf.push_str("</ul>\n");
+    for &node in &nodes {
+        let name = escape(&grammar[node].name);
+        writeln!(buf, "<h2 id=\"{name}\">{name}</h2>").unwrap();
+        buf.push_str(&node_diagram(grammar, node, "").unwrap());
+    }
+    buf.push_str("</body>\n</html>\n");
+    buf
+}
+
+/// A part of a diagram, with its size relative to the track it is on: it
+/// extends `up` above the track and `down` below.
+struct Element {
+    width: i32,
+    up: i32,
+    down: i32,
+    kind: ElementKind,
+}
+
+enum ElementKind {
+    Terminal(String),
+    NonTerminal(String),
+    Seq(Vec<Element>),
+    /// The first alternative is on the track, the others below.
+    Choice(Vec<Element>),
+    Optional(Box<Element>),
+    OneOrMore(Box<Element>),
+    Labeled(String, Box<Element>),
+}
+
+impl Element {
+    fn from_rule(grammar: &Grammar, rule: Rule) -> Element {
+        match &grammar[rule] {
+            RuleData::Labeled { label, rule } => {
+                Element::labeled(label.clone(), Element::from_rule(grammar, *rule))
+            }
+            RuleData::Node(node) => Element::text(ElementKind::NonTerminal, &grammar[*node].name),
+            RuleData::Token(token) => Element::text(ElementKind::Terminal, &grammar[*token].name),
+            RuleData::Seq(rules) => {
+                Element::seq(rules.iter().map(|&it| Element::from_rule(grammar, it)).collect())
+            }
+            RuleData::Alt(rules) => {
+                Element::choice(rules.iter().map(|&it| Element::from_rule(grammar, it)).collect())
+            }
+            RuleData::Opt(rule) => Element::optional(Element::from_rule(grammar, *rule)),
+            RuleData::Rep(rule) => {
+                Element::optional(Element::one_or_more(Element::from_rule(grammar, *rule)))
+            }
+        }
+    }
+
+    fn text(kind: fn(String) -> ElementKind, text: &str) -> Element {
+        let width = text.chars().count() as i32 * CHAR_WIDTH + 2 * BOX_PADDING;
+        let (up, down) = (BOX_HEIGHT / 2, BOX_HEIGHT / 2);
+        Element { width, up, down, kind: kind(text.to_owned()) }
+    }
+
+    fn seq(items: Vec<Element>) -> Element {
+        let gaps = items.len().saturating_sub(1) as i32 * GAP;
+        let width = items.iter().map(|it| it.width).sum::<i32>() + gaps;
+        let up = items.iter().map(|it| it.up).max().unwrap_or(0);
+        let down = items.iter().map(|it| it.down).max().unwrap_or(0);
+        Element { width, up, down, kind: ElementKind::Seq(items) }
+    }
+
+    fn choice(alts: Vec<Element>) -> Element {
+        let width = alts.iter().map(|it| it.width).max().unwrap_or(0) + 4 * ARC;
+        let up = alts.first().map_or(0, |it| it.up);
+        let down = choice_tracks(&alts).last().map_or(0, |&(y, alt)| y + alt.down);
+        Element { width, up, down, kind: ElementKind::Choice(alts) }
+    }
+
+    fn optional(item: Element) -> Element {
+        let width = item.width + 4 * ARC;
+        let up = bypass_height(&item);
+        let down = item.down;
+        Element { width, up, down, kind: ElementKind::Optional(Box::new(item)) }
+    }
+
+    fn one_or_more(item: Element) -> Element {
+        let width = item.width + 2 * ARC;
+        let up = item.up;
+        let down = loop_depth(&item);
+        Element { width, up, down, kind: ElementKind::OneOrMore(Box::new(item)) }
+    }
+
+    fn labeled(label: String, item: Element) -> Element {
+        let label_width = label.chars().count() as i32 * LABEL_CHAR_WIDTH;
+        let width = item.width.max(label_width);
+        let up = item.up + LABEL_HEIGHT;
+        let down = item.down;
+        Element { width, up, down, kind: ElementKind::Labeled(label, Box::new(item)) }
+    }
+}
+
+/// Returns the alternatives of a choice with the offset of their track from
+/// the track of the choice.
+fn choice_tracks(alts: &[Element]) -> Vec<(i32, &Element)> {
+    let mut res = Vec::new();
+    let mut bottom = 0;
+    for (i, alt) in alts.iter().enumerate() {
+        let y = if i == 0 { 0 } else { (bottom + TRACK_GAP + alt.up).max(2 * ARC) };
+        res.push((y, alt));
+        bottom = y + alt.down;
+    }
+    res
+}
+
+fn bypass_height(item: &Element) -> i32 {
+    (item.up + TRACK_GAP).max(2 * ARC)
+}
+
+fn loop_depth(item: &Element) -> i32 {
+    (item.down + TRACK_GAP).max(2 * ARC)
+}
+
+struct Renderer<'a> {
+    buf: String,
+    href_prefix: &'a str,
+}
+
+impl Renderer<'_> {
+    /// Draws `element` with its track entering at `(x, y)`.
+    fn element(&mut self, element: &Element, x: i32, y: i32) {
+        let width = element.width;
+        match &element.kind {
+            ElementKind::Terminal(text) => self.text_box("terminal", text, x, y, width),
+            ElementKind::NonTerminal(name) => {
+                let href = format!("{}#{}", self.href_prefix, name);
+                writeln!(self.buf, "<a href=\"{}\">", escape(&href)).unwrap();
+                self.text_box("nonterminal", name, x, y, width);
+                self.buf.push_str("</a>\n");
+            }
+            ElementKind::Seq(items) => {
+                let mut x = x;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        self.path(format!("M{x} {y} h{GAP}"));
+                        x += GAP;
+                    }
+                    self.element(item, x, y);
+                    x += item.width;
+                }
+            }
+            ElementKind::Choice(alts) => {
+                let end = x + width;
+                for (dy, alt) in choice_tracks(alts) {
+                    let alt_end = x + 2 * ARC + alt.width;
+                    if dy == 0 {
+                        self.path(format!("M{x} {y} h{}", 2 * ARC));
+                    } else {
+                        let v = dy - 2 * ARC;
+                        self.path(format!(
+                            "M{x} {y} a{ARC} {ARC} 0 0 1 {ARC} {ARC} v{v} a{ARC} {ARC} 0 0 0 {ARC} {ARC}"
+                        ));
+                    }
+                    self.element(alt, x + 2 * ARC, y + dy);
+                    if dy == 0 {
+                        self.path(format!("M{alt_end} {y} H{end}"));
+                    } else {
+                        let v = dy - 2 * ARC;
+                        self.path(format!(
+                            "M{alt_end} {} H{} a{ARC} {ARC} 0 0 0 {ARC} -{ARC} v-{v} a{ARC} {ARC} 0 0 1 {ARC} -{ARC}",
+                            y + dy,
+                            end - 2 * ARC,
+                        ));
+                    }
+                }
+            }
+            ElementKind::Optional(item) => {
+                let v = bypass_height(item) - 2 * ARC;
+                let h = width - 4 * ARC;
+                self.path(format!(
+                    "M{x} {y} a{ARC} {ARC} 0 0 0 {ARC} -{ARC} v-{v} a{ARC} {ARC} 0 0 1 {ARC} -{ARC} \
+                     h{h} a{ARC} {ARC} 0 0 1 {ARC} {ARC} v{v} a{ARC} {ARC} 0 0 0 {ARC} {ARC}"
+                ));
+                self.path(format!("M{x} {y} h{}", 2 * ARC));
+                self.element(item, x + 2 * ARC, y);
+                self.path(format!("M{} {y} h{}", x + 2 * ARC + item.width, 2 * ARC));
+            }
+            ElementKind::OneOrMore(item) => {
+                let v = loop_depth(item) - 2 * ARC;
+                let h = width - 2 * ARC;
+                self.path(format!("M{x} {y} h{ARC}"));
+                self.element(item, x + ARC, y);
+                self.path(format!("M{} {y} h{ARC}", x + ARC + item.width));
+                self.path(format!(
+                    "M{} {y} a{ARC} {ARC} 0 0 1 {ARC} {ARC} v{v} a{ARC} {ARC} 0 0 1 -{ARC} {ARC} \
+                     h-{h} a{ARC} {ARC} 0 0 1 -{ARC} -{ARC} v-{v} a{ARC} {ARC} 0 0 1 {ARC} -{ARC}",
+                    x + width - ARC,
+                ));
+            }
+            ElementKind::Labeled(label, item) => {
+                writeln!(
+                    self.buf,
+                    "<text class=\"label\" x=\"{x}\" y=\"{}\">{}</text>",
+                    y - item.up - 4,
+                    escape(label),
+                )
+                .unwrap();
+                self.element(item, x, y);
+                if item.width < width {
+                    self.path(format!("M{} {y} h{}", x + item.width, width - item.width));
+                }
+            }
+        }
+    }
+
+    fn text_box(&mut self, class: &str, text: &str, x: i32, y: i32, width: i32) {
+        let rx = if class == "terminal" { BOX_HEIGHT / 2 } else { 0 };
+        writeln!(
+            self.buf,
+            "<rect class=\"{class}\" x=\"{x}\" y=\"{}\" width=\"{width}\" height=\"{BOX_HEIGHT}\" rx=\"{rx}\"/>",
+            y - BOX_HEIGHT / 2,
+        )
+        .unwrap();
+        writeln!(
+            self.buf,
+            "<text x=\"{}\" y=\"{}\">{}</text>",
+            x + width / 2,
+            y + FONT_SIZE / 3,
+            escape(text),
+        )
+        .unwrap();
+    }
+
+    fn path(&mut self, d: String) {
+        writeln!(self.buf, "<path d=\"{d}\"/>").unwrap();
+    }
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut res = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => res.push_str("&amp;"),
+            '<' => res.push_str("&lt;"),
+            '>' => res.push_str("&gt;"),
+            '"' => res.push_str("&quot;"),
+            c => res.push(c),
+        }
+    }
+    res
+}
@@ -0,0 +1,139 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    lower,
+    railroad::{node_diagram, to_html},
+};
+
+fn check_diagram(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    let node = grammar.iter().next().unwrap();
+    expect.assert_eq(&node_diagram(&grammar, node, "").unwrap());
+}
+
+#[test]
+fn sequence() {
+    check_diagram(
+        "Fn = 'fn' name:Name",
+        expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" class="railroad" width="138" height="56" viewBox="0 0 138 56">
+        <style>
+        path { fill: none; stroke: #333; stroke-width: 2; }
+        rect { stroke: #333; stroke-width: 2; }
+        rect.terminal { fill: #e8f5e8; }
+        rect.nonterminal { fill: #e8ecf8; }
+        text { font-family: monospace; font-size: 13px; text-anchor: middle; }
+        text.label { font-size: 11px; font-style: italic; text-anchor: start; fill: #555; }
+        a text { fill: #1a3c8c; }
+        </style>
+        <path d="M10 24 v22 m0 -11 h10"/>
+        <rect class="terminal" x="20" y="24" width="36" height="22" rx="11"/>
+        <text x="38" y="39">fn</text>
+        <path d="M56 35 h10"/>
+        <text class="label" x="66" y="20">name</text>
+        <a href="#Name">
+        <rect class="nonterminal" x="66" y="24" width="52" height="22" rx="0"/>
+        <text x="92" y="39">Name</text>
+        </a>
+        <path d="M118 35 h10 m0 -11 v22"/>
+        </svg>
+    "##]],
+    );
+}
+
+#[test]
+fn choice() {
+    check_diagram(
+        "Item = Fn | Struct",
+        expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" class="railroad" width="148" height="74" viewBox="0 0 148 74">
+        <style>
+        path { fill: none; stroke: #333; stroke-width: 2; }
+        rect { stroke: #333; stroke-width: 2; }
+        rect.terminal { fill: #e8f5e8; }
+        rect.nonterminal { fill: #e8ecf8; }
+        text { font-family: monospace; font-size: 13px; text-anchor: middle; }
+        text.label { font-size: 11px; font-style: italic; text-anchor: start; fill: #555; }
+        a text { fill: #1a3c8c; }
+        </style>
+        <path d="M10 10 v22 m0 -11 h10"/>
+        <path d="M20 21 h20"/>
+        <a href="#Fn">
+        <rect class="nonterminal" x="40" y="10" width="36" height="22" rx="0"/>
+        <text x="58" y="25">Fn</text>
+        </a>
+        <path d="M76 21 H128"/>
+        <path d="M20 21 a10 10 0 0 1 10 10 v12 a10 10 0 0 0 10 10"/>
+        <a href="#Struct">
+        <rect class="nonterminal" x="40" y="42" width="68" height="22" rx="0"/>
+        <text x="74" y="57">Struct</text>
+        </a>
+        <path d="M108 53 H108 a10 10 0 0 0 10 -10 v-12 a10 10 0 0 1 10 -10"/>
+        <path d="M128 21 h10 m0 -11 v22"/>
+        </svg>
+    "##]],
+    );
+}
+
+#[test]
+fn optional_and_repetition() {
+    check_diagram(
+        "List = '(' Item* ')' ';'?",
+        expect![[r##"
+        <svg xmlns="http://www.w3.org/2000/svg" class="railroad" width="306" height="62" viewBox="0 0 306 62">
+        <style>
+        path { fill: none; stroke: #333; stroke-width: 2; }
+        rect { stroke: #333; stroke-width: 2; }
+        rect.terminal { fill: #e8f5e8; }
+        rect.nonterminal { fill: #e8ecf8; }
+        text { font-family: monospace; font-size: 13px; text-anchor: middle; }
+        text.label { font-size: 11px; font-style: italic; text-anchor: start; fill: #555; }
+        a text { fill: #1a3c8c; }
+        </style>
+        <path d="M10 20 v22 m0 -11 h10"/>
+        <rect class="terminal" x="20" y="20" width="28" height="22" rx="11"/>
+        <text x="34" y="35">(</text>
+        <path d="M48 31 h10"/>
+        <path d="M58 31 a10 10 0 0 0 10 -10 v-1 a10 10 0 0 1 10 -10 h72 a10 10 0 0 1 10 10 v1 a10 10 0 0 0 10 10"/>
+        <path d="M58 31 h20"/>
+        <path d="M78 31 h10"/>
+        <a href="#Item">
+        <rect class="nonterminal" x="88" y="20" width="52" height="22" rx="0"/>
+        <text x="114" y="35">Item</text>
+        </a>
+        <path d="M140 31 h10"/>
+        <path d="M140 31 a10 10 0 0 1 10 10 v1 a10 10 0 0 1 -10 10 h-52 a10 10 0 0 1 -10 -10 v-1 a10 10 0 0 1 10 -10"/>
+        <path d="M150 31 h20"/>
+        <path d="M170 31 h10"/>
+        <rect class="terminal" x="180" y="20" width="28" height="22" rx="11"/>
+        <text x="194" y="35">)</text>
+        <path d="M208 31 h10"/>
+        <path d="M218 31 a10 10 0 0 0 10 -10 v-1 a10 10 0 0 1 10 -10 h28 a10 10 0 0 1 10 10 v1 a10 10 0 0 0 10 10"/>
+        <path d="M218 31 h20"/>
+        <rect class="terminal" x="238" y="20" width="28" height="22" rx="11"/>
+        <text x="252" y="35">;</text>
+        <path d="M266 31 h20"/>
+        <path d="M286 31 h10 m0 -11 v22"/>
+        </svg>
+    "##]],
+    );
+}
+
+#[test]
+fn html_links_all_nodes() {
+    let grammar = lower("Item = Fn | Struct\nFn = 'fn' '<&>'\n");
+    let html = to_html(&grammar, "Items");
+    let headings = html.lines().filter(|it| it.starts_with("<h") || it.starts_with("<li"));
+    expect![[r##"
+        <html>
+        <head>
+        <h1>Items</h1>
+        <li><a href="#Item">Item</a></li>
+        <li><a href="#Fn">Fn</a></li>
+        <h2 id="Item">Item</h2>
+        <h2 id="Fn">Fn</h2>"##]]
+    .assert_eq(&headings.collect::<Vec<_>>().join("\n"));
+    assert!(html.contains("<a href=\"#Fn\">"));
+    assert!(html.contains("&lt;&amp;&gt;"));
+    assert!(node_diagram(&grammar, grammar.find_node("Struct").unwrap(), "").is_none());
+}
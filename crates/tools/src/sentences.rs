@@ -0,0 +1,189 @@
+//! Generates random sentences of a grammar, to fuzz the parsers whose
+//! structure the grammar describes.
+//!
+//! A sentence is the sequence of the texts of the tokens a node derives.
+//! Alternatives are picked uniformly, optional rules are taken and repeated
+//! rules continue with a probability of one half. The optional and repeated
+//! rules of the root itself are taken at least once when the bounds allow,
+//! otherwise a root like `SourceFile = Item*` would produce an empty
+//! sentence half of the time. Token kinds, like
+//! `'ident'`, are produced as they are spelled in the grammar and nodes
+//! which are referenced but never defined produce nothing.
+//!
+//! Sentences are kept within bounds on their number of tokens and on the
+//! nesting of nodes: when a bound would be exceeded, the generator takes the
+//! shortest way out instead, skipping optional and repeated rules and picking
+//! the alternative with the shortest derivation. A sentence only exceeds
+//! `max_tokens` if the shortest sentence of the root does, and nodes without
+//! any finite derivation, like `A = 'a' A`, are cut off at `max_depth`.
+
+#[cfg(test)]
+mod tests;
+
+use hir::{Grammar, Node, Rule, RuleData};
+use oorandom::Rand64;
+use rustc_hash::FxHashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SentenceConfig {
+    /// The same seed produces the same sentences for the same grammar.
+    pub seed: u64,
+    /// How deeply nodes may nest, the root being at depth 0.
+    pub max_depth: usize,
+    pub max_tokens: usize,
+}
+
+impl Default for SentenceConfig {
+    fn default() -> SentenceConfig {
+        SentenceConfig { seed: 0, max_depth: 16, max_tokens: 256 }
+    }
+}
+
+pub struct SentenceGenerator<'a> {
+    grammar: &'a Grammar,
+    config: SentenceConfig,
+    /// The cost of the shortest derivation of each node.
+    costs: FxHashMap<Node, Cost>,
+    rng: Rand64,
+}
+
+/// The number of tokens and the nesting of nodes of the shortest derivation
+/// of a rule. Following the cheapest alternatives always terminates, as the
+/// nesting decreases with each node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Cost {
+    tokens: usize,
+    height: usize,
+}
+
+impl Cost {
+    const ZERO: Cost = Cost { tokens: 0, height: 0 };
+    const INFINITE: Cost = Cost { tokens: usize::MAX, height: usize::MAX };
+
+    fn then(self, other: Cost) -> Cost {
+        Cost {
+            tokens: self.tokens.saturating_add(other.tokens),
+            height: self.height.max(other.height),
+        }
+    }
+}
+
+impl<'a> SentenceGenerator<'a> {
+    pub fn new(grammar: &'a Grammar, config: SentenceConfig) -> SentenceGenerator<'a> {
+        let mut gen = SentenceGenerator {
+            grammar,
+            config,
+            costs: grammar.iter().map(|node| (node, Cost::INFINITE)).collect(),
+            rng: Rand64::new(config.seed.into()),
+        };
+        // The costs only decrease, until they are the least fixed point.
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in grammar.iter() {
+                let cost = match grammar[node].rule {
+                    Some(rule) => gen.cost(rule),
+                    None => Cost::ZERO,
+                };
+                if cost < gen.costs[&node] {
+                    gen.costs.insert(node, cost);
+                    changed = true;
+                }
+            }
+        }
+        gen
+    }
+
+    /// Returns the texts of the tokens of a random sentence of `root`.
+    pub fn sentence(&mut self, root: Node) -> Vec<String> {
+        let mut res = Vec::new();
+        self.node(root, 0, self.config.max_tokens, &mut res);
+        res
+    }
+
+    fn node_cost(&self, node: Node) -> Cost {
+        let cost = self.costs[&node];
+        Cost { height: cost.height.saturating_add(1), ..cost }
+    }
+
+    fn cost(&self, rule: Rule) -> Cost {
+        match &self.grammar[rule] {
+            RuleData::Labeled { rule, .. } => self.cost(*rule),
+            RuleData::Node(node) => self.node_cost(*node),
+            RuleData::Token(_) => Cost { tokens: 1, height: 0 },
+            RuleData::Seq(rules) => {
+                rules.iter().fold(Cost::ZERO, |acc, &it| acc.then(self.cost(it)))
+            }
+            RuleData::Alt(rules) => {
+                rules.iter().map(|&it| self.cost(it)).min().unwrap_or(Cost::ZERO)
+            }
+            RuleData::Opt(_) | RuleData::Rep(_) => Cost::ZERO,
+        }
+    }
+
+    fn node(&mut self, node: Node, depth: usize, budget: usize, acc: &mut Vec<String>) {
+        let Some(rule) = self.grammar[node].rule else { return };
+        if depth >= self.config.max_depth && self.node_cost(node) == Cost::INFINITE {
+            return;
+        }
+        self.rule(rule, depth, budget, acc);
+    }
+
+    /// Appends the tokens of `rule` to `acc`, using at most `budget` tokens
+    /// unless even the shortest derivation needs more.
+    fn rule(&mut self, rule: Rule, depth: usize, budget: usize, acc: &mut Vec<String>) {
+        let grammar = self.grammar;
+        // Past the depth bound, only the shortest derivations are generated.
+        let free = depth < self.config.max_depth;
+        let root = depth == 0;
+        match &grammar[rule] {
+            RuleData::Labeled { rule, .. } => self.rule(*rule, depth, budget, acc),
+            RuleData::Node(node) => self.node(*node, depth + 1, budget, acc),
+            RuleData::Token(token) => acc.push(grammar[*token].name.clone()),
+            RuleData::Seq(rules) => {
+                let start = acc.len();
+                for (i, &rule) in rules.iter().enumerate() {
+                    let rest = rules[i + 1..]
+                        .iter()
+                        .fold(0, |acc: usize, &it| acc.saturating_add(self.cost(it).tokens));
+                    let used = acc.len() - start;
+                    let budget = budget.saturating_sub(used).saturating_sub(rest);
+                    self.rule(rule, depth, budget, acc);
+                }
+            }
+            RuleData::Alt(rules) => {
+                let fitting = rules
+                    .iter()
+                    .copied()
+                    .filter(|&it| self.cost(it).tokens <= budget)
+                    .collect::<Vec<_>>();
+                let rule = if free && !fitting.is_empty() {
+                    fitting[self.rng.rand_range(0..fitting.len() as u64) as usize]
+                } else {
+                    *rules.iter().min_by_key(|&&it| self.cost(it)).unwrap()
+                };
+                self.rule(rule, depth, budget, acc);
+            }
+            RuleData::Opt(rule) => {
+                if free && self.cost(*rule).tokens <= budget && (root || self.coin()) {
+                    self.rule(*rule, depth, budget, acc);
+                }
+            }
+            RuleData::Rep(rule) => {
+                let start = acc.len();
+                loop {
+                    let budget = budget - (acc.len() - start).min(budget);
+                    let first = root && acc.len() == start;
+                    if !free || self.cost(*rule).tokens > budget || !(first || self.coin()) {
+                        break;
+                    }
+                    self.rule(*rule, depth, budget, acc);
+                }
+            }
+        }
+    }
+
+    fn coin(&mut self) -> bool {
+        self.rng.rand_range(0..2) == 0
+    }
+}
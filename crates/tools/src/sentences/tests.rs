@@ -0,0 +1,110 @@
+use expect_test::{expect, Expect};
+
+use crate::{
+    lower,
+    sentences::{SentenceConfig, SentenceGenerator},
+};
+
+fn check(ungrammar: &str, config: SentenceConfig, expect: Expect) {
+    let grammar = lower(ungrammar);
+    let root = grammar.iter().next().unwrap();
+    let mut gen = SentenceGenerator::new(&grammar, config);
+    let sentences = (0..5).map(|_| gen.sentence(root).join(" ")).collect::<Vec<_>>();
+    expect.assert_eq(&sentences.join("\n"));
+}
+
+const EXPR: &str = r#"
+Expr = Literal | BinExpr | ParenExpr | CallExpr
+Literal = 'int_number'
+BinExpr = lhs:Expr ('+' | '*') rhs:Expr
+ParenExpr = '(' Expr ')'
+CallExpr = Expr '(' (Expr (',' Expr)*)? ')'
+"#;
+
+#[test]
+fn random_sentences() {
+    check(
+        EXPR,
+        SentenceConfig { seed: 1, max_tokens: 32, ..SentenceConfig::default() },
+        expect![[r#"
+            int_number
+            ( int_number ( int_number , int_number , int_number ) + ( int_number ) * int_number ( ) ( ) ) * int_number * ( int_number * int_number ) ( ) ( )
+            int_number ( ( int_number ) ( int_number * int_number + int_number * int_number , ( int_number ) ( ) ) + int_number , int_number ) + int_number + int_number * int_number
+            int_number
+            ( ( int_number ( ) ) + int_number ( int_number ( ) , int_number , ( int_number ) , int_number ) )"#]],
+    );
+}
+
+#[test]
+fn same_seed_same_sentences() {
+    let grammar = lower(EXPR);
+    let root = grammar.iter().next().unwrap();
+    let config = SentenceConfig { seed: 42, ..SentenceConfig::default() };
+    let sentences = || {
+        let mut gen = SentenceGenerator::new(&grammar, config);
+        (0..10).map(|_| gen.sentence(root)).collect::<Vec<_>>()
+    };
+    assert_eq!(sentences(), sentences());
+}
+
+#[test]
+fn bounds() {
+    check(
+        EXPR,
+        SentenceConfig { seed: 1, max_depth: 2, max_tokens: 256 },
+        expect![[r#"
+            int_number
+            int_number + int_number
+            ( int_number )
+            int_number ( )
+            int_number + int_number"#]],
+    );
+    check(
+        EXPR,
+        SentenceConfig { seed: 1, max_depth: 16, max_tokens: 5 },
+        expect![[r#"
+        int_number
+        int_number * int_number + int_number
+        int_number + int_number ( )
+        int_number
+        ( ( int_number ) )"#]],
+    );
+}
+
+#[test]
+fn repetitions_of_the_root() {
+    check(
+        "SourceFile = Item* Eof?\nItem = 'fn' | 'struct'\nEof = 'eof'",
+        SentenceConfig { seed: 1, ..SentenceConfig::default() },
+        expect![[r#"
+            fn fn eof
+            struct eof
+            fn struct fn struct struct eof
+            fn eof
+            struct eof"#]],
+    );
+}
+
+#[test]
+fn infinite_and_undefined_nodes() {
+    check(
+        "A = 'a' A | B 'b'",
+        SentenceConfig { seed: 1, max_depth: 3, max_tokens: 256 },
+        expect![[r#"
+            a a a b
+            b
+            b
+            b
+            a a b"#]],
+    );
+    check(
+        "A = 'a' A",
+        SentenceConfig { seed: 1, max_depth: 3, max_tokens: 256 },
+        expect![[r#"
+        a a a
+        a a a
+        a a a
+        a a a
+        a a a"#]],
+    );
+}
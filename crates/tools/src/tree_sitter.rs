@@ -0,0 +1,268 @@
+//! Generates a skeleton of a tree-sitter `grammar.js` from a grammar.
+//!
+//! Every defined node becomes a rule of the same shape: sequences become
+//! `seq`, alternatives `choice`, `?` and `*` become `optional` and `repeat`,
+//! and labels become `field`s. The first node is the root of the tree-sitter
+//! grammar. Nodes which are alternatives of other nodes, the enums of the
+//! typed AST, become hidden supertypes, so they don't add a level to the
+//! tree.
+//!
+//! Token literals become string terminals, except those which name a kind of
+//! token (see [`crate::codegen::derive_kinds`]), like `'ident'`: they become
+//! rules of their own, whose pattern has to be filled in.
+//!
+//! Ungrammar says nothing about precedence or associativity, which
+//! tree-sitter needs to resolve ambiguities. Nodes which start with
+//! themselves, like `BinExpr = Expr '+' Expr` with `Expr = BinExpr | ...`,
+//! and alternatives which start with the same symbol get a `TODO` comment.
+
+#[cfg(test)]
+mod tests;
+
+use std::fmt::Write;
+
+use hir::{fields, Grammar, Node, Rule, RuleData, Token};
+use rustc_hash::FxHashSet;
+use stdx::to_lower_snake_case;
+
+use crate::codegen::KindsSrc;
+
+/// Returns the `grammar.js` of a tree-sitter grammar named `name`.
+pub fn to_grammar_js(grammar: &Grammar, name: &str) -> String {
+    let gen = Generator { grammar, kinds: KindsSrc::from_grammar(grammar) };
+    let nodes = grammar.iter().collect::<Vec<_>>();
+    let supertypes = nodes.iter().filter(|&&node| gen.is_supertype(node)).collect::<Vec<_>>();
+    let token_rules =
+        grammar.tokens().filter(|&token| gen.is_token_rule(token)).collect::<Vec<_>>();
+
+    let mut buf = String::new();
+    writeln!(buf, "module.exports = grammar({{\n  name: {},\n", js_string(name)).unwrap();
+    buf.push_str("  extras: $ => [/\\s/],\n");
+    if let Some(&ident) = token_rules.iter().find(|&&token| grammar[token].name == "ident") {
+        writeln!(buf, "\n  word: $ => $.{},", gen.token_rule_name(ident)).unwrap();
+    }
+    if !supertypes.is_empty() {
+        buf.push_str("\n  supertypes: $ => [\n");
+        for &&node in &supertypes {
+            writeln!(buf, "    $.{},", gen.rule_name(node)).unwrap();
+        }
+        buf.push_str("  ],\n");
+    }
+    buf.push_str("\n  rules: {\n");
+    for &node in &nodes {
+        let rule_name = gen.rule_name(node);
+        let Some(rule) = grammar[node].rule else {
+            let name = &grammar[node].name;
+            writeln!(buf, "    // TODO: `{name}` is referenced but not defined.").unwrap();
+            writeln!(buf, "    {rule_name}: $ => blank(),\n").unwrap();
+            continue;
+        };
+        for todo in gen.precedence_todos(node) {
+            writeln!(buf, "    // TODO: precedence, {todo}.").unwrap();
+        }
+        let prefix = format!("    {rule_name}: $ => ");
+        buf.push_str(&prefix);
+        gen.rule(rule).write(&mut buf, prefix.len(), 4);
+        buf.push_str(",\n\n");
+    }
+    for &token in &token_rules {
+        let text = &grammar[token].name;
+        writeln!(buf, "    // TODO: the pattern of `{text}` tokens.").unwrap();
+        writeln!(buf, "    {}: $ => /TODO/,\n", gen.token_rule_name(token)).unwrap();
+    }
+    buf.truncate(buf.trim_end().len());
+    buf.push_str("\n  },\n});\n");
+    buf
+}
+
+struct Generator<'a> {
+    grammar: &'a Grammar,
+    kinds: KindsSrc,
+}
+
+impl Generator<'_> {
+    fn is_supertype(&self, node: Node) -> bool {
+        fields::is_enum(self.grammar, node)
+    }
+
+    fn rule_name(&self, node: Node) -> String {
+        let name = to_lower_snake_case(&self.grammar[node].name);
+        if self.is_supertype(node) {
+            format!("_{name}")
+        } else {
+            name
+        }
+    }
+
+    fn is_token_rule(&self, token: Token) -> bool {
+        let text = &self.grammar[token].name;
+        self.kinds.token_kind(text).is_some_and(|kind| self.kinds.tokens.contains(&kind))
+    }
+
+    fn token_rule_name(&self, token: Token) -> String {
+        self.grammar[token].name.to_lowercase()
+    }
+
+    fn rule(&self, rule: Rule) -> Js {
+        let call = |name: &str, args: Vec<Js>| Js::Call(name.to_owned(), args);
+        let list = |rules: &[Rule]| rules.iter().map(|&it| self.rule(it)).collect();
+        match &self.grammar[rule] {
+            RuleData::Labeled { label, rule } => {
+                call("field", vec![Js::Atom(js_string(label)), self.rule(*rule)])
+            }
+            RuleData::Node(node) => Js::Atom(format!("$.{}", self.rule_name(*node))),
+            RuleData::Token(token) if self.is_token_rule(*token) => {
+                Js::Atom(format!("$.{}", self.token_rule_name(*token)))
+            }
+            RuleData::Token(token) => Js::Atom(js_string(&self.grammar[*token].name)),
+            RuleData::Seq(rules) => call("seq", list(rules)),
+            RuleData::Alt(rules) => call("choice", list(rules)),
+            RuleData::Opt(rule) => call("optional", vec![self.rule(*rule)]),
+            RuleData::Rep(rule) => call("repeat", vec![self.rule(*rule)]),
+        }
+    }
+
+    fn precedence_todos(&self, node: Node) -> Vec<String> {
+        let grammar = self.grammar;
+        let mut res = Vec::new();
+        let name = &grammar[node].name;
+        if !self.is_supertype(node) && self.starts_with(node, node) {
+            res.push(format!("`{name}` starts with itself, use prec.left or prec.right"));
+        }
+        let Some(rule) = grammar[node].rule else { return res };
+        grammar.walk_rule(rule, &mut |rule| {
+            let RuleData::Alt(alts) = &grammar[rule] else { return };
+            let mut seen = FxHashSet::default();
+            let mut shared = Vec::new();
+            for &alt in alts {
+                for symbol in self.first(alt) {
+                    if !seen.insert(symbol) && !shared.contains(&symbol) {
+                        shared.push(symbol);
+                    }
+                }
+            }
+            for symbol in shared {
+                let symbol = match symbol {
+                    Symbol::Node(node) => format!("`{}`", grammar[node].name),
+                    Symbol::Token(token) => format!("`'{}'`", grammar[token].name),
+                };
+                res.push(format!("several alternatives of `{name}` start with {symbol}"));
+            }
+        });
+        res
+    }
+
+    /// Returns true if `node` can start with `target`, through the first
+    /// symbols of the rules.
+    fn starts_with(&self, node: Node, target: Node) -> bool {
+        let mut visited = FxHashSet::default();
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            let Some(rule) = self.grammar[node].rule else { continue };
+            for symbol in self.first(rule) {
+                let Symbol::Node(next) = symbol else { continue };
+                if next == target {
+                    return true;
+                }
+                if visited.insert(next) {
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Returns the symbols `rule` may start with. References to other nodes
+    /// aren't followed, and are assumed not to match the empty string.
+    fn first(&self, rule: Rule) -> Vec<Symbol> {
+        match &self.grammar[rule] {
+            RuleData::Labeled { rule, .. } | RuleData::Opt(rule) | RuleData::Rep(rule) => {
+                self.first(*rule)
+            }
+            RuleData::Node(node) => vec![Symbol::Node(*node)],
+            RuleData::Token(token) => vec![Symbol::Token(*token)],
+            RuleData::Alt(rules) => rules.iter().flat_map(|&it| self.first(it)).collect(),
+            RuleData::Seq(rules) => {
+                let mut res = Vec::new();
+                for &rule in rules {
+                    res.extend(self.first(rule));
+                    if !self.nullable(rule) {
+                        break;
+                    }
+                }
+                res
+            }
+        }
+    }
+
+    fn nullable(&self, rule: Rule) -> bool {
+        match &self.grammar[rule] {
+            RuleData::Labeled { rule, .. } => self.nullable(*rule),
+            RuleData::Node(_) | RuleData::Token(_) => false,
+            RuleData::Seq(rules) => rules.iter().all(|&it| self.nullable(it)),
+            RuleData::Alt(rules) => rules.iter().any(|&it| self.nullable(it)),
+            RuleData::Opt(_) | RuleData::Rep(_) => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Symbol {
+    Node(Node),
+    Token(Token),
+}
+
+/// A JavaScript expression, laid out on one line if it fits.
+enum Js {
+    Atom(String),
+    Call(String, Vec<Js>),
+}
+
+const LINE_WIDTH: usize = 80;
+
+impl Js {
+    fn flat(&self) -> String {
+        match self {
+            Js::Atom(it) => it.clone(),
+            Js::Call(name, args) => {
+                let args = args.iter().map(Js::flat).collect::<Vec<_>>();
+                format!("{name}({})", args.join(", "))
+            }
+        }
+    }
+
+    /// Writes the expression starting at `column`, breaking the arguments of
+    /// calls which don't fit on separate lines indented by `indent`.
+    fn write(&self, buf: &mut String, column: usize, indent: usize) {
+        let flat = self.flat();
+        match self {
+            Js::Call(name, args) if column + flat.len() > LINE_WIDTH => {
+                writeln!(buf, "{name}(").unwrap();
+                for arg in args {
+                    buf.push_str(&" ".repeat(indent + 2));
+                    arg.write(buf, indent + 2, indent + 2);
+                    buf.push_str(",\n");
+                }
+                buf.push_str(&" ".repeat(indent));
+                buf.push(')');
+            }
+            _ => buf.push_str(&flat),
+        }
+    }
+}
+
+fn js_string(text: &str) -> String {
+    let mut res = String::from("'");
+    for c in text.chars() {
+        match c {
+            '\'' => res.push_str("\\'"),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c => res.push(c),
+        }
+    }
+    res.push('\'');
+    res
+}
@@ -0,0 +1,117 @@
+use expect_test::{expect, Expect};
+
+use crate::{lower, tree_sitter::to_grammar_js};
+
+fn check(ungrammar: &str, expect: Expect) {
+    let grammar = lower(ungrammar);
+    expect.assert_eq(&to_grammar_js(&grammar, "test"));
+}
+
+#[test]
+fn rules() {
+    check(
+        r#"
+SourceFile = Item*
+Item = Fn | Struct
+Fn = 'fn' name:Name ParamList ('->' ret:Type)? body:Block
+ParamList = '(' (Param (',' Param)* ','?)? ')'
+Struct = 'struct' name:Name '{' '}'
+Name = 'ident'
+"#,
+        expect![[r#"
+            module.exports = grammar({
+              name: 'test',
+
+              extras: $ => [/\s/],
+
+              word: $ => $.ident,
+
+              supertypes: $ => [
+                $._item,
+              ],
+
+              rules: {
+                source_file: $ => repeat($._item),
+
+                _item: $ => choice($.fn, $.struct),
+
+                fn: $ => seq(
+                  'fn',
+                  field('name', $.name),
+                  $.param_list,
+                  optional(seq('->', field('ret', $.type))),
+                  field('body', $.block),
+                ),
+
+                param_list: $ => seq(
+                  '(',
+                  optional(seq($.param, repeat(seq(',', $.param)), optional(','))),
+                  ')',
+                ),
+
+                struct: $ => seq('struct', field('name', $.name), '{', '}'),
+
+                name: $ => $.ident,
+
+                // TODO: `Type` is referenced but not defined.
+                type: $ => blank(),
+
+                // TODO: `Block` is referenced but not defined.
+                block: $ => blank(),
+
+                // TODO: `Param` is referenced but not defined.
+                param: $ => blank(),
+
+                // TODO: the pattern of `ident` tokens.
+                ident: $ => /TODO/,
+              },
+            });
+        "#]],
+    );
+}
+
+#[test]
+fn precedence_todos() {
+    check(
+        r#"
+Expr = BinExpr | Literal | CallExpr
+BinExpr = lhs:Expr op:('+' | '*') rhs:Expr
+CallExpr = Expr '(' ')'
+Literal = 'int_number' | 'string' | 'int_number' '\''
+"#,
+        expect![[r#"
+            module.exports = grammar({
+              name: 'test',
+
+              extras: $ => [/\s/],
+
+              supertypes: $ => [
+                $._expr,
+              ],
+
+              rules: {
+                _expr: $ => choice($.bin_expr, $.literal, $.call_expr),
+
+                // TODO: precedence, `BinExpr` starts with itself, use prec.left or prec.right.
+                bin_expr: $ => seq(
+                  field('lhs', $._expr),
+                  field('op', choice('+', '*')),
+                  field('rhs', $._expr),
+                ),
+
+                // TODO: precedence, `CallExpr` starts with itself, use prec.left or prec.right.
+                call_expr: $ => seq($._expr, '(', ')'),
+
+                // TODO: precedence, several alternatives of `Literal` start with `'int_number'`.
+                literal: $ => choice($.int_number, $.string, seq($.int_number, '\'')),
+
+                // TODO: the pattern of `int_number` tokens.
+                int_number: $ => /TODO/,
+
+                // TODO: the pattern of `string` tokens.
+                string: $ => /TODO/,
+              },
+            });
+        "#]],
+    );
+}
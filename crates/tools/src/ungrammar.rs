@@ -0,0 +1,103 @@
+//! Prints rules in Ungrammar notation, for the tools which show them to
+//! people, like the reference docs, or which produce Ungrammar, like the
+//! importers.
+//!
+//! The rules of a [`hir::Grammar`] and the ones built by the importers have
+//! different representations, both describe their [`Shape`] to the printer.
+
+use hir::{Grammar, Rule, RuleData};
+
+/// Productions longer than this put each alternative on a line of its own.
+const LINE_WIDTH: usize = 80;
+
+/// How tightly a rule binds, a rule needs parentheses in a context which
+/// binds tighter. A label applies to a whole postfix rule, `label:Expr*`,
+/// so it can't be followed by a postfix operator itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Prec {
+    Alt,
+    Seq,
+    Labeled,
+    Postfix,
+    Atom,
+}
+
+/// A rule, with its parts as rules of the same representation.
+pub(crate) enum Shape<'a, R> {
+    Labeled(&'a str, R),
+    Node(&'a str),
+    /// The unescaped text of the token.
+    Token(&'a str),
+    Seq(Vec<R>),
+    Alt(Vec<R>),
+    Opt(R),
+    Rep(R),
+}
+
+pub(crate) trait ToShape: Sized {
+    fn shape(&self) -> Shape<'_, Self>;
+}
+
+/// A rule of a [`hir::Grammar`].
+#[derive(Clone, Copy)]
+pub(crate) struct GrammarRule<'a> {
+    pub(crate) grammar: &'a Grammar,
+    pub(crate) rule: Rule,
+}
+
+impl ToShape for GrammarRule<'_> {
+    fn shape(&self) -> Shape<'_, Self> {
+        let grammar = self.grammar;
+        let rules = |rules: &[Rule]| {
+            rules.iter().map(|&rule| GrammarRule { grammar, rule }).collect::<Vec<_>>()
+        };
+        match &grammar[self.rule] {
+            RuleData::Labeled { label, rule } => {
+                Shape::Labeled(label, GrammarRule { grammar, rule: *rule })
+            }
+            RuleData::Node(node) => Shape::Node(&grammar[*node].name),
+            RuleData::Token(token) => Shape::Token(&grammar[*token].name),
+            RuleData::Seq(it) => Shape::Seq(rules(it)),
+            RuleData::Alt(it) => Shape::Alt(rules(it)),
+            RuleData::Opt(rule) => Shape::Opt(GrammarRule { grammar, rule: *rule }),
+            RuleData::Rep(rule) => Shape::Rep(GrammarRule { grammar, rule: *rule }),
+        }
+    }
+}
+
+/// Returns `Name = rule`, with alternatives which don't fit on one line put
+/// on lines of their own.
+pub(crate) fn production_source<R: ToShape>(name: &str, rule: &R) -> String {
+    let line = format!("{name} = {}", rule_source(rule, Prec::Alt));
+    match rule.shape() {
+        Shape::Alt(alts) if line.len() > LINE_WIDTH => {
+            let alts = alts.iter().map(|alt| rule_source(alt, Prec::Seq)).collect::<Vec<_>>();
+            format!("{name} =\n  {}", alts.join("\n| "))
+        }
+        _ => line,
+    }
+}
+
+fn rule_source<R: ToShape>(rule: &R, min_prec: Prec) -> String {
+    let list = |rules: &[R], sep: &str, min_prec: Prec| {
+        rules.iter().map(|it| rule_source(it, min_prec)).collect::<Vec<_>>().join(sep)
+    };
+    let (prec, res) = match rule.shape() {
+        Shape::Labeled(label, rule) => {
+            (Prec::Labeled, format!("{label}:{}", rule_source(&rule, Prec::Postfix)))
+        }
+        Shape::Node(name) => (Prec::Atom, name.to_owned()),
+        Shape::Token(text) => {
+            (Prec::Atom, format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'")))
+        }
+        Shape::Seq(rules) => (Prec::Seq, list(&rules, " ", Prec::Labeled)),
+        Shape::Alt(rules) => (Prec::Alt, list(&rules, " | ", Prec::Seq)),
+        Shape::Opt(rule) => (Prec::Postfix, format!("{}?", rule_source(&rule, Prec::Postfix))),
+        Shape::Rep(rule) => (Prec::Postfix, format!("{}*", rule_source(&rule, Prec::Postfix))),
+    };
+    if prec < min_prec {
+        format!("({res})")
+    } else {
+        res
+    }
+}
@@ -0,0 +1,329 @@
+//! Driver for ungrammar-analyzer.
+//!
+//! Based on cli flags, either spawns an LSP server, runs a subcommand, or
+//! prints usage information.
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+};
+
+use ide::{Edition, LineEndings};
+use lsp_server::Connection;
+use syntax::{ast, ParseConfig};
+use tools::{docs::DocsFormat, sentences::SentenceConfig};
+use ungrammar_analyzer::{
+    cli::{self, ExportConfig, ExportFormat},
+    Config, Result,
+};
+
+const USAGE: &str = "\
+ungrammar-analyzer
+
+USAGE:
+    ungrammar-analyzer [PARSE_FLAGS] [FLAGS]
+    ungrammar-analyzer [PARSE_FLAGS] --stats <FILE>...
+    ungrammar-analyzer [PARSE_FLAGS] codegen <FILE> <OUT_DIR>
+    ungrammar-analyzer [PARSE_FLAGS] kinds <FILE>
+    ungrammar-analyzer [PARSE_FLAGS] railroad <FILE> <OUT_DIR>
+    ungrammar-analyzer [PARSE_FLAGS] docs [--html] <FILE> <OUT_DIR>
+    ungrammar-analyzer import [--format <FORMAT>] <FILE>
+    ungrammar-analyzer [PARSE_FLAGS] generate [--seed <N>] [--count <N>]
+                           [--max-depth <N>] [--max-tokens <N>] <FILE> <NODE>
+    ungrammar-analyzer [PARSE_FLAGS] export --format <FORMAT> [--label-comments]
+                           <FILE>
+    ungrammar-analyzer [PARSE_FLAGS] export --format dot [--focus <NODE>]
+                           [--depth <N>] [--collapse-sccs] <FILE>
+
+Unless asked for help or the version, runs the language server on stdin/stdout.
+
+SUBCOMMANDS:
+    codegen     Writes the `SyntaxKind` enum and the typed AST of the grammar
+                to `syntax_kind.rs` and `nodes.rs` in the output directory
+    kinds       Prints the token and node kinds derived from the grammar, and
+                warns about the token literals which need classifying by hand
+    railroad    Writes a railroad diagram of each node to `Name.svg` in the
+                output directory, and a page showing all of them to
+                `index.html`
+    docs        Writes the reference documentation of the grammar to the
+                output directory, a Markdown page per node and `index.md`, or
+                HTML pages with `--html`
+    import      Prints the grammar of another tool in Ungrammar, and warns
+                about what couldn't be converted. The format, one of
+                `w3c-ebnf`, `pest` or `lalrpop`, defaults to the one the
+                extension of the file stands for (`.ebnf`, `.pest` or
+                `.lalrpop`)
+    generate    Prints random sentences of a node, one per line, as the texts
+                of their tokens separated by spaces. The same `--seed` (0 by
+                default) gives the same sentences. Sentences stay within
+                `--max-tokens` tokens (256 by default) and `--max-depth`
+                nested nodes (16 by default) when the grammar allows it.
+                `--count` sentences are printed, 1 by default
+    export      Prints the grammar in another notation, one of `w3c-ebnf`,
+                `iso-ebnf`, `json`, `tree-sitter` or `dot`. With
+                `--label-comments`, labels are kept as comments rather than
+                dropped. The JSON document also contains the syntax trees and
+                errors of the files. The `dot` graph of the references between
+                nodes can be restricted to the nodes within `--depth`
+                references (1 by default) of the `--focus` node, and with
+                `--collapse-sccs`, each cycle is drawn as a single vertex
+
+FLAGS:
+    -h, --help          Prints help information
+    -V, --version       Prints version information
+        --stats         Parses the files and prints the largest values
+                        reached by the parser limits, instead of running
+                        the language server. Needs a build with the `stats`
+                        feature
+
+PARSE_FLAGS:
+        --allow-crlf    Accepts `\\r\\n` line endings, unless the client's
+                        `allowCrlf` setting says otherwise
+        --edition <N>   Parses the files without an `// edition: N` comment
+                        with edition N rather than the latest one, unless the
+                        client's `edition` setting says otherwise
+";
+
+#[allow(clippy::print_stderr)]
+fn main() {
+    if let Err(err) = try_main() {
+        eprintln!("{err}");
+        process::exit(101);
+    }
+}
+
+#[allow(clippy::print_stdout)]
+fn try_main() -> Result<()> {
+    let mut parse_config = ParseConfig::default();
+    let mut args = env::args().skip(1).peekable();
+    // How to parse the files applies to the server and the subcommands alike.
+    loop {
+        match args.peek().map(String::as_str) {
+            Some("--allow-crlf") => parse_config.line_endings = LineEndings::Crlf,
+            Some("--edition") => {
+                args.next();
+                let value = args.next().ok_or("`--edition` expects an edition")?;
+                parse_config.default_edition =
+                    value.parse::<Edition>().map_err(|err| err.to_string())?;
+                continue;
+            }
+            _ => break,
+        }
+        args.next();
+    }
+    match args.peek().map(String::as_str) {
+        Some("codegen") => {
+            args.next();
+            return match (args.next(), args.next(), args.next()) {
+                (Some(path), Some(out_dir), None) => {
+                    cli::codegen(Path::new(&path), Path::new(&out_dir), parse_config)
+                }
+                _ => {
+                    Err(format!("`codegen` expects a file and an output directory\n\n{USAGE}")
+                        .into())
+                }
+            };
+        }
+        Some("railroad") => {
+            args.next();
+            return match (args.next(), args.next(), args.next()) {
+                (Some(path), Some(out_dir), None) => {
+                    cli::railroad(Path::new(&path), Path::new(&out_dir), parse_config)
+                }
+                _ => {
+                    Err(format!("`railroad` expects a file and an output directory\n\n{USAGE}")
+                        .into())
+                }
+            };
+        }
+        Some("docs") => {
+            args.next();
+            let mut args = args.collect::<Vec<_>>();
+            let html = args.iter().position(|it| it == "--html").map(|i| args.remove(i));
+            let format = if html.is_some() { DocsFormat::Html } else { DocsFormat::Markdown };
+            return match &args[..] {
+                [path, out_dir] => {
+                    cli::docs(Path::new(path), Path::new(out_dir), format, parse_config)
+                }
+                _ => {
+                    Err(format!("`docs` expects a file and an output directory\n\n{USAGE}").into())
+                }
+            };
+        }
+        Some("kinds") => {
+            args.next();
+            return match (args.next(), args.next()) {
+                (Some(path), None) => cli::kinds(Path::new(&path), parse_config),
+                _ => Err(format!("`kinds` expects a file\n\n{USAGE}").into()),
+            };
+        }
+        Some("import") => {
+            args.next();
+            return match (args.next(), args.next(), args.next(), args.next()) {
+                (Some(flag), Some(format), Some(path), None) if flag == "--format" => {
+                    cli::import(Path::new(&path), Some(cli::import_format(&format)?))
+                }
+                (Some(path), None, None, None) if !path.starts_with('-') => {
+                    cli::import(Path::new(&path), None)
+                }
+                _ => Err(format!("`import` expects a file\n\n{USAGE}").into()),
+            };
+        }
+        Some("export") => {
+            args.next();
+            return export(args, parse_config);
+        }
+        Some("generate") => {
+            args.next();
+            return generate(args, parse_config);
+        }
+        _ => (),
+    }
+    if let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-V" | "--version" => {
+                println!("ungrammar-analyzer {}", env!("CARGO_PKG_VERSION"));
+                return Ok(());
+            }
+            "-h" | "--help" => {
+                print!("{USAGE}");
+                return Ok(());
+            }
+            "--stats" => return print_stats(&args.collect::<Vec<_>>(), parse_config),
+            flag => return Err(format!("unknown flag: `{flag}`\n\n{USAGE}").into()),
+        }
+    }
+    run_server(parse_config)
+}
+
+fn export(mut args: impl Iterator<Item = String>, parse_config: ParseConfig) -> Result<()> {
+    let mut format = None;
+    let mut label_comments = false;
+    let mut focus = None;
+    let mut depth = None;
+    let mut collapse_sccs = false;
+    let mut path = None;
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args.next().ok_or("`--format` expects a format")?;
+                format = Some(value.parse::<ExportFormat>()?);
+            }
+            "--label-comments" => label_comments = true,
+            "--focus" => focus = Some(args.next().ok_or("`--focus` expects a node")?),
+            "--depth" => {
+                let value = args.next().ok_or("`--depth` expects a number")?;
+                depth = Some(value.parse().map_err(|_| format!("invalid depth: `{value}`"))?);
+            }
+            "--collapse-sccs" => collapse_sccs = true,
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            arg => return Err(format!("unexpected argument: `{arg}`\n\n{USAGE}").into()),
+        }
+    }
+    let (Some(format), Some(path)) = (format, path) else {
+        return Err(format!("`export` expects a format and a file\n\n{USAGE}").into());
+    };
+    let config = ExportConfig { format, label_comments, focus, depth, collapse_sccs };
+    cli::export(Path::new(&path), &config, parse_config)
+}
+
+fn generate(mut args: impl Iterator<Item = String>, parse_config: ParseConfig) -> Result<()> {
+    let mut config = SentenceConfig::default();
+    let mut count = 1;
+    let mut positional = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seed" => config.seed = number(&arg, args.next())?,
+            "--count" => count = number(&arg, args.next())?,
+            "--max-depth" => config.max_depth = number(&arg, args.next())?,
+            "--max-tokens" => config.max_tokens = number(&arg, args.next())?,
+            _ if !arg.starts_with('-') => positional.push(arg),
+            arg => return Err(format!("unexpected argument: `{arg}`\n\n{USAGE}").into()),
+        }
+    }
+    let [path, root] = &positional[..] else {
+        return Err(format!("`generate` expects a file and a node\n\n{USAGE}").into());
+    };
+    cli::generate(Path::new(path), root, count, config, parse_config)
+}
+
+/// Parses the value of `flag`.
+fn number<T: FromStr>(flag: &str, value: Option<String>) -> Result<T> {
+    let value = value.ok_or_else(|| format!("`{flag}` expects a number"))?;
+    Ok(value.parse().map_err(|_| format!("invalid number for `{flag}`: `{value}`"))?)
+}
+
+/// Prints a line per file, like `grammar.ungram: steps 4/15000000, depth
+/// 2/256`.
+#[allow(clippy::print_stdout)]
+fn print_stats(paths: &[String], config: ParseConfig) -> Result<()> {
+    if !cfg!(feature = "stats") {
+        let message = "`--stats` needs a build with the `stats` feature: \
+                       `cargo build --features stats`";
+        return Err(message.into());
+    }
+    if paths.is_empty() {
+        return Err(format!("`--stats` expects files\n\n{USAGE}").into());
+    }
+    for path in paths {
+        let text = fs::read_to_string(path).map_err(|err| format!("can't read `{path}`: {err}"))?;
+        let (_, limit_stats) = ast::Grammar::parse_with_stats(&text, config);
+        let limit_stats = limit_stats
+            .iter()
+            .map(|it| match it.max {
+                Some(max) => format!("{} {max}/{}", it.name, it.upper_bound),
+                None => format!("{} ?/{}", it.name, it.upper_bound),
+            })
+            .collect::<Vec<_>>();
+        println!("{path}: {}", limit_stats.join(", "));
+    }
+    Ok(())
+}
+
+fn run_server(parse_config: ParseConfig) -> Result<()> {
+    tracing::info!("server version {} will start", env!("CARGO_PKG_VERSION"));
+
+    let (connection, io_threads) = Connection::stdio();
+
+    let (initialize_id, initialize_params) = connection.initialize_start()?;
+    tracing::info!("InitializeParams: {}", initialize_params);
+    let initialize_params =
+        serde_json::from_value::<lsp_types::InitializeParams>(initialize_params)?;
+
+    let workspace_roots = workspace_roots(&initialize_params);
+    let mut config = Config::new(initialize_params.capabilities, workspace_roots);
+    config.set_default_line_endings(parse_config.line_endings);
+    config.set_default_edition(parse_config.default_edition);
+    if let Some(json) = initialize_params.initialization_options {
+        config.update(json)?;
+    }
+
+    let initialize_result = lsp_types::InitializeResult {
+        capabilities: ungrammar_analyzer::server_capabilities(&config),
+        server_info: Some(lsp_types::ServerInfo {
+            name: String::from("ungrammar-analyzer"),
+            version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+        }),
+    };
+    connection.initialize_finish(initialize_id, serde_json::to_value(initialize_result)?)?;
+
+    ungrammar_analyzer::main_loop(config, connection)?;
+
+    io_threads.join()?;
+    tracing::info!("server did shut down");
+    Ok(())
+}
+
+/// Returns the paths of the workspace folders, falling back to the root of
+/// the workspace for clients which don't support folders.
+fn workspace_roots(params: &lsp_types::InitializeParams) -> Vec<PathBuf> {
+    #[allow(deprecated)]
+    let root_uri = params.root_uri.as_ref();
+    let urls = match &params.workspace_folders {
+        Some(folders) => folders.iter().map(|it| &it.uri).collect(),
+        None => root_uri.into_iter().collect::<Vec<_>>(),
+    };
+    urls.into_iter().filter_map(|url| url.to_file_path().ok()).collect()
+}
@@ -0,0 +1,365 @@
+//! Subcommands of the command line which run the grammar tools on files,
+//! rather than serving an editor.
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+
+use hir::Grammar;
+use ide::{Analysis, AnalysisHost, Change, FileId, FileSet};
+use rustc_hash::FxHashMap;
+use syntax::{ast, AstNode, ParseConfig};
+use tools::{
+    codegen::{derive_kinds, generate_ast, generate_syntax_kinds, render_kinds_src, KindsSrc},
+    docs::{self, DocsFormat},
+    dot::{to_dot, DotConfig},
+    ebnf::{to_ebnf, EbnfConfig, Flavor},
+    import::ImportFormat,
+    json, railroad,
+    sentences::{SentenceConfig, SentenceGenerator},
+    tree_sitter,
+};
+use vfs::VfsPath;
+
+use crate::Result;
+
+/// Writes the `SyntaxKind` enum and the typed AST of the grammar at `path`
+/// to `syntax_kind.rs` and `nodes.rs` in `out_dir`.
+pub fn codegen(path: &Path, out_dir: &Path, parse_config: ParseConfig) -> Result<()> {
+    let grammar = load_grammar(path, parse_config)?;
+    let kinds = KindsSrc::from_grammar(&grammar);
+    fs::create_dir_all(out_dir)?;
+    write(&out_dir.join("syntax_kind.rs"), &generate_syntax_kinds(&kinds))?;
+    write(&out_dir.join("nodes.rs"), &generate_ast(&grammar, &kinds))?;
+    Ok(())
+}
+
+/// Writes the railroad diagram of each node of the grammar at `path` to
+/// `Name.svg` in `out_dir`, and a page showing all of them to `index.html`.
+pub fn railroad(path: &Path, out_dir: &Path, parse_config: ParseConfig) -> Result<()> {
+    let grammar = load_grammar(path, parse_config)?;
+    fs::create_dir_all(out_dir)?;
+    for node in grammar.iter() {
+        let Some(svg) = railroad::node_diagram(&grammar, node, "index.html") else { continue };
+        write(&out_dir.join(format!("{}.svg", grammar[node].name)), &svg)?;
+    }
+    let title = path.file_stem().unwrap_or_default().to_string_lossy();
+    write(&out_dir.join("index.html"), &railroad::to_html(&grammar, &title))
+}
+
+/// Writes the reference documentation of the grammar at `path` to `out_dir`,
+/// a page per node and an index.
+pub fn docs(
+    path: &Path,
+    out_dir: &Path,
+    format: DocsFormat,
+    parse_config: ParseConfig,
+) -> Result<()> {
+    let file = load_syntax(path, parse_config)?;
+    let title = path.file_stem().unwrap_or_default().to_string_lossy();
+    fs::create_dir_all(out_dir)?;
+    for (name, contents) in docs::reference(&file, &title, format) {
+        write(&out_dir.join(name), &contents)?;
+    }
+    Ok(())
+}
+
+/// Prints the kinds derived from the grammar at `path`, in the shape of a
+/// hand-written `KindsSrc`, followed by the literals and nodes whose kinds
+/// need a decision by hand.
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub fn kinds(path: &Path, parse_config: ParseConfig) -> Result<()> {
+    let grammar = load_grammar(path, parse_config)?;
+    let (kinds, issues) = derive_kinds(&grammar);
+    print!("{}", render_kinds_src(&kinds));
+    for issue in issues {
+        eprintln!("warning: {}: {}", issue.subject, issue.message);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    W3cEbnf,
+    IsoEbnf,
+    /// The grammar model and the syntax trees of its files, see
+    /// [`tools::json`].
+    Json,
+    /// A skeleton of a tree-sitter `grammar.js`.
+    TreeSitter,
+    /// The graph of the references between nodes, for Graphviz.
+    Dot,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<ExportFormat, String> {
+        let format = match s {
+            "w3c-ebnf" => ExportFormat::W3cEbnf,
+            "iso-ebnf" => ExportFormat::IsoEbnf,
+            "json" => ExportFormat::Json,
+            "tree-sitter" => ExportFormat::TreeSitter,
+            "dot" => ExportFormat::Dot,
+            _ => return Err(format!("unknown export format: `{s}`")),
+        };
+        Ok(format)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportConfig {
+    pub format: ExportFormat,
+    /// Keep labels as comments in formats which can't express them.
+    pub label_comments: bool,
+    /// Restrict the graph to the nodes within `depth` references of the node
+    /// with this name.
+    pub focus: Option<String>,
+    pub depth: Option<usize>,
+    /// Collapse the cycles of the graph into single vertices.
+    pub collapse_sccs: bool,
+}
+
+/// Prints the grammar at `path` in another notation.
+#[allow(clippy::print_stdout)]
+pub fn export(path: &Path, config: &ExportConfig, parse_config: ParseConfig) -> Result<()> {
+    let flavor = match config.format {
+        ExportFormat::W3cEbnf => Flavor::W3c,
+        ExportFormat::IsoEbnf => Flavor::Iso,
+        ExportFormat::Json => {
+            // The syntax errors are part of the document.
+            let files = load_files(path, parse_config)?;
+            let file = ast::Grammar::parse_with_config(&files.visible_defs(), parse_config).tree();
+            let grammar = Grammar::lower(&file).0;
+            let parses = files
+                .paths
+                .iter()
+                .enumerate()
+                .map(|(idx, path)| {
+                    let text = files.analysis.file_text(FileId(idx as u32));
+                    (
+                        path.display().to_string(),
+                        ast::Grammar::parse_with_config(&text, parse_config),
+                    )
+                })
+                .collect::<Vec<_>>();
+            let parses = parses.iter().map(|(path, parse)| (path.as_str(), parse));
+            let document = json::document(&grammar, &parses.collect::<Vec<_>>());
+            println!("{}", serde_json::to_string_pretty(&document)?);
+            return Ok(());
+        }
+        ExportFormat::TreeSitter => {
+            let grammar = load_grammar(path, parse_config)?;
+            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+            let name = name.replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+            print!("{}", tree_sitter::to_grammar_js(&grammar, &name));
+            return Ok(());
+        }
+        ExportFormat::Dot => {
+            let grammar = load_grammar(path, parse_config)?;
+            let mut dot_config =
+                DotConfig { collapse_sccs: config.collapse_sccs, ..Default::default() };
+            if let Some(name) = &config.focus {
+                let node =
+                    grammar.find_node(name).ok_or_else(|| format!("unknown node: `{name}`"))?;
+                dot_config.focus = Some(node);
+            }
+            if let Some(depth) = config.depth {
+                dot_config.depth = depth;
+            }
+            print!("{}", to_dot(&grammar, &dot_config));
+            return Ok(());
+        }
+    };
+    let grammar = load_grammar(path, parse_config)?;
+    print!("{}", to_ebnf(&grammar, &EbnfConfig { flavor, label_comments: config.label_comments }));
+    Ok(())
+}
+
+/// Prints `count` random sentences of the node `root` of the grammar at
+/// `path`, one per line, their tokens separated by spaces.
+#[allow(clippy::print_stdout)]
+pub fn generate(
+    path: &Path,
+    root: &str,
+    count: usize,
+    config: SentenceConfig,
+    parse_config: ParseConfig,
+) -> Result<()> {
+    let grammar = load_grammar(path, parse_config)?;
+    let root = grammar.find_node(root).ok_or_else(|| format!("unknown node: `{root}`"))?;
+    let mut gen = SentenceGenerator::new(&grammar, config);
+    for _ in 0..count {
+        println!("{}", gen.sentence(root).join(" "));
+    }
+    Ok(())
+}
+
+/// Returns the import format named `name`, as on the command line.
+pub fn import_format(name: &str) -> Result<ImportFormat> {
+    let format = match name {
+        "w3c-ebnf" => ImportFormat::W3cEbnf,
+        "pest" => ImportFormat::Pest,
+        "lalrpop" => ImportFormat::Lalrpop,
+        _ => return Err(format!("unknown import format: `{name}`").into()),
+    };
+    Ok(format)
+}
+
+/// Prints the Ungrammar version of the grammar at `path`, written in
+/// `format` or else the format its extension stands for, and warns about
+/// what couldn't be imported.
+#[allow(clippy::print_stdout, clippy::print_stderr)]
+pub fn import(path: &Path, format: Option<ImportFormat>) -> Result<()> {
+    let format = match format {
+        Some(format) => format,
+        None => match path.extension().and_then(|it| it.to_str()) {
+            Some("ebnf") => ImportFormat::W3cEbnf,
+            Some("pest") => ImportFormat::Pest,
+            Some("lalrpop") => ImportFormat::Lalrpop,
+            _ => return Err(format!("can't tell the format of `{}`", path.display()).into()),
+        },
+    };
+    let text = fs::read_to_string(path)
+        .map_err(|err| format!("can't read `{}`: {err}", path.display()))?;
+    let import = tools::import::import(&text, format);
+    print!("{}", import.text);
+    for diagnostic in import.diagnostics {
+        let offset = u32::from(diagnostic.range.start()) as usize;
+        let line = text[..offset].matches('\n').count() + 1;
+        eprintln!("{}:{line}: warning: {}", path.display(), diagnostic.message);
+    }
+    Ok(())
+}
+
+/// Lowers the grammar at `path` together with the files it includes,
+/// directly or through other includes. Syntax errors are fatal, a tool
+/// would produce confusing output for the half-parsed grammar.
+pub fn load_grammar(path: &Path, parse_config: ParseConfig) -> Result<Grammar> {
+    Ok(Grammar::lower(&load_syntax(path, parse_config)?).0)
+}
+
+/// Like [`load_grammar`], but returns the syntax tree of all the files, for
+/// tools which need more than the model, like doc comments.
+pub fn load_syntax(path: &Path, parse_config: ParseConfig) -> Result<ast::Grammar> {
+    let files = load_files(path, parse_config)?;
+    for (idx, path) in files.paths.iter().enumerate() {
+        let file_id = FileId(idx as u32);
+        if let Some(err) = files.analysis.syntax_errors(file_id).first() {
+            let text = files.analysis.file_text(file_id);
+            let offset = u32::from(err.range().start()) as usize;
+            let line = text[..offset].matches('\n').count() + 1;
+            return Err(format!("{}:{line}: {err}", path.display()).into());
+        }
+    }
+    Ok(ast::Grammar::parse_with_config(&files.visible_defs(), parse_config).tree())
+}
+
+/// The file at `path` and the files it includes, loaded into an analysis like
+/// the ones of the server, so that names resolve the same way as in the
+/// editor. The file at `paths[i]` has the id `FileId(i)`, the root is the
+/// first one.
+struct Files {
+    analysis: Analysis,
+    paths: Vec<PathBuf>,
+}
+
+impl Files {
+    /// Returns the text of the definitions which the names visible from the
+    /// root resolve to, each parsed with the edition of its own file. A tool
+    /// sees a single definition per name, so the ones which an included file
+    /// would see instead are reported.
+    #[allow(clippy::print_stderr)]
+    fn visible_defs(&self) -> String {
+        let mut res = String::new();
+        for node in self.analysis.visible_nodes(FileId(0)) {
+            let path = &self.paths[node.file_id.0 as usize];
+            let name = node.def.name().map(|it| it.text()).unwrap_or_default();
+            for shadowed in node.shadowed {
+                let shadowed = &self.paths[shadowed.0 as usize];
+                eprintln!(
+                    "warning: `{name}` is defined in `{}` and `{}`, using the definition of `{}`",
+                    path.display(),
+                    shadowed.display(),
+                    path.display()
+                );
+            }
+            writeln!(res, "{}", node.def.syntax()).unwrap();
+        }
+        res
+    }
+}
+
+/// Reads the file at `path` and the files it includes, in the order their
+/// definitions are looked up.
+fn load_files(path: &Path, parse_config: ParseConfig) -> Result<Files> {
+    let path = normalize(&std::path::absolute(path)?);
+    let mut paths = vec![path.clone()];
+    let mut file_set = FileSet::default();
+    file_set.insert(FileId(0), VfsPath::from(path.clone()));
+    // A file may be included under different paths, through `..` or links.
+    let mut ids = FxHashMap::from_iter([(canonical(&path), FileId(0))]);
+    let mut change = Change::new();
+    let mut idx = 0;
+    while let Some(path) = paths.get(idx).cloned() {
+        let text = fs::read_to_string(&path)
+            .map_err(|err| format!("can't read `{}`: {err}", path.display()))?;
+        for include in ast::Grammar::parse_with_config(&text, parse_config).tree().includes() {
+            let Some(string) = include.string() else { continue };
+            let Some(value) = string.value() else { continue };
+            let included = normalize(&path.parent().unwrap_or(Path::new("")).join(&*value));
+            let next_id = FileId(paths.len() as u32);
+            let file_id = *ids.entry(canonical(&included)).or_insert_with(|| {
+                paths.push(included.clone());
+                next_id
+            });
+            if file_set.file_for_path(&VfsPath::from(included.clone())).is_none() {
+                file_set.insert(file_id, VfsPath::from(included));
+                // The includes of the file stay relative to the path it was
+                // read from.
+                file_set.insert(file_id, VfsPath::from(paths[file_id.0 as usize].clone()));
+            }
+        }
+        change.change_file(FileId(idx as u32), Some(Arc::from(text)));
+        idx += 1;
+    }
+    change.set_file_set(file_set);
+    change.set_default_edition(parse_config.default_edition);
+    change.set_line_endings(parse_config.line_endings);
+    let mut host = AnalysisHost::new();
+    host.apply_change(change);
+    Ok(Files { analysis: host.analysis(), paths })
+}
+
+/// Resolves `.` and `..` lexically, like the paths of the server.
+fn normalize(path: &Path) -> PathBuf {
+    let mut res = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir
+                if matches!(res.components().next_back(), Some(Component::Normal(_))) =>
+            {
+                res.pop();
+            }
+            _ => res.push(component),
+        }
+    }
+    res
+}
+
+/// Returns the path which identifies the file, whichever way it is spelled.
+/// Files which can't be read keep their normalized path, reading them fails
+/// anyway.
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| normalize(path))
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents).map_err(|err| format!("can't write `{}`: {err}", path.display()))?;
+    Ok(())
+}
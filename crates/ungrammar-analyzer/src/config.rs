@@ -0,0 +1,150 @@
+//! Config used by the language server.
+//!
+//! The settings come from the `initializationOptions` of the `initialize`
+//! request and use the same shape as the VS Code settings without the
+//! `ungrammar-analyzer.` prefix, e.g. `{ "inlayHints": { "fieldNames": {
+//! "enable": false } } }`.
+
+use std::path::{Path, PathBuf};
+
+use ide::{AnnotationConfig, DiagnosticsConfig, Edition, InlayHintsConfig, LineEndings};
+use lsp_types::ClientCapabilities;
+use serde::{Deserialize, Deserializer};
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    caps: ClientCapabilities,
+    /// The directories whose `.ungram` files make up the workspace.
+    workspace_roots: Vec<PathBuf>,
+    /// The line endings and the edition selected on the command line, which
+    /// the settings override.
+    default_line_endings: LineEndings,
+    default_edition: Edition,
+    data: ConfigData,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct ConfigData {
+    /// The edition of the files without an `// edition: N` comment, e.g.
+    /// `"1"`. Defaults to the `--edition` command line flag, or else the
+    /// latest edition.
+    #[serde(deserialize_with = "deserialize_edition")]
+    edition: Option<Edition>,
+    /// Whether `\r\n` line endings are accepted. Defaults to the
+    /// `--allow-crlf` command line flag.
+    allow_crlf: Option<bool>,
+    inlay_hints: InlayHintsData,
+    lens: LensData,
+    diagnostics: DiagnosticsData,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct InlayHintsData {
+    field_names: EnableData,
+}
+
+impl Default for InlayHintsData {
+    fn default() -> Self {
+        InlayHintsData { field_names: EnableData { enable: true } }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct LensData {
+    references: EnableData,
+}
+
+impl Default for LensData {
+    fn default() -> Self {
+        LensData { references: EnableData { enable: true } }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct DiagnosticsData {
+    disabled: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EnableData {
+    enable: bool,
+}
+
+fn deserialize_edition<'de, D: Deserializer<'de>>(d: D) -> Result<Option<Edition>, D::Error> {
+    let Some(edition) = Option::<String>::deserialize(d)? else { return Ok(None) };
+    edition.parse().map(Some).map_err(serde::de::Error::custom)
+}
+
+impl Config {
+    pub fn new(caps: ClientCapabilities, workspace_roots: Vec<PathBuf>) -> Config {
+        Config {
+            caps,
+            workspace_roots,
+            default_line_endings: LineEndings::Unix,
+            default_edition: Edition::CURRENT,
+            data: ConfigData::default(),
+        }
+    }
+
+    pub fn set_default_line_endings(&mut self, line_endings: LineEndings) {
+        self.default_line_endings = line_endings;
+    }
+
+    pub fn set_default_edition(&mut self, edition: Edition) {
+        self.default_edition = edition;
+    }
+
+    /// Updates the settings from the JSON sent by the client.
+    pub fn update(&mut self, json: serde_json::Value) -> Result<(), serde_json::Error> {
+        self.data = serde_json::from_value(json)?;
+        Ok(())
+    }
+
+    pub fn caps(&self) -> &ClientCapabilities {
+        &self.caps
+    }
+
+    pub fn workspace_roots(&self) -> &[PathBuf] {
+        &self.workspace_roots
+    }
+
+    /// Returns `true` if `path` is an ungrammar file inside the workspace.
+    pub fn is_in_workspace(&self, path: &Path) -> bool {
+        vfs::loader::is_ungrammar_file(path)
+            && self.workspace_roots.iter().any(|root| path.starts_with(root))
+    }
+
+    pub fn did_change_watched_files_dynamic_registration(&self) -> bool {
+        (|| self.caps.workspace.as_ref()?.did_change_watched_files?.dynamic_registration)()
+            .unwrap_or(false)
+    }
+
+    pub fn edition(&self) -> Edition {
+        self.data.edition.unwrap_or(self.default_edition)
+    }
+
+    pub fn line_endings(&self) -> LineEndings {
+        match self.data.allow_crlf {
+            Some(true) => LineEndings::Crlf,
+            Some(false) => LineEndings::Unix,
+            None => self.default_line_endings,
+        }
+    }
+
+    pub fn inlay_hints(&self) -> InlayHintsConfig {
+        InlayHintsConfig { field_names: self.data.inlay_hints.field_names.enable }
+    }
+
+    pub fn annotations(&self) -> AnnotationConfig {
+        AnnotationConfig { annotate_references: self.data.lens.references.enable }
+    }
+
+    pub fn diagnostics(&self) -> DiagnosticsConfig {
+        DiagnosticsConfig { disabled: self.data.diagnostics.disabled.iter().cloned().collect() }
+    }
+}
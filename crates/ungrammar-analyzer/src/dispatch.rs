@@ -0,0 +1,113 @@
+//! See [RequestDispatcher].
+
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::global_state::{GlobalState, GlobalStateSnapshot};
+
+/// A visitor for routing a raw JSON request to an appropriate handler.
+///
+/// Requests are answered synchronously from a snapshot of the state: the
+/// analyses are cheap enough for a single grammar file.
+pub(crate) struct RequestDispatcher<'a> {
+    pub(crate) req: Option<lsp_server::Request>,
+    pub(crate) global_state: &'a mut GlobalState,
+}
+
+impl RequestDispatcher<'_> {
+    pub(crate) fn on<R>(
+        &mut self,
+        f: fn(GlobalStateSnapshot, R::Params) -> crate::Result<R::Result>,
+    ) -> &mut Self
+    where
+        R: lsp_types::request::Request,
+        R::Params: DeserializeOwned,
+        R::Result: Serialize,
+    {
+        let Some((id, params)) = self.parse::<R>() else { return self };
+        let _p = tracing::span!(tracing::Level::INFO, "request", method = R::METHOD).entered();
+
+        let result = f(self.global_state.snapshot(), params);
+        let response = match result {
+            Ok(result) => lsp_server::Response::new_ok(id, result),
+            Err(err) => lsp_server::Response::new_err(
+                id,
+                lsp_server::ErrorCode::InternalError as i32,
+                err.to_string(),
+            ),
+        };
+        self.global_state.respond(response);
+        self
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if let Some(req) = self.req.take() {
+            tracing::error!("unknown request: {:?}", req);
+            let response = lsp_server::Response::new_err(
+                req.id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                "unknown request".to_owned(),
+            );
+            self.global_state.respond(response);
+        }
+    }
+
+    fn parse<R>(&mut self) -> Option<(lsp_server::RequestId, R::Params)>
+    where
+        R: lsp_types::request::Request,
+        R::Params: DeserializeOwned,
+    {
+        let req = match &self.req {
+            Some(req) if req.method == R::METHOD => self.req.take()?,
+            _ => return None,
+        };
+
+        match serde_json::from_value(req.params) {
+            Ok(params) => Some((req.id, params)),
+            Err(err) => {
+                let response = lsp_server::Response::new_err(
+                    req.id,
+                    lsp_server::ErrorCode::InvalidParams as i32,
+                    err.to_string(),
+                );
+                self.global_state.respond(response);
+                None
+            }
+        }
+    }
+}
+
+pub(crate) struct NotificationDispatcher<'a> {
+    pub(crate) not: Option<lsp_server::Notification>,
+    pub(crate) global_state: &'a mut GlobalState,
+}
+
+impl NotificationDispatcher<'_> {
+    pub(crate) fn on<N>(
+        &mut self,
+        f: fn(&mut GlobalState, N::Params) -> crate::Result<()>,
+    ) -> crate::Result<&mut Self>
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: DeserializeOwned,
+    {
+        let not = match self.not.take() {
+            Some(not) if not.method == N::METHOD => not,
+            not => {
+                self.not = not;
+                return Ok(self);
+            }
+        };
+        let _p = tracing::span!(tracing::Level::INFO, "notification", method = N::METHOD).entered();
+        let params = serde_json::from_value::<N::Params>(not.params)?;
+        f(self.global_state, params)?;
+        Ok(self)
+    }
+
+    pub(crate) fn finish(&mut self) {
+        if let Some(not) = &self.not {
+            if !not.method.starts_with("$/") {
+                tracing::error!("unhandled notification: {:?}", not);
+            }
+        }
+    }
+}
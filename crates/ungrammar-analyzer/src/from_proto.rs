@@ -0,0 +1,48 @@
+//! Conversion lsp_types types to the analyzer specific ones.
+
+use ide::{FilePosition, LineIndex, TextRange, TextSize, WideLineCol};
+use vfs::VfsPath;
+
+use crate::global_state::GlobalStateSnapshot;
+
+pub(crate) fn offset(
+    line_index: &LineIndex,
+    position: lsp_types::Position,
+) -> crate::Result<TextSize> {
+    let line_col = line_index.to_utf8(WideLineCol { line: position.line, col: position.character });
+    line_index.offset(line_col).ok_or_else(|| {
+        format!("Invalid offset {position:?} (line index length: {:?})", line_index.len()).into()
+    })
+}
+
+pub(crate) fn text_range(
+    line_index: &LineIndex,
+    range: lsp_types::Range,
+) -> crate::Result<TextRange> {
+    let start = offset(line_index, range.start)?;
+    let end = offset(line_index, range.end)?;
+    match end < start {
+        true => Err("Invalid Range".into()),
+        false => Ok(TextRange::new(start, end)),
+    }
+}
+
+pub(crate) fn file_position(
+    snap: &GlobalStateSnapshot,
+    tdpp: lsp_types::TextDocumentPositionParams,
+) -> crate::Result<FilePosition> {
+    let file_id = snap.url_to_file_id(&tdpp.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id);
+    let offset = offset(&line_index, tdpp.position)?;
+    Ok(FilePosition { file_id, offset })
+}
+
+/// Returns the VFS path of a document. Documents which are not files, like
+/// untitled editor buffers, get a virtual path.
+pub(crate) fn vfs_path(url: &lsp_types::Url) -> crate::Result<VfsPath> {
+    if url.scheme() != "file" {
+        return Ok(VfsPath::new_virtual_path(url.to_string()));
+    }
+    let path = url.to_file_path().map_err(|()| format!("invalid file url: {url}"))?;
+    Ok(VfsPath::from(path))
+}
@@ -0,0 +1,134 @@
+//! The context or environment in which the language server functions.
+//!
+//! Requests are answered from an immutable [`GlobalStateSnapshot`], while
+//! notifications update the [`GlobalState`] itself.
+
+use std::sync::{Arc, RwLock};
+
+use crossbeam_channel::Sender;
+use ide::{Analysis, AnalysisHost, Change, FileId, LineIndex};
+use lsp_types::Url;
+use rustc_hash::FxHashMap;
+use vfs::{ChangeKind, Vfs, VfsPath};
+
+use crate::{from_proto, to_proto, Config};
+
+/// `GlobalState` is the primary mutable state of the language server.
+pub(crate) struct GlobalState {
+    sender: Sender<lsp_server::Message>,
+    next_request_id: i32,
+    pub(crate) config: Arc<Config>,
+    pub(crate) analysis_host: AnalysisHost,
+    pub(crate) vfs: Arc<RwLock<Vfs>>,
+    /// Documents which are open in the editor, with their versions. The
+    /// editor owns their contents, so changes on disk are ignored.
+    pub(crate) mem_docs: FxHashMap<VfsPath, i32>,
+}
+
+/// An immutable snapshot of the world's state at a point in time.
+pub(crate) struct GlobalStateSnapshot {
+    pub(crate) config: Arc<Config>,
+    pub(crate) analysis: Analysis,
+    vfs: Arc<RwLock<Vfs>>,
+}
+
+impl GlobalState {
+    pub(crate) fn new(sender: Sender<lsp_server::Message>, config: Config) -> GlobalState {
+        let mut analysis_host = AnalysisHost::new();
+        let mut change = Change::new();
+        change.set_default_edition(config.edition());
+        change.set_line_endings(config.line_endings());
+        analysis_host.apply_change(change);
+        GlobalState {
+            sender,
+            next_request_id: 0,
+            config: Arc::new(config),
+            analysis_host,
+            vfs: Arc::new(RwLock::new(Vfs::default())),
+            mem_docs: FxHashMap::default(),
+        }
+    }
+
+    /// Applies the pending changes of the VFS to the analysis and returns the
+    /// ids of the changed files.
+    ///
+    /// Creating or deleting a file may resolve or break the includes of the
+    /// other files, so all of them are considered changed then.
+    pub(crate) fn process_changes(&mut self) -> Vec<FileId> {
+        let mut vfs = self.vfs.write().unwrap();
+        let changed_files = vfs.take_changes();
+        let mut change = Change::new();
+        let mut file_ids = Vec::with_capacity(changed_files.len());
+        let paths_changed = changed_files.iter().any(|it| it.change_kind != ChangeKind::Modify);
+        for file in changed_files {
+            let text = file.exists().then(|| {
+                Arc::from(String::from_utf8_lossy(vfs.file_contents(file.file_id)).as_ref())
+            });
+            change.change_file(file.file_id, text);
+            if !file_ids.contains(&file.file_id) {
+                file_ids.push(file.file_id);
+            }
+        }
+        if paths_changed {
+            change.set_file_set(vfs.file_set());
+            for (file_id, _) in vfs.iter() {
+                if !file_ids.contains(&file_id) {
+                    file_ids.push(file_id);
+                }
+            }
+        }
+        drop(vfs);
+        self.analysis_host.apply_change(change);
+        file_ids
+    }
+
+    pub(crate) fn snapshot(&self) -> GlobalStateSnapshot {
+        GlobalStateSnapshot {
+            config: Arc::clone(&self.config),
+            analysis: self.analysis_host.analysis(),
+            vfs: Arc::clone(&self.vfs),
+        }
+    }
+
+    pub(crate) fn send_request<R: lsp_types::request::Request>(&mut self, params: R::Params) {
+        self.next_request_id += 1;
+        let req = lsp_server::Request::new(
+            format!("ungrammar-analyzer/{}", self.next_request_id).into(),
+            R::METHOD.to_owned(),
+            params,
+        );
+        self.send(req.into());
+    }
+
+    pub(crate) fn send_notification<N: lsp_types::notification::Notification>(
+        &self,
+        params: N::Params,
+    ) {
+        let not = lsp_server::Notification::new(N::METHOD.to_owned(), params);
+        self.send(not.into());
+    }
+
+    pub(crate) fn respond(&self, response: lsp_server::Response) {
+        self.send(response.into());
+    }
+
+    fn send(&self, message: lsp_server::Message) {
+        self.sender.send(message).unwrap()
+    }
+}
+
+impl GlobalStateSnapshot {
+    pub(crate) fn url_to_file_id(&self, url: &Url) -> crate::Result<FileId> {
+        let path = from_proto::vfs_path(url)?;
+        let vfs = self.vfs.read().unwrap();
+        vfs.file_id(&path).ok_or_else(|| format!("file not found: {path}").into())
+    }
+
+    pub(crate) fn file_id_to_url(&self, id: FileId) -> Url {
+        to_proto::url_from_path(self.vfs.read().unwrap().file_path(id))
+    }
+
+    pub(crate) fn file_line_index(&self, file_id: FileId) -> Arc<LineIndex> {
+        self.analysis.file_line_index(file_id)
+    }
+}
@@ -0,0 +1,4 @@
+//! Handlers for the LSP requests and notifications we support.
+
+pub(crate) mod notification;
+pub(crate) mod request;
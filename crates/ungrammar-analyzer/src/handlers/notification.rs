@@ -0,0 +1,75 @@
+//! This module is responsible for implementing handlers for Language Server
+//! Protocol. This module specifically handles notifications.
+//!
+//! The handlers only update the VFS, the main loop then applies the changes
+//! to the analysis and publishes the diagnostics of the changed files.
+
+use lsp_types::{
+    DidChangeTextDocumentParams, DidChangeWatchedFilesParams, DidCloseTextDocumentParams,
+    DidOpenTextDocumentParams,
+};
+use vfs::{loader, VfsPath};
+
+use crate::{from_proto, global_state::GlobalState};
+
+pub(crate) fn handle_did_open_text_document(
+    state: &mut GlobalState,
+    params: DidOpenTextDocumentParams,
+) -> crate::Result<()> {
+    let path = from_proto::vfs_path(&params.text_document.uri)?;
+    state.mem_docs.insert(path.clone(), params.text_document.version);
+    state.vfs.write().unwrap().set_file_contents(path, Some(params.text_document.text.into()));
+    Ok(())
+}
+
+pub(crate) fn handle_did_change_text_document(
+    state: &mut GlobalState,
+    params: DidChangeTextDocumentParams,
+) -> crate::Result<()> {
+    let path = from_proto::vfs_path(&params.text_document.uri)?;
+    let Some(version) = state.mem_docs.get_mut(&path) else {
+        tracing::error!("unexpected DidChangeTextDocument: {path}");
+        return Ok(());
+    };
+    // We advertise full document sync, so the last change holds the whole
+    // text.
+    let Some(change) = params.content_changes.into_iter().last() else { return Ok(()) };
+    *version = params.text_document.version;
+    state.vfs.write().unwrap().set_file_contents(path, Some(change.text.into()));
+    Ok(())
+}
+
+pub(crate) fn handle_did_close_text_document(
+    state: &mut GlobalState,
+    params: DidCloseTextDocumentParams,
+) -> crate::Result<()> {
+    let path = from_proto::vfs_path(&params.text_document.uri)?;
+    if state.mem_docs.remove(&path).is_none() {
+        tracing::error!("orphan DidCloseTextDocument: {path}");
+        return Ok(());
+    }
+    // Fall back to the contents on disk for the files of the workspace.
+    let contents = disk_contents(state, &path);
+    state.vfs.write().unwrap().set_file_contents(path, contents);
+    Ok(())
+}
+
+pub(crate) fn handle_did_change_watched_files(
+    state: &mut GlobalState,
+    params: DidChangeWatchedFilesParams,
+) -> crate::Result<()> {
+    for change in params.changes {
+        let path = from_proto::vfs_path(&change.uri)?;
+        if state.mem_docs.contains_key(&path) {
+            continue;
+        }
+        let contents = disk_contents(state, &path);
+        state.vfs.write().unwrap().set_file_contents(path, contents);
+    }
+    Ok(())
+}
+
+fn disk_contents(state: &GlobalState, path: &VfsPath) -> Option<Vec<u8>> {
+    let path = path.as_path().filter(|it| state.config.is_in_workspace(it))?;
+    loader::load_file(path)
+}
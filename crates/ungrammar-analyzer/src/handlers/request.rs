@@ -0,0 +1,167 @@
+//! This module is responsible for implementing handlers for Language Server
+//! Protocol. This module specifically handles requests.
+
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    CodeActionOrCommand, CodeActionParams, CodeLens, CodeLensParams, DocumentHighlight,
+    DocumentHighlightParams, InlayHint, InlayHintParams, TextDocumentIdentifier,
+    TextDocumentPositionParams,
+};
+
+use crate::{from_proto, global_state::GlobalStateSnapshot, to_proto};
+
+pub(crate) fn handle_inlay_hints(
+    snap: GlobalStateSnapshot,
+    params: InlayHintParams,
+) -> crate::Result<Option<Vec<InlayHint>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_inlay_hints").entered();
+    let document_uri = &params.text_document.uri;
+    let file_id = snap.url_to_file_id(document_uri)?;
+    let line_index = snap.file_line_index(file_id);
+    let range = from_proto::text_range(&line_index, params.range)?;
+
+    let hints = snap.analysis.inlay_hints(&snap.config.inlay_hints(), file_id);
+    Ok(Some(
+        hints
+            .into_iter()
+            .filter(|hint| range.contains_range(hint.range))
+            .map(|hint| to_proto::inlay_hint(&line_index, hint))
+            .collect(),
+    ))
+}
+
+pub(crate) fn handle_document_highlight(
+    snap: GlobalStateSnapshot,
+    params: DocumentHighlightParams,
+) -> crate::Result<Option<Vec<DocumentHighlight>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_document_highlight").entered();
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+    let line_index = snap.file_line_index(position.file_id);
+
+    let highlights = snap.analysis.highlight_related(position);
+    Ok(Some(
+        highlights
+            .into_iter()
+            .map(|highlight| DocumentHighlight {
+                range: to_proto::range(&line_index, highlight.range),
+                kind: highlight.category.map(to_proto::document_highlight_kind),
+            })
+            .collect(),
+    ))
+}
+
+pub(crate) fn handle_code_lens(
+    snap: GlobalStateSnapshot,
+    params: CodeLensParams,
+) -> crate::Result<Option<Vec<CodeLens>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_code_lens").entered();
+    let file_id = snap.url_to_file_id(&params.text_document.uri)?;
+    let line_index = snap.file_line_index(file_id);
+
+    let annotations = snap.analysis.annotations(&snap.config.annotations(), file_id);
+    Ok(Some(
+        annotations
+            .into_iter()
+            .map(|annotation| {
+                to_proto::code_lens(&snap, &line_index, &params.text_document.uri, annotation)
+            })
+            .collect(),
+    ))
+}
+
+/// Returns the fixes of the diagnostics in the requested range.
+pub(crate) fn handle_code_action(
+    snap: GlobalStateSnapshot,
+    params: CodeActionParams,
+) -> crate::Result<Option<Vec<CodeActionOrCommand>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_code_action").entered();
+    let url = &params.text_document.uri;
+    let file_id = snap.url_to_file_id(url)?;
+    let line_index = snap.file_line_index(file_id);
+    let range = from_proto::text_range(&line_index, params.range)?;
+
+    let diagnostics = snap.analysis.diagnostics(&snap.config.diagnostics(), file_id);
+    let mut res = Vec::new();
+    for d in diagnostics.into_iter().filter(|d| d.range.intersect(range).is_some()) {
+        let fixes = d.fixes.clone();
+        let diagnostic = to_proto::diagnostic(&line_index, d);
+        res.extend(fixes.into_iter().map(|fix| {
+            let action = to_proto::code_action(&line_index, url, fix, diagnostic.clone());
+            CodeActionOrCommand::CodeAction(action)
+        }));
+    }
+    Ok(Some(res))
+}
+
+pub(crate) fn handle_call_hierarchy_prepare(
+    snap: GlobalStateSnapshot,
+    params: CallHierarchyPrepareParams,
+) -> crate::Result<Option<Vec<CallHierarchyItem>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_call_hierarchy_prepare").entered();
+    let position = from_proto::file_position(&snap, params.text_document_position_params)?;
+
+    let Some(targets) = snap.analysis.call_hierarchy(position) else { return Ok(None) };
+    Ok(Some(targets.into_iter().map(|it| to_proto::call_hierarchy_item(&snap, it)).collect()))
+}
+
+pub(crate) fn handle_call_hierarchy_incoming(
+    snap: GlobalStateSnapshot,
+    params: CallHierarchyIncomingCallsParams,
+) -> crate::Result<Option<Vec<CallHierarchyIncomingCall>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_call_hierarchy_incoming").entered();
+    let position = from_proto::file_position(&snap, item_position(params.item))?;
+
+    let Some(calls) = snap.analysis.incoming_calls(position) else { return Ok(None) };
+    Ok(Some(
+        calls
+            .into_iter()
+            .map(|call| {
+                // The ranges are in the rule of the caller, which may be in
+                // another file.
+                let line_index = snap.file_line_index(call.target.file_id);
+                CallHierarchyIncomingCall {
+                    from_ranges: call
+                        .ranges
+                        .iter()
+                        .map(|&it| to_proto::range(&line_index, it))
+                        .collect(),
+                    from: to_proto::call_hierarchy_item(&snap, call.target),
+                }
+            })
+            .collect(),
+    ))
+}
+
+pub(crate) fn handle_call_hierarchy_outgoing(
+    snap: GlobalStateSnapshot,
+    params: CallHierarchyOutgoingCallsParams,
+) -> crate::Result<Option<Vec<CallHierarchyOutgoingCall>>> {
+    let _p = tracing::span!(tracing::Level::INFO, "handle_call_hierarchy_outgoing").entered();
+    let position = from_proto::file_position(&snap, item_position(params.item))?;
+    let line_index = snap.file_line_index(position.file_id);
+
+    let Some(calls) = snap.analysis.outgoing_calls(position) else { return Ok(None) };
+    Ok(Some(
+        calls
+            .into_iter()
+            .map(|call| CallHierarchyOutgoingCall {
+                from_ranges: call
+                    .ranges
+                    .iter()
+                    .map(|&it| to_proto::range(&line_index, it))
+                    .collect(),
+                to: to_proto::call_hierarchy_item(&snap, call.target),
+            })
+            .collect(),
+    ))
+}
+
+/// Items don't carry any data, so the node is found again from the start of
+/// its name.
+fn item_position(item: CallHierarchyItem) -> TextDocumentPositionParams {
+    TextDocumentPositionParams {
+        text_document: TextDocumentIdentifier::new(item.uri),
+        position: item.selection_range.start,
+    }
+}
@@ -0,0 +1,27 @@
+//! Implementation of the LSP for the Ungrammar analyzer.
+//!
+//! This crate takes the analyzer's `ide` crate, which knows nothing about the
+//! LSP or the editor, and exposes it over the Language Server Protocol.
+//!
+//! The `main_loop` module reacts to the events coming from the client, the
+//! `global_state` module owns the state shared between them, and the
+//! `handlers` module implements the individual requests on top of a read-only
+//! snapshot of that state.
+//!
+//! The `cli` module runs the grammar tools of the `tools` crate on files.
+
+mod caps;
+mod config;
+mod dispatch;
+mod from_proto;
+mod global_state;
+mod handlers;
+mod main_loop;
+mod to_proto;
+
+pub mod cli;
+
+pub use crate::{caps::server_capabilities, config::Config, main_loop::main_loop};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+pub type Result<T, E = Error> = std::result::Result<T, E>;
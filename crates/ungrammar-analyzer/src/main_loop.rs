@@ -0,0 +1,160 @@
+//! The main loop of the language server, which reacts to the messages coming
+//! from the client.
+
+use lsp_server::{Connection, Message};
+use lsp_types::notification::{self, Notification};
+use vfs::{loader, VfsPath};
+
+use crate::{
+    config::Config,
+    dispatch::{NotificationDispatcher, RequestDispatcher},
+    global_state::GlobalState,
+    handlers,
+};
+
+pub fn main_loop(config: Config, connection: Connection) -> crate::Result<()> {
+    tracing::info!("initial config: {:#?}", config);
+    GlobalState::new(connection.sender.clone(), config).run(connection)
+}
+
+impl GlobalState {
+    fn run(mut self, connection: Connection) -> crate::Result<()> {
+        if self.config.did_change_watched_files_dynamic_registration() {
+            self.register_file_watchers();
+        }
+        self.load_workspace();
+
+        for msg in &connection.receiver {
+            match msg {
+                Message::Request(req) => {
+                    if connection.handle_shutdown(&req)? {
+                        return Ok(());
+                    }
+                    self.on_request(req);
+                }
+                Message::Notification(not) => {
+                    if not.method == notification::Exit::METHOD {
+                        return Ok(());
+                    }
+                    self.on_notification(not)?;
+                    self.process_changes_and_publish_diagnostics();
+                }
+                Message::Response(resp) => {
+                    tracing::debug!("unexpected response: {:?}", resp);
+                }
+            }
+        }
+        Err("client exited without proper shutdown sequence".into())
+    }
+
+    fn on_request(&mut self, req: lsp_server::Request) {
+        use lsp_types::request as lsp_request;
+
+        let mut dispatcher = RequestDispatcher { req: Some(req), global_state: self };
+        dispatcher
+            .on::<lsp_request::InlayHintRequest>(handlers::request::handle_inlay_hints)
+            .on::<lsp_request::DocumentHighlightRequest>(
+                handlers::request::handle_document_highlight,
+            )
+            .on::<lsp_request::CallHierarchyPrepare>(
+                handlers::request::handle_call_hierarchy_prepare,
+            )
+            .on::<lsp_request::CallHierarchyIncomingCalls>(
+                handlers::request::handle_call_hierarchy_incoming,
+            )
+            .on::<lsp_request::CallHierarchyOutgoingCalls>(
+                handlers::request::handle_call_hierarchy_outgoing,
+            )
+            .on::<lsp_request::CodeLensRequest>(handlers::request::handle_code_lens)
+            .on::<lsp_request::CodeActionRequest>(handlers::request::handle_code_action)
+            .finish();
+    }
+
+    fn on_notification(&mut self, not: lsp_server::Notification) -> crate::Result<()> {
+        use lsp_types::notification as notifs;
+
+        NotificationDispatcher { not: Some(not), global_state: self }
+            .on::<notifs::DidOpenTextDocument>(
+                handlers::notification::handle_did_open_text_document,
+            )?
+            .on::<notifs::DidChangeTextDocument>(
+                handlers::notification::handle_did_change_text_document,
+            )?
+            .on::<notifs::DidCloseTextDocument>(
+                handlers::notification::handle_did_close_text_document,
+            )?
+            .on::<notifs::DidChangeWatchedFiles>(
+                handlers::notification::handle_did_change_watched_files,
+            )?
+            .finish();
+        Ok(())
+    }
+
+    /// Reads the ungrammar files of all workspace roots into the VFS.
+    fn load_workspace(&mut self) {
+        let config = self.config.clone();
+        for root in config.workspace_roots() {
+            let files = loader::load_dir(root);
+            tracing::info!("loaded {} files from {}", files.len(), root.display());
+            let mut vfs = self.vfs.write().unwrap();
+            for (path, contents) in files {
+                let path = VfsPath::from(path);
+                if !self.mem_docs.contains_key(&path) {
+                    vfs.set_file_contents(path, Some(contents));
+                }
+            }
+        }
+        self.process_changes_and_publish_diagnostics();
+    }
+
+    /// Asks the client to tell us about the ungrammar files which change on
+    /// disk in the workspace.
+    fn register_file_watchers(&mut self) {
+        let watchers = self
+            .config
+            .workspace_roots()
+            .iter()
+            .map(|root| lsp_types::FileSystemWatcher {
+                glob_pattern: lsp_types::GlobPattern::String(format!(
+                    "{}/**/*.{}",
+                    root.display(),
+                    loader::EXTENSION
+                )),
+                kind: None,
+            })
+            .collect();
+        let registration_options = lsp_types::DidChangeWatchedFilesRegistrationOptions { watchers };
+        let registration = lsp_types::Registration {
+            id: "workspace/didChangeWatchedFiles".to_owned(),
+            method: "workspace/didChangeWatchedFiles".to_owned(),
+            register_options: Some(serde_json::to_value(registration_options).unwrap()),
+        };
+        self.send_request::<lsp_types::request::RegisterCapability>(
+            lsp_types::RegistrationParams { registrations: vec![registration] },
+        );
+    }
+
+    fn process_changes_and_publish_diagnostics(&mut self) {
+        for file_id in self.process_changes() {
+            self.publish_diagnostics(file_id);
+        }
+    }
+
+    /// Sends the diagnostics of `file_id` to the client.
+    fn publish_diagnostics(&mut self, file_id: ide::FileId) {
+        let snap = self.snapshot();
+        let line_index = snap.file_line_index(file_id);
+        let diagnostics = snap
+            .analysis
+            .diagnostics(&snap.config.diagnostics(), file_id)
+            .into_iter()
+            .map(|d| crate::to_proto::diagnostic(&line_index, d))
+            .collect();
+        let path = self.vfs.read().unwrap().file_path(file_id).clone();
+        let version = self.mem_docs.get(&path).copied();
+        let uri = snap.file_id_to_url(file_id);
+        self.send_notification::<notification::PublishDiagnostics>(
+            lsp_types::PublishDiagnosticsParams { uri, diagnostics, version },
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Conversion of the analyzer specific types to lsp_types equivalents.
+
+use ide::{
+    Annotation, AnnotationKind, Diagnostic, FileId, Fix, InlayHint, InlayHintPosition, InlayKind,
+    LineIndex, NavigationTarget, ReferenceCategory, Severity, TextEdit, TextRange, TextSize,
+};
+use vfs::VfsPath;
+
+use crate::global_state::GlobalStateSnapshot;
+
+/// Returns the url of a VFS path, the inverse of [`from_proto::vfs_path`].
+///
+/// [`from_proto::vfs_path`]: crate::from_proto::vfs_path
+pub(crate) fn url_from_path(path: &VfsPath) -> lsp_types::Url {
+    match path {
+        VfsPath::PathBuf(path) => lsp_types::Url::from_file_path(path).unwrap(),
+        VfsPath::Virtual(url) => lsp_types::Url::parse(url).unwrap(),
+    }
+}
+
+pub(crate) fn position(line_index: &LineIndex, offset: TextSize) -> lsp_types::Position {
+    let line_col = line_index.to_wide(line_index.line_col(offset));
+    lsp_types::Position::new(line_col.line, line_col.col)
+}
+
+pub(crate) fn range(line_index: &LineIndex, range: TextRange) -> lsp_types::Range {
+    let start = position(line_index, range.start());
+    let end = position(line_index, range.end());
+    lsp_types::Range::new(start, end)
+}
+
+pub(crate) fn document_highlight_kind(
+    category: ReferenceCategory,
+) -> lsp_types::DocumentHighlightKind {
+    match category {
+        ReferenceCategory::Write => lsp_types::DocumentHighlightKind::WRITE,
+        ReferenceCategory::Read => lsp_types::DocumentHighlightKind::READ,
+    }
+}
+
+pub(crate) fn diagnostic_severity(severity: Severity) -> lsp_types::DiagnosticSeverity {
+    match severity {
+        Severity::Error => lsp_types::DiagnosticSeverity::ERROR,
+        Severity::Warning => lsp_types::DiagnosticSeverity::WARNING,
+    }
+}
+
+pub(crate) fn diagnostic(line_index: &LineIndex, d: Diagnostic) -> lsp_types::Diagnostic {
+    lsp_types::Diagnostic {
+        range: range(line_index, d.range),
+        severity: Some(diagnostic_severity(d.severity)),
+        code: Some(lsp_types::NumberOrString::String(d.code.as_str().to_owned())),
+        code_description: None,
+        source: Some("ungrammar-analyzer".to_owned()),
+        message: d.message,
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+pub(crate) fn text_edit(line_index: &LineIndex, edit: TextEdit) -> lsp_types::TextEdit {
+    lsp_types::TextEdit::new(range(line_index, edit.range), edit.insert)
+}
+
+/// Converts the fix of `diagnostic` to a quick-fix editing the document `url`.
+pub(crate) fn code_action(
+    line_index: &LineIndex,
+    url: &lsp_types::Url,
+    fix: Fix,
+    diagnostic: lsp_types::Diagnostic,
+) -> lsp_types::CodeAction {
+    let edits = fix.edits.into_iter().map(|it| text_edit(line_index, it)).collect();
+    lsp_types::CodeAction {
+        title: fix.label,
+        kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic]),
+        edit: Some(lsp_types::WorkspaceEdit {
+            changes: Some([(url.clone(), edits)].into_iter().collect()),
+            ..Default::default()
+        }),
+        command: None,
+        is_preferred: Some(true),
+        disabled: None,
+        data: None,
+    }
+}
+
+pub(crate) fn inlay_hint(line_index: &LineIndex, inlay_hint: InlayHint) -> lsp_types::InlayHint {
+    let offset = match inlay_hint.position {
+        InlayHintPosition::Before => inlay_hint.range.start(),
+        InlayHintPosition::After => inlay_hint.range.end(),
+    };
+    let position = position(line_index, offset);
+    let (kind, text_edits) = match inlay_hint.kind {
+        // Accepting a field name hint turns it into a label.
+        InlayKind::FieldName => (
+            Some(lsp_types::InlayHintKind::PARAMETER),
+            Some(vec![lsp_types::TextEdit::new(
+                lsp_types::Range::new(position, position),
+                inlay_hint.label.clone(),
+            )]),
+        ),
+    };
+    lsp_types::InlayHint {
+        position,
+        label: lsp_types::InlayHintLabel::String(inlay_hint.label),
+        kind,
+        text_edits,
+        tooltip: None,
+        padding_left: None,
+        padding_right: None,
+        data: None,
+    }
+}
+
+pub(crate) fn code_lens(
+    snap: &GlobalStateSnapshot,
+    line_index: &LineIndex,
+    url: &lsp_types::Url,
+    annotation: Annotation,
+) -> lsp_types::CodeLens {
+    let range = range(line_index, annotation.range);
+    let command = match annotation.kind {
+        AnnotationKind::HasReferences { references } => {
+            let title = match references.len() {
+                1 => "1 reference".to_owned(),
+                n => format!("{n} references"),
+            };
+            let locations = references
+                .into_iter()
+                .map(|it| location(snap, it.file_id, it.range))
+                .collect::<Vec<_>>();
+            command::show_references(title, url, range.start, locations)
+        }
+    };
+    lsp_types::CodeLens { range, command: Some(command), data: None }
+}
+
+pub(crate) fn location(
+    snap: &GlobalStateSnapshot,
+    file_id: FileId,
+    range: TextRange,
+) -> lsp_types::Location {
+    let line_index = snap.file_line_index(file_id);
+    lsp_types::Location::new(snap.file_id_to_url(file_id), self::range(&line_index, range))
+}
+
+pub(crate) fn call_hierarchy_item(
+    snap: &GlobalStateSnapshot,
+    target: NavigationTarget,
+) -> lsp_types::CallHierarchyItem {
+    let line_index = snap.file_line_index(target.file_id);
+    lsp_types::CallHierarchyItem {
+        name: target.name.clone(),
+        kind: lsp_types::SymbolKind::STRUCT,
+        tags: None,
+        detail: None,
+        uri: snap.file_id_to_url(target.file_id),
+        range: range(&line_index, target.full_range),
+        selection_range: range(&line_index, target.focus_or_full_range()),
+        data: None,
+    }
+}
+
+pub(crate) mod command {
+    use serde_json::to_value;
+
+    /// Shows `locations` in a peek view at `position`. The VS Code extension
+    /// registers this command and forwards it to `editor.action.showReferences`.
+    pub(crate) fn show_references(
+        title: String,
+        url: &lsp_types::Url,
+        position: lsp_types::Position,
+        locations: Vec<lsp_types::Location>,
+    ) -> lsp_types::Command {
+        lsp_types::Command {
+            title,
+            command: "ungrammar-analyzer.showReferences".into(),
+            arguments: Some(vec![
+                to_value(url).unwrap(),
+                to_value(position).unwrap(),
+                to_value(locations).unwrap(),
+            ]),
+        }
+    }
+}
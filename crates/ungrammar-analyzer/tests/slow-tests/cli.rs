@@ -0,0 +1,70 @@
+//! Tests of the subcommands, which read the grammars from disk.
+
+use std::{fs, sync::Arc};
+
+use base_db::SourceDatabase;
+use hir::{Grammar, HirDatabase, RuleData};
+use ide::{FileId, FileSet, RootDatabase};
+use syntax::{LineEndings, ParseConfig};
+use ungrammar_analyzer::cli;
+use vfs::VfsPath;
+
+use crate::support::TestDir;
+
+#[test]
+fn cyclic_includes_through_parent_dirs() {
+    let dir = TestDir::new("cyclic_includes_through_parent_dirs");
+    dir.write("grammar.ungram", "include 'rules/../rules/expr.ungram'\nFile = Expr\n");
+    dir.write("rules/expr.ungram", "include '../rules/../grammar.ungram'\nExpr = 'int'\n");
+
+    let grammar =
+        cli::load_grammar(&dir.path().join("grammar.ungram"), ParseConfig::default()).unwrap();
+    let names = grammar.iter().map(|it| grammar[it].name.as_str()).collect::<Vec<_>>();
+    assert_eq!(names, ["File", "Expr"]);
+}
+
+#[test]
+fn crlf_line_endings() {
+    let dir = TestDir::new("cli_crlf_line_endings");
+    dir.write("grammar.ungram", "File = Expr\r\nExpr = 'int'\r\n");
+    let path = dir.path().join("grammar.ungram");
+
+    let err = cli::load_grammar(&path, ParseConfig::default()).unwrap_err();
+    assert!(err.to_string().ends_with("unexpected `\\r`, only Unix-style line endings allowed"));
+
+    let config = ParseConfig { line_endings: LineEndings::Crlf, ..Default::default() };
+    let grammar = cli::load_grammar(&path, config).unwrap();
+    assert_eq!(grammar.iter().count(), 2);
+}
+
+#[test]
+fn included_files_redefining_nodes() {
+    let dir = TestDir::new("cli_included_files_redefining_nodes");
+    dir.write("grammar.ungram", "include 'expr.ungram'\nFile = Expr Lit\nExpr = 'root'\n");
+    dir.write("expr.ungram", "Lit = Expr\nExpr = 'included'\n");
+
+    let grammar =
+        cli::load_grammar(&dir.path().join("grammar.ungram"), ParseConfig::default()).unwrap();
+    assert_eq!(token_of(&grammar, "Expr"), "root");
+
+    // The server sees the same definition from the root file.
+    let mut db = RootDatabase::default();
+    let mut file_set = FileSet::default();
+    for (idx, name) in ["grammar.ungram", "expr.ungram"].into_iter().enumerate() {
+        let path = dir.path().join(name);
+        db.set_file_text(FileId(idx as u32), Arc::from(fs::read_to_string(&path).unwrap()));
+        file_set.insert(FileId(idx as u32), VfsPath::from(path));
+    }
+    db.set_file_set(Arc::new(file_set));
+    let def_file = db.resolve_node(FileId(0), "Expr".to_owned()).unwrap();
+    assert_eq!(token_of(&db.grammar(def_file), "Expr"), "root");
+}
+
+/// Returns the token which is the whole rule of the node `name`.
+fn token_of(grammar: &Grammar, name: &str) -> String {
+    let rule = grammar[grammar.find_node(name).unwrap()].rule.unwrap();
+    match grammar[rule] {
+        RuleData::Token(token) => grammar[token].name.clone(),
+        ref it => panic!("not a token: {it:?}"),
+    }
+}
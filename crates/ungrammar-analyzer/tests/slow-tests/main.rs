@@ -0,0 +1,393 @@
+//! The most high-level integrated tests for the analyzer.
+//!
+//! This tests run a full LSP event loop, spawn a server in a separate thread
+//! and communicate with it through an in-memory connection.
+
+mod cli;
+mod support;
+
+use expect_test::expect;
+use lsp_types::{
+    notification::{DidChangeWatchedFiles, DidCloseTextDocument, PublishDiagnostics},
+    request::{
+        CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+        CodeActionRequest, CodeLensRequest, DocumentHighlightRequest, InlayHintRequest,
+    },
+    CallHierarchyIncomingCallsParams, CallHierarchyItem, CallHierarchyOutgoingCallsParams,
+    CallHierarchyPrepareParams, CodeActionContext, CodeActionParams, CodeLensParams,
+    DidChangeWatchedFilesParams, DidCloseTextDocumentParams, DocumentHighlightParams,
+    FileChangeType, FileEvent, InlayHintParams, Position, Range, TextDocumentPositionParams,
+};
+use serde_json::json;
+
+use crate::support::{Server, TestDir};
+
+#[test]
+fn inlay_hints() {
+    let server = Server::new(json!({}));
+    server.open("grammar.ungram", "BinExpr = Expr '+' Expr\n");
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<InlayHintRequest>(InlayHintParams {
+        work_done_progress_params: Default::default(),
+        text_document: server.doc_id("grammar.ungram"),
+        range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+    });
+    let labels = res
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|hint| format!("{} {}", hint["position"]["character"], hint["label"]))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "10 \"expr:\"",
+            "15 \"plus_token:\"",
+            "19 \"expr:\"",
+        ]
+    "#]]
+    .assert_debug_eq(&labels);
+}
+
+#[test]
+fn inlay_hints_can_be_disabled() {
+    let server = Server::new(json!({ "inlayHints": { "fieldNames": { "enable": false } } }));
+    server.open("grammar.ungram", "A = B\n");
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<InlayHintRequest>(InlayHintParams {
+        work_done_progress_params: Default::default(),
+        text_document: server.doc_id("grammar.ungram"),
+        range: Range::new(Position::new(0, 0), Position::new(1, 0)),
+    });
+    assert_eq!(res, json!([]));
+}
+
+#[test]
+fn publishes_diagnostics() {
+    let server = Server::new(json!({}));
+    server.open("grammar.ungram", "BinExpr = Expr '+' Expr\nA = \n");
+    let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+    let diagnostics = diagnostics
+        .diagnostics
+        .iter()
+        .map(|d| format!("{}:{} {}", d.range.start.line, d.range.start.character, d.message))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "2:0 expected rule",
+            "0:10 field `expr` of `BinExpr` is inferred 2 times, add labels to tell them apart",
+            "0:19 field `expr` of `BinExpr` is inferred 2 times, add labels to tell them apart",
+        ]
+    "#]]
+    .assert_debug_eq(&diagnostics);
+}
+
+#[test]
+fn edition_config() {
+    let server = Server::new(json!({ "edition": "1" }));
+    server.open("grammar.ungram", "include 'grammar.ungram'\n");
+    let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+    let messages = diagnostics.diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<_>>();
+    assert_eq!(messages, ["`include` items require edition 2 or later"]);
+
+    // The marker of a file overrides the config.
+    server.open("marked.ungram", "// edition: 2\ninclude 'grammar.ungram'\n");
+    loop {
+        let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+        if diagnostics.uri.path().ends_with("marked.ungram") {
+            assert_eq!(diagnostics.diagnostics, []);
+            break;
+        }
+    }
+}
+
+#[test]
+fn crlf_line_endings() {
+    let server = Server::new(json!({}));
+    server.open("grammar.ungram", "A = 'a'\r\nB = 'b'\r\n");
+    let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+    let codes = diagnostics.diagnostics.iter().map(|d| d.code.clone().unwrap()).collect::<Vec<_>>();
+    assert_eq!(codes, [lsp_types::NumberOrString::String("invalid-line-endings".to_owned())]);
+
+    let res = server.send_request::<CodeActionRequest>(CodeActionParams {
+        text_document: server.doc_id("grammar.ungram"),
+        range: Range::new(Position::new(0, 0), Position::new(0, 7)),
+        context: CodeActionContext::default(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let url = server.url("grammar.ungram");
+    let edits = res[0]["edit"]["changes"][url.as_str()]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|it| {
+            format!(
+                "{}:{} {:?}",
+                it["range"]["start"]["line"], it["range"]["start"]["character"], it["newText"]
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(res[0]["title"], "Convert line endings to `\\n`");
+    assert_eq!(edits, [r#"0:7 String("")"#, r#"1:7 String("")"#]);
+
+    let server = Server::new(json!({ "allowCrlf": true }));
+    server.open("grammar.ungram", "A = 'a'\r\nB = 'b'\r\n");
+    let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+    assert_eq!(diagnostics.diagnostics, []);
+}
+
+#[test]
+fn code_lens() {
+    let server = Server::new(json!({}));
+    server.open(
+        "grammar.ungram",
+        "Expr = Literal | BinExpr\nBinExpr = lhs:Expr '+' rhs:Expr\nLiteral = 'int'\n",
+    );
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<CodeLensRequest>(CodeLensParams {
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        text_document: server.doc_id("grammar.ungram"),
+    });
+    let lenses = res
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|lens| {
+            let locations = lens["command"]["arguments"][2]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|it| {
+                    format!(
+                        "{}:{}",
+                        it["range"]["start"]["line"], it["range"]["start"]["character"]
+                    )
+                })
+                .collect::<Vec<_>>();
+            format!(
+                "{} {} {:?}",
+                lens["range"]["start"]["line"], lens["command"]["title"], locations
+            )
+        })
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "0 \"2 references\" [\"1:14\", \"1:27\"]",
+            "1 \"1 reference\" [\"0:17\"]",
+            "2 \"1 reference\" [\"0:7\"]",
+        ]
+    "#]]
+    .assert_debug_eq(&lenses);
+}
+
+#[test]
+fn document_highlight() {
+    let server = Server::new(json!({}));
+    server.open("grammar.ungram", "Expr = Literal | BinExpr\nBinExpr = lhs:Expr '+' rhs:Expr\n");
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<DocumentHighlightRequest>(DocumentHighlightParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: server.doc_id("grammar.ungram"),
+            position: Position::new(1, 15),
+        },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let highlights = res
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|it| {
+            let start = &it["range"]["start"];
+            format!("{}:{} {}", start["line"], start["character"], it["kind"])
+        })
+        .collect::<Vec<_>>();
+    expect![[r#"
+        [
+            "0:0 3",
+            "1:14 2",
+            "1:27 2",
+        ]
+    "#]]
+    .assert_debug_eq(&highlights);
+}
+
+#[test]
+fn call_hierarchy() {
+    let server = Server::new(json!({}));
+    server.open(
+        "grammar.ungram",
+        "Expr = Literal | BinExpr\nBinExpr = lhs:Expr '+' rhs:Expr\nLiteral = 'int'\n",
+    );
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<CallHierarchyPrepare>(CallHierarchyPrepareParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: server.doc_id("grammar.ungram"),
+            position: Position::new(0, 1),
+        },
+        work_done_progress_params: Default::default(),
+    });
+    let item: CallHierarchyItem = serde_json::from_value(res[0].clone()).unwrap();
+    assert_eq!(item.name, "Expr");
+
+    let res = server.send_request::<CallHierarchyIncomingCalls>(CallHierarchyIncomingCallsParams {
+        item: item.clone(),
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let incoming = res
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|it| format!("{} {}", it["from"]["name"], it["fromRanges"].as_array().unwrap().len()))
+        .collect::<Vec<_>>();
+
+    let res = server.send_request::<CallHierarchyOutgoingCalls>(CallHierarchyOutgoingCallsParams {
+        item,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    let outgoing = res
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|it| format!("{} {}", it["to"]["name"], it["fromRanges"].as_array().unwrap().len()))
+        .collect::<Vec<_>>();
+    expect![[r#"
+        (
+            [
+                "\"BinExpr\" 2",
+            ],
+            [
+                "\"Literal\" 1",
+                "\"BinExpr\" 1",
+            ],
+        )
+    "#]]
+    .assert_debug_eq(&(incoming, outgoing));
+}
+
+#[test]
+fn call_hierarchy_across_includes() {
+    let dir = TestDir::new("call_hierarchy_across_includes");
+    dir.write("grammar.ungram", "include 'tokens.ungram'\nFile = Literal*\n");
+    dir.write("tokens.ungram", "Literal = 'int'\n");
+    let server = Server::with_workspace(json!({}), &dir);
+    server.wait_for_notification::<PublishDiagnostics>();
+    server.wait_for_notification::<PublishDiagnostics>();
+
+    let res = server.send_request::<CallHierarchyPrepare>(CallHierarchyPrepareParams {
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: server.doc_id("tokens.ungram"),
+            position: Position::new(0, 1),
+        },
+        work_done_progress_params: Default::default(),
+    });
+    let item: CallHierarchyItem = serde_json::from_value(res[0].clone()).unwrap();
+
+    // The ranges are in the including file, not in the file of `Literal`.
+    let res = server.send_request::<CallHierarchyIncomingCalls>(CallHierarchyIncomingCallsParams {
+        item,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    });
+    assert_eq!(res[0]["from"]["name"], "File");
+    assert_eq!(res[0]["from"]["uri"], server.url("grammar.ungram").to_string());
+    assert_eq!(
+        res[0]["fromRanges"],
+        json!([{
+            "start": { "line": 1, "character": 7 },
+            "end": { "line": 1, "character": 14 },
+        }])
+    );
+}
+
+#[test]
+fn workspace_files() {
+    let dir = TestDir::new("workspace_files");
+    dir.write("a.ungram", "A = B\n");
+    dir.write("nested/b.ungram", "B = \n");
+    dir.write("notes.txt", "C = \n");
+    let server = Server::with_workspace(json!({}), &dir);
+
+    let mut summary = Vec::new();
+    let mut record = |params: lsp_types::PublishDiagnosticsParams| {
+        let path = params.uri.path().rsplit('/').next().unwrap().to_owned();
+        let messages = params.diagnostics.iter().map(|it| it.message.clone()).collect::<Vec<_>>();
+        summary.push(format!("{path} {:?} {messages:?}", params.version));
+    };
+
+    // The files of the workspace are loaded on startup.
+    record(server.wait_for_notification::<PublishDiagnostics>());
+    record(server.wait_for_notification::<PublishDiagnostics>());
+
+    // Open documents take precedence over the contents on disk...
+    server.open("nested/b.ungram", "B = 'b'\n");
+    record(server.wait_for_notification::<PublishDiagnostics>());
+    dir.write("nested/b.ungram", "B = (\n");
+    server.notification::<DidChangeWatchedFiles>(DidChangeWatchedFilesParams {
+        changes: vec![FileEvent::new(server.url("nested/b.ungram"), FileChangeType::CHANGED)],
+    });
+
+    // ...until they are closed.
+    server.notification::<DidCloseTextDocument>(DidCloseTextDocumentParams {
+        text_document: server.doc_id("nested/b.ungram"),
+    });
+    record(server.wait_for_notification::<PublishDiagnostics>());
+
+    dir.remove("a.ungram");
+    server.notification::<DidChangeWatchedFiles>(DidChangeWatchedFilesParams {
+        changes: vec![FileEvent::new(server.url("a.ungram"), FileChangeType::DELETED)],
+    });
+    record(server.wait_for_notification::<PublishDiagnostics>());
+
+    expect![[r#"
+        [
+            "a.ungram None []",
+            "b.ungram None [\"expected rule\"]",
+            "b.ungram Some(0) []",
+            "b.ungram None [\"expected rule\", \"expected R_PAREN\"]",
+            "a.ungram None []",
+        ]
+    "#]]
+    .assert_debug_eq(&summary);
+}
+
+#[test]
+fn includes() {
+    let dir = TestDir::new("includes");
+    dir.write("grammar.ungram", "include 'tokens.ungram'\nFile = Literal*\n");
+    let server = Server::with_workspace(json!({}), &dir);
+    let diagnostics = server.wait_for_notification::<PublishDiagnostics>();
+    assert_eq!(diagnostics.diagnostics[0].message, "unresolved include `tokens.ungram`");
+
+    // Creating the included file resolves the include.
+    dir.write("tokens.ungram", "Literal = 'int'\n");
+    server.notification::<DidChangeWatchedFiles>(DidChangeWatchedFilesParams {
+        changes: vec![FileEvent::new(server.url("tokens.ungram"), FileChangeType::CREATED)],
+    });
+    let mut published = Vec::new();
+    for _ in 0..2 {
+        let params = server.wait_for_notification::<PublishDiagnostics>();
+        let path = params.uri.path().rsplit('/').next().unwrap().to_owned();
+        published.push((path, params.diagnostics.len()));
+    }
+    published.sort();
+    assert_eq!(published, [("grammar.ungram".to_owned(), 0), ("tokens.ungram".to_owned(), 0)]);
+
+    let res = server.send_request::<CodeLensRequest>(CodeLensParams {
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+        text_document: server.doc_id("tokens.ungram"),
+    });
+    let location = &res[0]["command"]["arguments"][2][0];
+    assert_eq!(res[0]["command"]["title"], "1 reference");
+    assert_eq!(location["uri"], server.url("grammar.ungram").to_string());
+    assert_eq!(location["range"]["start"], json!({ "line": 1, "character": 7 }));
+}
@@ -0,0 +1,171 @@
+use std::{
+    cell::Cell,
+    env, fs,
+    path::{Path, PathBuf},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crossbeam_channel::RecvTimeoutError;
+use lsp_server::{Connection, Message, Notification, Request};
+use lsp_types::{
+    notification::{DidOpenTextDocument, Exit},
+    request::Shutdown,
+    ClientCapabilities, DidOpenTextDocumentParams, TextDocumentItem, Url,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use ungrammar_analyzer::{main_loop, Config};
+
+pub(crate) struct Server {
+    req_id: Cell<i32>,
+    root: PathBuf,
+    client: Connection,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    pub(crate) fn new(config: Value) -> Server {
+        Server::start(config, PathBuf::from("/tmp"), Vec::new())
+    }
+
+    /// Starts a server for the workspace in `dir`.
+    pub(crate) fn with_workspace(config: Value, dir: &TestDir) -> Server {
+        let root = dir.path().to_path_buf();
+        Server::start(config, root.clone(), vec![root])
+    }
+
+    fn start(config: Value, root: PathBuf, workspace_roots: Vec<PathBuf>) -> Server {
+        let (connection, client) = Connection::memory();
+        let mut server_config = Config::new(ClientCapabilities::default(), workspace_roots);
+        server_config.update(config).unwrap();
+
+        let thread = thread::Builder::new()
+            .name("test server".to_owned())
+            .spawn(move || main_loop(server_config, connection).unwrap())
+            .unwrap();
+
+        Server { req_id: Cell::new(1), root, client, thread: Some(thread) }
+    }
+
+    pub(crate) fn url(&self, rel_path: &str) -> Url {
+        Url::from_file_path(self.root.join(rel_path)).unwrap()
+    }
+
+    pub(crate) fn doc_id(&self, rel_path: &str) -> lsp_types::TextDocumentIdentifier {
+        lsp_types::TextDocumentIdentifier { uri: self.url(rel_path) }
+    }
+
+    pub(crate) fn open(&self, rel_path: &str, text: &str) {
+        self.notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: self.url(rel_path),
+                language_id: "ungrammar".to_owned(),
+                version: 0,
+                text: text.to_owned(),
+            },
+        })
+    }
+
+    pub(crate) fn notification<N>(&self, params: N::Params)
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: Serialize,
+    {
+        let r = Notification::new(N::METHOD.to_owned(), params);
+        self.client.sender.send(Message::Notification(r)).unwrap();
+    }
+
+    pub(crate) fn send_request<R>(&self, params: R::Params) -> Value
+    where
+        R: lsp_types::request::Request,
+        R::Params: Serialize,
+    {
+        let id = self.req_id.get();
+        self.req_id.set(id.wrapping_add(1));
+
+        let r = Request::new(id.into(), R::METHOD.to_owned(), params);
+        self.client.sender.send(Message::Request(r)).unwrap();
+        loop {
+            match self.recv() {
+                Message::Response(res) if res.id == id.into() => {
+                    if let Some(err) = res.error {
+                        panic!("error response: {err:#?}");
+                    }
+                    return res.result.unwrap();
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    pub(crate) fn wait_for_notification<N>(&self) -> N::Params
+    where
+        N: lsp_types::notification::Notification,
+        N::Params: DeserializeOwned,
+    {
+        loop {
+            match self.recv() {
+                Message::Notification(n) if n.method == N::METHOD => {
+                    return serde_json::from_value(n.params).unwrap();
+                }
+                _ => continue,
+            }
+        }
+    }
+
+    fn recv(&self) -> Message {
+        match self.client.receiver.recv_timeout(Duration::from_secs(60)) {
+            Ok(msg) => msg,
+            Err(RecvTimeoutError::Timeout) => panic!("timed out waiting for the server"),
+            Err(RecvTimeoutError::Disconnected) => panic!("the server exited"),
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return;
+        }
+        self.send_request::<Shutdown>(());
+        self.notification::<Exit>(());
+        self.thread.take().unwrap().join().unwrap();
+    }
+}
+
+/// A temporary directory on disk, removed on drop.
+pub(crate) struct TestDir {
+    path: PathBuf,
+}
+
+impl TestDir {
+    pub(crate) fn new(name: &str) -> TestDir {
+        let path = env::temp_dir()
+            .join("ungrammar-analyzer-tests")
+            .join(format!("{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+        TestDir { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn write(&self, rel_path: &str, text: &str) {
+        let path = self.path.join(rel_path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    pub(crate) fn remove(&self, rel_path: &str) {
+        fs::remove_file(self.path.join(rel_path)).unwrap();
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
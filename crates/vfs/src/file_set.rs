@@ -0,0 +1,58 @@
+//! See [`FileSet`].
+
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use crate::{FileId, VfsPath};
+
+/// A set of files, which can be looked up by path and resolve paths relative
+/// to each other.
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct FileSet {
+    files: FxHashMap<VfsPath, FileId>,
+    paths: FxHashMap<FileId, VfsPath>,
+}
+
+impl FileSet {
+    /// Returns the number of files in the set.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns `true` if the set contains no files.
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Returns the file at `path`, which is relative to the directory of
+    /// `anchor`.
+    pub fn resolve_path(&self, anchor: FileId, path: &str) -> Option<FileId> {
+        let path = self.paths.get(&anchor)?.join(path)?;
+        self.file_for_path(&path)
+    }
+
+    pub fn file_for_path(&self, path: &VfsPath) -> Option<FileId> {
+        self.files.get(path).copied()
+    }
+
+    pub fn path_for_file(&self, file_id: FileId) -> Option<&VfsPath> {
+        self.paths.get(&file_id)
+    }
+
+    pub fn insert(&mut self, file_id: FileId, path: VfsPath) {
+        self.files.insert(path.clone(), file_id);
+        self.paths.insert(file_id, path);
+    }
+
+    /// Returns an iterator over the files of the set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = FileId> + '_ {
+        self.paths.keys().copied()
+    }
+}
+
+impl fmt::Debug for FileSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileSet").field("n_files", &self.files.len()).finish()
+    }
+}
@@ -0,0 +1,154 @@
+//! # Virtual File System
+//!
+//! VFS stores all the files known to ungrammar-analyzer: the `.ungram` files
+//! of the workspace read from disk, and the documents open in the editor.
+//! Reading a file from the VFS always returns the same contents, unless the
+//! VFS was explicitly modified with [`Vfs::set_file_contents`]. All changes to
+//! the VFS are logged and can be retrieved via [`Vfs::take_changes`].
+//!
+//! Files are identified by a [`FileId`], which is stable for the lifetime of
+//! the VFS: a deleted and then re-created file gets the same id back.
+//!
+//! The VFS itself never touches the disk, so tests can use it as a plain
+//! in-memory map. Reading the files from disk is the job of the [`loader`].
+
+mod file_set;
+pub mod loader;
+mod path_interner;
+mod vfs_path;
+
+#[cfg(test)]
+mod tests;
+
+use std::mem;
+
+use crate::path_interner::PathInterner;
+
+pub use crate::{file_set::FileSet, vfs_path::VfsPath};
+
+/// Handle to a file in [`Vfs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FileId(pub u32);
+
+/// Storage for all the files read by ungrammar-analyzer.
+#[derive(Default)]
+pub struct Vfs {
+    interner: PathInterner,
+    data: Vec<Option<Vec<u8>>>,
+    changes: Vec<ChangedFile>,
+}
+
+/// A modification to a file in the [`Vfs`], created by a call to
+/// [`Vfs::set_file_contents`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangedFile {
+    pub file_id: FileId,
+    pub change_kind: ChangeKind,
+}
+
+impl ChangedFile {
+    /// Returns `true` if the change is not [`Delete`](ChangeKind::Delete).
+    pub fn exists(&self) -> bool {
+        self.change_kind != ChangeKind::Delete
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Create,
+    Modify,
+    Delete,
+}
+
+impl Vfs {
+    /// Returns the id of the file at `path`, if it exists.
+    pub fn file_id(&self, path: &VfsPath) -> Option<FileId> {
+        self.interner.get(path).filter(|&it| self.get(it).is_some())
+    }
+
+    /// Returns the path of the file with `file_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id is not present in the VFS.
+    pub fn file_path(&self, file_id: FileId) -> &VfsPath {
+        self.interner.lookup(file_id)
+    }
+
+    /// Returns the contents of the file with `file_id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id is not present or the file was deleted.
+    pub fn file_contents(&self, file_id: FileId) -> &[u8] {
+        self.get(file_id).as_deref().unwrap()
+    }
+
+    /// Returns `true` if the file with `file_id` exists.
+    pub fn exists(&self, file_id: FileId) -> bool {
+        self.get(file_id).is_some()
+    }
+
+    /// Returns an iterator over the ids and paths of all the existing files.
+    pub fn iter(&self) -> impl Iterator<Item = (FileId, &VfsPath)> + '_ {
+        (0..self.data.len())
+            .map(|it| FileId(it as u32))
+            .filter(move |&file_id| self.exists(file_id))
+            .map(move |file_id| (file_id, self.file_path(file_id)))
+    }
+
+    /// Returns a [`FileSet`] of all the existing files.
+    pub fn file_set(&self) -> FileSet {
+        let mut res = FileSet::default();
+        for (file_id, path) in self.iter() {
+            res.insert(file_id, path.clone());
+        }
+        res
+    }
+
+    /// Updates the contents of the file at `path`, or deletes it if
+    /// `contents` is `None`.
+    ///
+    /// Returns `true` if the file was modified, and records the change.
+    pub fn set_file_contents(&mut self, path: VfsPath, contents: Option<Vec<u8>>) -> bool {
+        let file_id = self.alloc_file_id(path);
+        let change_kind = match (self.get(file_id), &contents) {
+            (None, None) => return false,
+            (Some(old), Some(new)) if old == new => return false,
+            (None, Some(_)) => ChangeKind::Create,
+            (Some(_), None) => ChangeKind::Delete,
+            (Some(_), Some(_)) => ChangeKind::Modify,
+        };
+        self.data[file_id.0 as usize] = contents;
+        self.changes.push(ChangedFile { file_id, change_kind });
+        true
+    }
+
+    /// Returns `true` if the VFS contains changes not yet taken.
+    pub fn has_changes(&self) -> bool {
+        !self.changes.is_empty()
+    }
+
+    /// Drains and returns the changes recorded since the last call.
+    pub fn take_changes(&mut self) -> Vec<ChangedFile> {
+        mem::take(&mut self.changes)
+    }
+
+    fn alloc_file_id(&mut self, path: VfsPath) -> FileId {
+        let file_id = self.interner.intern(path);
+        let idx = file_id.0 as usize;
+        let len = self.data.len().max(idx + 1);
+        self.data.resize(len, None);
+        file_id
+    }
+
+    fn get(&self, file_id: FileId) -> &Option<Vec<u8>> {
+        self.data.get(file_id.0 as usize).unwrap_or(&None)
+    }
+}
+
+impl std::fmt::Debug for Vfs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Vfs").field("n_files", &self.data.len()).finish()
+    }
+}
@@ -0,0 +1,56 @@
+//! Reads the `.ungram` files of a workspace from disk.
+//!
+//! The loader is synchronous: the language server calls it once for every
+//! workspace root at startup, and then for the files the client reports as
+//! changed on disk.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The extension of the files the loader picks up.
+pub const EXTENSION: &str = "ungram";
+
+/// Recursively collects the `.ungram` files under `root`, skipping hidden
+/// directories and `target` directories.
+pub fn load_dir(root: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut res = Vec::new();
+    let mut work = vec![root.to_path_buf()];
+    while let Some(dir) = work.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(it) => it,
+            Err(err) => {
+                tracing::warn!("failed to read {}: {err}", dir.display());
+                continue;
+            }
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let Ok(file_type) = entry.file_type() else { continue };
+            if file_type.is_dir() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if !name.starts_with('.') && name != "target" {
+                    work.push(path);
+                }
+            } else if is_ungrammar_file(&path) {
+                if let Some(contents) = load_file(&path) {
+                    res.push((path, contents));
+                }
+            }
+        }
+    }
+    res.sort_by(|(a, _), (b, _)| a.cmp(b));
+    res
+}
+
+/// Reads the file at `path`, or returns `None` if it doesn't exist.
+pub fn load_file(path: &Path) -> Option<Vec<u8>> {
+    fs::read(path).ok()
+}
+
+/// Returns `true` if `path` has the [`EXTENSION`] of ungrammar files.
+pub fn is_ungrammar_file(path: &Path) -> bool {
+    path.extension().is_some_and(|it| it == EXTENSION)
+}
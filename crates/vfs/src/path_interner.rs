@@ -0,0 +1,44 @@
+//! Maps paths to compact integer ids. We don't care about clearing paths
+//! which no longer exist -- the assumption is total size of paths we ever look
+//! at is not too big.
+
+use rustc_hash::FxHashMap;
+
+use crate::{FileId, VfsPath};
+
+/// Structure to map between [`VfsPath`] and [`FileId`].
+#[derive(Default)]
+pub(crate) struct PathInterner {
+    map: FxHashMap<VfsPath, FileId>,
+    paths: Vec<VfsPath>,
+}
+
+impl PathInterner {
+    /// Gets the id corresponding to `path`.
+    ///
+    /// If `path` does not exists in `self`, returns [`None`].
+    pub(crate) fn get(&self, path: &VfsPath) -> Option<FileId> {
+        self.map.get(path).copied()
+    }
+
+    /// Gets the id corresponding to `path`, interning it if it's not there
+    /// yet.
+    pub(crate) fn intern(&mut self, path: VfsPath) -> FileId {
+        if let Some(&id) = self.map.get(&path) {
+            return id;
+        }
+        let id = FileId(self.paths.len() as u32);
+        self.paths.push(path.clone());
+        self.map.insert(path, id);
+        id
+    }
+
+    /// Returns the path corresponding to `id`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` does not exists in `self`.
+    pub(crate) fn lookup(&self, id: FileId) -> &VfsPath {
+        &self.paths[id.0 as usize]
+    }
+}
@@ -0,0 +1,103 @@
+use std::{env, fs, process};
+
+use crate::{loader, ChangeKind, ChangedFile, FileId, Vfs, VfsPath};
+
+fn path(path: &str) -> VfsPath {
+    VfsPath::new_virtual_path(path.to_owned())
+}
+
+#[test]
+fn records_changes() {
+    let mut vfs = Vfs::default();
+    assert!(vfs.set_file_contents(path("/a.ungram"), Some(b"A = 'a'".to_vec())));
+    assert!(vfs.set_file_contents(path("/b.ungram"), Some(b"B = 'b'".to_vec())));
+    assert!(!vfs.set_file_contents(path("/b.ungram"), Some(b"B = 'b'".to_vec())));
+    assert!(vfs.set_file_contents(path("/a.ungram"), Some(b"A = B".to_vec())));
+    assert!(vfs.set_file_contents(path("/b.ungram"), None));
+    assert!(!vfs.set_file_contents(path("/c.ungram"), None));
+
+    let (a, b) = (FileId(0), FileId(1));
+    assert_eq!(
+        vfs.take_changes(),
+        [
+            ChangedFile { file_id: a, change_kind: ChangeKind::Create },
+            ChangedFile { file_id: b, change_kind: ChangeKind::Create },
+            ChangedFile { file_id: a, change_kind: ChangeKind::Modify },
+            ChangedFile { file_id: b, change_kind: ChangeKind::Delete },
+        ]
+    );
+    assert!(!vfs.has_changes());
+
+    assert_eq!(vfs.file_contents(a), b"A = B");
+    assert_eq!(vfs.file_id(&path("/a.ungram")), Some(a));
+    assert_eq!(vfs.file_id(&path("/b.ungram")), None);
+    assert_eq!(vfs.iter().map(|(id, _)| id).collect::<Vec<_>>(), [a]);
+
+    // A re-created file keeps its id.
+    vfs.set_file_contents(path("/b.ungram"), Some(Vec::new()));
+    assert_eq!(vfs.file_id(&path("/b.ungram")), Some(b));
+}
+
+#[test]
+fn file_names() {
+    assert_eq!(path("/src/grammar.ungram").file_name(), Some("grammar.ungram"));
+    assert_eq!(path("/src/").file_name(), None);
+    let path = VfsPath::from(env::temp_dir().join("grammar.ungram"));
+    assert_eq!(path.file_name(), Some("grammar.ungram"));
+}
+
+#[test]
+fn joins_paths() {
+    let base = path("/src/grammar.ungram");
+    assert_eq!(base.join("tokens.ungram"), Some(path("/src/tokens.ungram")));
+    assert_eq!(base.join("./lib/../tokens.ungram"), Some(path("/src/tokens.ungram")));
+    assert_eq!(base.join("../common.ungram"), Some(path("/common.ungram")));
+    assert_eq!(base.join("/common.ungram"), Some(path("/common.ungram")));
+    assert_eq!(base.join("../../common.ungram"), None);
+
+    let dir = env::temp_dir();
+    let base = VfsPath::from(dir.join("src/grammar.ungram"));
+    assert_eq!(base.join("../tokens.ungram"), Some(VfsPath::from(dir.join("tokens.ungram"))));
+}
+
+#[test]
+fn resolves_paths_in_file_set() {
+    let mut vfs = Vfs::default();
+    vfs.set_file_contents(path("/src/grammar.ungram"), Some(Vec::new()));
+    vfs.set_file_contents(path("/src/tokens.ungram"), Some(Vec::new()));
+    vfs.set_file_contents(path("/src/deleted.ungram"), Some(Vec::new()));
+    vfs.set_file_contents(path("/src/deleted.ungram"), None);
+
+    let file_set = vfs.file_set();
+    let (grammar, tokens) = (FileId(0), FileId(1));
+    assert_eq!(file_set.len(), 2);
+    assert_eq!(file_set.resolve_path(grammar, "tokens.ungram"), Some(tokens));
+    assert_eq!(file_set.resolve_path(tokens, "./grammar.ungram"), Some(grammar));
+    assert_eq!(file_set.resolve_path(grammar, "deleted.ungram"), None);
+    assert_eq!(file_set.path_for_file(tokens), Some(&path("/src/tokens.ungram")));
+}
+
+#[test]
+fn loads_ungrammar_files() {
+    let root = env::temp_dir().join(format!("vfs-loader-{}", process::id()));
+    let _ = fs::remove_dir_all(&root);
+    for dir in ["nested", ".hidden", "target"] {
+        fs::create_dir_all(root.join(dir)).unwrap();
+    }
+    fs::write(root.join("a.ungram"), "A = 'a'").unwrap();
+    fs::write(root.join("nested/b.ungram"), "B = 'b'").unwrap();
+    fs::write(root.join("nested/notes.txt"), "").unwrap();
+    fs::write(root.join(".hidden/c.ungram"), "").unwrap();
+    fs::write(root.join("target/d.ungram"), "").unwrap();
+
+    let files = loader::load_dir(&root);
+    let names = files
+        .iter()
+        .map(|(path, _)| path.strip_prefix(&root).unwrap().to_string_lossy().replace('\\', "/"))
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["a.ungram", "nested/b.ungram"]);
+    assert_eq!(files[0].1, b"A = 'a'");
+    assert_eq!(loader::load_file(&root.join("missing.ungram")), None);
+
+    fs::remove_dir_all(&root).unwrap();
+}
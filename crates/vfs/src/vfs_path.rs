@@ -0,0 +1,111 @@
+//! Abstract-ish representation of paths for VFS.
+
+use std::{
+    fmt,
+    path::{Component, Path, PathBuf},
+};
+
+/// Path in [`Vfs`](crate::Vfs).
+///
+/// Long-term, we want to support files which do not reside in the file
+/// system, like untitled editor buffers, so we treat paths as opaque strings
+/// unless they come from the disk.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum VfsPath {
+    /// An absolute path on the file system.
+    PathBuf(PathBuf),
+    /// Path that doesn't exist on the file system, e.g. an editor buffer or
+    /// a file in a test fixture.
+    Virtual(String),
+}
+
+impl VfsPath {
+    /// Creates a virtual path from a `/`-separated string.
+    pub fn new_virtual_path(path: String) -> VfsPath {
+        VfsPath::Virtual(path)
+    }
+
+    /// Returns the file system path, if this is not a virtual path.
+    pub fn as_path(&self) -> Option<&Path> {
+        match self {
+            VfsPath::PathBuf(it) => Some(it),
+            VfsPath::Virtual(_) => None,
+        }
+    }
+
+    /// Returns the file name of the path, e.g. `grammar.ungram`.
+    pub fn file_name(&self) -> Option<&str> {
+        match self {
+            VfsPath::PathBuf(it) => it.file_name()?.to_str(),
+            VfsPath::Virtual(it) => it.rsplit('/').next().filter(|it| !it.is_empty()),
+        }
+    }
+
+    /// Resolves `path` relative to the directory containing this file.
+    ///
+    /// `.` and `..` are resolved lexically, without touching the disk.
+    /// Returns `None` if `path` goes above the root.
+    pub fn join(&self, path: &str) -> Option<VfsPath> {
+        match self {
+            VfsPath::PathBuf(it) => {
+                let mut res = PathBuf::new();
+                for component in it.parent()?.join(path).components() {
+                    match component {
+                        Component::CurDir => (),
+                        Component::ParentDir => {
+                            if !res.pop() {
+                                return None;
+                            }
+                        }
+                        _ => res.push(component),
+                    }
+                }
+                Some(VfsPath::PathBuf(res))
+            }
+            VfsPath::Virtual(it) => {
+                let mut res = match path.starts_with('/') {
+                    true => String::new(),
+                    false => it[..it.rfind('/')?].to_owned(),
+                };
+                for segment in path.split('/') {
+                    match segment {
+                        "" | "." => (),
+                        ".." => {
+                            let idx = res.rfind('/')?;
+                            res.truncate(idx);
+                        }
+                        _ => {
+                            res.push('/');
+                            res.push_str(segment);
+                        }
+                    }
+                }
+                Some(VfsPath::Virtual(res))
+            }
+        }
+    }
+}
+
+impl From<PathBuf> for VfsPath {
+    fn from(path: PathBuf) -> VfsPath {
+        VfsPath::PathBuf(path)
+    }
+}
+
+impl fmt::Display for VfsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VfsPath::PathBuf(it) => it.display().fmt(f),
+            VfsPath::Virtual(it) => it.fmt(f),
+        }
+    }
+}
+
+impl fmt::Debug for VfsPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VfsPath::PathBuf(it) => it.fmt(f),
+            VfsPath::Virtual(it) => it.fmt(f),
+        }
+    }
+}
@@ -6,4 +6,41 @@ export function activate(context: vscode.ExtensionContext) {
   });
 
   context.subscriptions.push(disposable);
+  context.subscriptions.push(
+    vscode.commands.registerCommand("ungrammar-analyzer.showReferences", showReferences),
+  );
+}
+
+interface LspPosition {
+  line: number;
+  character: number;
+}
+
+interface LspLocation {
+  uri: string;
+  range: { start: LspPosition; end: LspPosition };
+}
+
+/**
+ * Shows the locations of a reference count code lens in a peek view. The
+ * server sends them as LSP values, which `editor.action.showReferences`
+ * doesn't accept as is.
+ */
+async function showReferences(uri: string, position: LspPosition, locations: LspLocation[]) {
+  await vscode.commands.executeCommand(
+    "editor.action.showReferences",
+    vscode.Uri.parse(uri),
+    toPosition(position),
+    locations.map(
+      (it) =>
+        new vscode.Location(
+          vscode.Uri.parse(it.uri),
+          new vscode.Range(toPosition(it.range.start), toPosition(it.range.end)),
+        ),
+    ),
+  );
+}
+
+function toPosition(position: LspPosition): vscode.Position {
+  return new vscode.Position(position.line, position.character);
 }
@@ -1,38 +1,45 @@
 //! Defines input for code generation process.
 
-pub(crate) struct KindsSrc<'a> {
-    pub(crate) punct: &'a [(&'a str, &'a str)],
-    pub(crate) tokens: &'a [&'a str],
-    pub(crate) nodes: &'a [&'a str],
-}
+use tools::codegen::KindsSrc;
 
-pub(crate) const KINDS_SRC: KindsSrc<'_> = KindsSrc {
-    punct: &[
-        ("=", "EQ"),
-        ("*", "STAR"),
-        ("|", "PIPE"),
-        ("?", "QUESTION"),
-        (":", "COLON"),
-        ("(", "L_PAREN"),
-        (")", "R_PAREN"),
-    ],
-    tokens: &["ERROR", "IDENT", "STRING", "WHITESPACE", "COMMENT"],
-    nodes: &[
-        "GRAMMAR",
-        "NODE",
-        // atoms
-        "LABEL",
-        "PAREN_RULE",
-        // unary
-        "NAME",
-        "NAME_REF",
-        "TOKEN",
-        "SEQ_RULE",
-        "LABELED_RULE",
-        "ALT_RULE",
-        "OPT_RULE",
-        "REP_RULE",
-        // combined
-        "END_OF_NODE",
-    ],
-};
+pub(crate) fn kinds_src() -> KindsSrc {
+    let strings = |it: &[&str]| it.iter().map(|it| it.to_string()).collect();
+    KindsSrc {
+        punct: [
+            ("=", "EQ"),
+            ("*", "STAR"),
+            ("|", "PIPE"),
+            ("?", "QUESTION"),
+            (":", "COLON"),
+            ("(", "L_PAREN"),
+            (")", "R_PAREN"),
+        ]
+        .iter()
+        .map(|(text, name)| (text.to_string(), name.to_string()))
+        .collect(),
+        keywords: Vec::new(),
+        // Identifiers which are keywords only in some positions, so they can
+        // still be used as node names.
+        contextual_keywords: strings(&["include"]),
+        tokens: strings(&["ERROR", "IDENT", "STRING", "WHITESPACE", "COMMENT", "DOC_COMMENT"]),
+        nodes: strings(&[
+            "GRAMMAR",
+            "NODE",
+            "INCLUDE",
+            // atoms
+            "LABEL",
+            "PAREN_RULE",
+            // unary
+            "NAME",
+            "NAME_REF",
+            "TOKEN",
+            "SEQ_RULE",
+            "LABELED_RULE",
+            "ALT_RULE",
+            "OPT_RULE",
+            "REP_RULE",
+            // combined
+            "END_OF_NODE",
+        ]),
+    }
+}